@@ -0,0 +1,16 @@
+#![no_main]
+
+use german_kr_lib::chm::ChmArchive;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(chm) = ChmArchive::open(data.to_vec()) else {
+        return;
+    };
+    for path in ["/#SYSTEM", "/#TOPICS", "/$FIftiMain", "/index.htm"] {
+        let _ = chm.find_entry(path);
+    }
+    if let Ok(entries) = chm.entries() {
+        let _ = entries.len();
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use german_kr_lib::chm::{ChmArchive, ChmSystem};
+use libfuzzer_sys::fuzz_target;
+
+/// Upper bound per read so decompression bombs do not hit the RSS limit.
+const MAX_READ: u64 = 1 << 20;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(chm) = ChmArchive::open(data.to_vec()) else {
+        return;
+    };
+    let _ = ChmSystem::read(&chm);
+    if let Ok(entries) = chm.entries() {
+        for entry in entries.iter().take(256) {
+            let _ = chm.read_object_range(&entry.path, 0, MAX_READ);
+            let _ = chm.read_object_range(&entry.path, entry.length / 2, 64);
+        }
+    }
+});
@@ -0,0 +1,26 @@
+#![no_main]
+
+use german_kr_lib::chm::lzx::{decompress_block, LzxState, LZX_FRAME_LEN};
+use libfuzzer_sys::fuzz_target;
+
+// Input: window-bits selector, then frames of `u16 input len, u16 output len, bytes`,
+// decoded in order with one state like the blocks of a reset interval.
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, mut rest)) = data.split_first() else {
+        return;
+    };
+    let Ok(mut state) = LzxState::new(15 + selector % 7) else {
+        return;
+    };
+    while rest.len() >= 4 {
+        let input_len = usize::from(u16::from_le_bytes([rest[0], rest[1]]));
+        let out_len = usize::from(u16::from_le_bytes([rest[2], rest[3]])) % (LZX_FRAME_LEN + 1);
+        rest = &rest[4..];
+        let (input, tail) = rest.split_at(input_len.min(rest.len()));
+        rest = tail;
+        let mut padded = input.to_vec();
+        padded.extend_from_slice(&[0, 0]);
+        // Keep going after errors: later frames then see partially updated state.
+        let _ = decompress_block(&mut state, &padded, out_len);
+    }
+});
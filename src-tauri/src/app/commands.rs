@@ -7,15 +7,24 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::app::model::{
-    BuildStatus, ContentItem, ContentPage, DictionaryIndexEntry, EntryDetail,
-    LinkTarget, SearchHit,
+    BuildStatus, CacheGcReport, CacheStatsReport, CompoundSplit, ContentItem, ContentPage, DictionaryIndexEntry,
+    DictionaryInfo, EntryDetail, HeadwordLookupMode, HeadwordSuggestion, LinkTarget, RuntimeCacheReport, SearchHit,
 };
+use crate::error::DokhanError;
 use crate::runtime::link_media::{resolve_link_target_impl, resolve_media_data_url_impl};
-use crate::runtime::search::{get_index_entries_impl, search_entries_impl};
+use crate::runtime::registry::{
+    delete_dictionary_impl, gc_runtime_cache_impl, list_dictionaries_impl, rename_dictionary_impl,
+    set_dictionary_enabled_impl, set_dictionary_pinned_impl,
+};
+use crate::runtime::search::{
+    get_index_entries_impl, lookup_headwords_impl, search_entries_impl, search_reverse_impl, split_compound_impl,
+    suggest_headwords_impl,
+};
 use crate::runtime::state::{
-    get_content_page_impl, get_entry_detail_impl, get_master_build_status_impl,
-    get_master_contents_impl, start_master_build_impl,
+    cancel_master_build_impl, get_content_page_impl, get_entry_detail_impl,
+    get_master_build_status_impl, get_master_contents_impl, start_master_build_impl, verify_runtime_cache_impl,
 };
+use crate::runtime::zip::get_cache_stats_impl;
 #[cfg(target_os = "android")]
 use std::io;
 #[cfg(target_os = "android")]
@@ -29,10 +38,10 @@ use tauri_plugin_fs::FsExt;
 ///
 /// Returns an error when source URI cannot be opened or temp file write fails.
 #[tauri::command]
-fn prepare_zip_source(path: String, app: tauri::AppHandle) -> Result<String, String> {
+fn prepare_zip_source(path: String, app: tauri::AppHandle) -> Result<String, DokhanError> {
     let raw = path.trim();
     if raw.is_empty() {
-        return Err("zip path is empty".to_string());
+        return Err(DokhanError::InvalidInput("zip path is empty".to_string()));
     }
 
     if raw.starts_with("content://") {
@@ -40,41 +49,41 @@ fn prepare_zip_source(path: String, app: tauri::AppHandle) -> Result<String, Str
         {
             let mut dir = std::env::temp_dir();
             dir.push("dokhan-zips");
-            fs::create_dir_all(&dir).map_err(|e| format!("failed to create temp zip dir: {e}"))?;
+            fs::create_dir_all(&dir).map_err(|e| DokhanError::io("failed to create temp zip dir", e))?;
 
             let stamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
-                .map_err(|e| format!("failed to read system time: {e}"))?
+                .map_err(|e| DokhanError::Internal(format!("failed to read system time: {e}")))?
                 .as_millis();
             let file_name = format!("picked-{stamp}-{}.zip", std::process::id());
             let mut out = PathBuf::from(&dir);
             out.push(file_name);
 
-            let uri = url::Url::parse(raw).map_err(|e| format!("invalid content uri: {e}"))?;
+            let uri = url::Url::parse(raw).map_err(|e| DokhanError::InvalidInput(format!("invalid content uri: {e}")))?;
             let mut open_opts = tauri_plugin_fs::OpenOptions::new();
             open_opts.read(true);
             let mut src = app
                 .fs()
                 .open(tauri_plugin_fs::FilePath::Url(uri), open_opts)
-                .map_err(|e| format!("failed to open content uri: {e}"))?;
+                .map_err(|e| DokhanError::SourceUnavailable(format!("failed to open content uri: {e}")))?;
             let mut dst =
-                fs::File::create(&out).map_err(|e| format!("failed to create temp zip: {e}"))?;
+                fs::File::create(&out).map_err(|e| DokhanError::io("failed to create temp zip", e))?;
             io::copy(&mut src, &mut dst)
-                .map_err(|e| format!("failed to copy content uri to temp zip: {e}"))?;
+                .map_err(|e| DokhanError::io("failed to copy content uri to temp zip", e))?;
             return Ok(out.to_string_lossy().to_string());
         }
         #[cfg(not(target_os = "android"))]
         {
             let _ = app;
-            return Err("content uri is only supported on Android".to_string());
+            return Err(DokhanError::Unsupported("content uri is only supported on Android".to_string()));
         }
     }
 
     if raw.starts_with("file://") {
-        let url = url::Url::parse(raw).map_err(|e| format!("invalid file url: {e}"))?;
+        let url = url::Url::parse(raw).map_err(|e| DokhanError::InvalidInput(format!("invalid file url: {e}")))?;
         let path = url
             .to_file_path()
-            .map_err(|_| "file url is not a valid path".to_string())?;
+            .map_err(|_| DokhanError::InvalidInput("file url is not a valid path".to_string()))?;
         return Ok(path.to_string_lossy().to_string());
     }
 
@@ -83,18 +92,21 @@ fn prepare_zip_source(path: String, app: tauri::AppHandle) -> Result<String, Str
 
 /// Start asynchronous runtime build for a ZIP dataset.
 ///
-/// If a build is already running for the same ZIP source, the existing polling key is returned.
+/// If a build is already running for the same ZIP source, the existing key is returned.
+/// Progress is pushed as `build://progress`, `build://done`, and `build://error` events tagged with that key.
 ///
 /// # Errors
 ///
 /// Returns an error when `zip_path` is missing/invalid or status storage cannot be updated.
 #[tauri::command]
-fn start_master_build(zip_path: Option<String>, app: tauri::AppHandle) -> Result<String, String> {
+fn start_master_build(zip_path: Option<String>, app: tauri::AppHandle) -> Result<String, DokhanError> {
     start_master_build_impl(&app, zip_path)
 }
 
 /// Get current asynchronous build status for the given ZIP source.
 ///
+/// Snapshot fallback for windows that subscribed after build events were emitted.
+///
 /// # Errors
 ///
 /// Returns an error when `zip_path` is missing/invalid or status storage is unavailable.
@@ -102,10 +114,37 @@ fn start_master_build(zip_path: Option<String>, app: tauri::AppHandle) -> Result
 fn get_master_build_status(
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<BuildStatus, String> {
+) -> Result<BuildStatus, DokhanError> {
     get_master_build_status_impl(&app, zip_path)
 }
 
+/// Cancel the running build for the given ZIP source.
+///
+/// Returns `true` when a running build was signalled; it finishes with the `cancelled` phase.
+///
+/// # Errors
+///
+/// Returns an error when `zip_path` is missing/invalid or status storage is unavailable.
+#[tauri::command]
+fn cancel_master_build(zip_path: Option<String>, app: tauri::AppHandle) -> Result<bool, DokhanError> {
+    cancel_master_build_impl(&app, zip_path)
+}
+
+/// Verify runtime cache checksums, recorded parser/schema versions, and the search index.
+///
+/// Reports corruption or outdated formats without discarding or rebuilding anything.
+///
+/// # Errors
+///
+/// Returns an error when `zip_path` is missing/invalid or the cache directory cannot be resolved.
+#[tauri::command]
+fn verify_runtime_cache(
+    zip_path: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<RuntimeCacheReport, DokhanError> {
+    verify_runtime_cache_impl(&app, zip_path)
+}
+
 /// Return parsed content tree entries.
 ///
 /// # Errors
@@ -115,12 +154,78 @@ fn get_master_build_status(
 fn get_master_contents(
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<Vec<ContentItem>, String> {
+) -> Result<Vec<ContentItem>, DokhanError> {
     get_master_contents_impl(&app, zip_path)
 }
 
+/// List managed dictionaries with name, size, fingerprint, last-used time, and build state.
+///
+/// # Errors
+///
+/// Returns an error when the managed ZIP directory or registry file cannot be accessed.
+#[tauri::command]
+fn list_dictionaries(app: tauri::AppHandle) -> Result<Vec<DictionaryInfo>, DokhanError> {
+    list_dictionaries_impl(&app)
+}
+
+/// Include or exclude a dictionary from federated search and index queries.
+///
+/// # Errors
+///
+/// Returns an error when the dictionary id is unknown or the registry cannot be saved.
+#[tauri::command]
+fn set_dictionary_enabled(
+    id: String,
+    enabled: bool,
+    app: tauri::AppHandle,
+) -> Result<DictionaryInfo, DokhanError> {
+    set_dictionary_enabled_impl(&app, &id, enabled)
+}
+
+/// Give a dictionary a display name.
+///
+/// # Errors
+///
+/// Returns an error when the name is blank, the id is unknown, or the registry cannot be saved.
+#[tauri::command]
+fn rename_dictionary(id: String, name: String, app: tauri::AppHandle) -> Result<DictionaryInfo, DokhanError> {
+    rename_dictionary_impl(&app, &id, &name)
+}
+
+/// Pin or unpin a dictionary; pinned dictionaries are listed first and cannot be deleted.
+///
+/// # Errors
+///
+/// Returns an error when the dictionary id is unknown or the registry cannot be saved.
+#[tauri::command]
+fn set_dictionary_pinned(id: String, pinned: bool, app: tauri::AppHandle) -> Result<DictionaryInfo, DokhanError> {
+    set_dictionary_pinned_impl(&app, &id, pinned)
+}
+
+/// Delete a managed dictionary with its runtime cache and search index.
+///
+/// # Errors
+///
+/// Returns an error when the dictionary is unknown, pinned, still building, or cannot be removed.
+#[tauri::command]
+fn delete_dictionary(id: String, app: tauri::AppHandle) -> Result<CacheGcReport, DokhanError> {
+    delete_dictionary_impl(&app, &id)
+}
+
+/// Remove runtime-cache and search index directories whose managed ZIP is gone.
+///
+/// # Errors
+///
+/// Returns an error when the cache directories cannot be read or cleaned.
+#[tauri::command]
+fn gc_runtime_cache(app: tauri::AppHandle) -> Result<CacheGcReport, DokhanError> {
+    gc_runtime_cache_impl(&app)
+}
+
 /// Return index rows with optional prefix filtering.
 ///
+/// Without `zip_path`, rows from every enabled dictionary are returned grouped per dictionary.
+///
 /// # Errors
 ///
 /// Returns an error when runtime source resolution or runtime loading fails.
@@ -130,12 +235,67 @@ fn get_index_entries(
     limit: Option<usize>,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<Vec<DictionaryIndexEntry>, String> {
+) -> Result<Vec<DictionaryIndexEntry>, DokhanError> {
     get_index_entries_impl(&app, prefix, limit, zip_path)
 }
 
+/// Look up headwords by prefix, edit distance, or regular expression over the headword FST.
+///
+/// `mode` defaults to `prefix`; `max_distance` only applies to `fuzzy`.
+///
+/// # Errors
+///
+/// Returns an error for an invalid pattern or distance, or when runtime loading fails.
+#[tauri::command]
+fn lookup_headwords(
+    query: String,
+    mode: Option<HeadwordLookupMode>,
+    max_distance: Option<u32>,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Vec<DictionaryIndexEntry>, DokhanError> {
+    lookup_headwords_impl(&app, &query, mode.unwrap_or_default(), max_distance, limit, zip_path)
+}
+
+/// Suggest the closest headwords for a misspelled query.
+///
+/// Without `zip_path`, suggestions from every enabled dictionary are merged by distance.
+///
+/// # Errors
+///
+/// Returns an error when runtime source resolution or runtime loading fails.
+#[tauri::command]
+fn suggest_headwords(
+    query: String,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Vec<HeadwordSuggestion>, DokhanError> {
+    suggest_headwords_impl(&app, &query, limit, zip_path)
+}
+
+/// Split a compound query into headword parts linked to their entries.
+///
+/// Meant for queries without a direct hit; without `zip_path`, each enabled dictionary
+/// contributes its own split.
+///
+/// # Errors
+///
+/// Returns an error when runtime source resolution or runtime loading fails.
+#[tauri::command]
+fn split_compound(
+    query: String,
+    zip_path: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Vec<CompoundSplit>, DokhanError> {
+    split_compound_impl(&app, &query, zip_path)
+}
+
 /// Run full-text search against in-memory runtime index.
 ///
+/// Without `zip_path`, every enabled dictionary is searched and hits are grouped per dictionary.
+///
 /// # Errors
 ///
 /// Returns an error when runtime source resolution or runtime loading fails.
@@ -145,10 +305,27 @@ fn search_entries(
     limit: Option<usize>,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<Vec<SearchHit>, String> {
+) -> Result<Vec<SearchHit>, DokhanError> {
     search_entries_impl(&app, &query, limit, zip_path)
 }
 
+/// Find German headwords by Korean meaning, ranked by gloss exactness and position.
+///
+/// Without `zip_path`, every enabled dictionary is searched and hits are grouped per dictionary.
+///
+/// # Errors
+///
+/// Returns an error when runtime source resolution, runtime loading, or the search index fails.
+#[tauri::command]
+fn search_reverse(
+    query: String,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    search_reverse_impl(&app, &query, limit, zip_path)
+}
+
 /// Load a dictionary entry detail by stable runtime id.
 ///
 /// # Errors
@@ -159,7 +336,7 @@ fn get_entry_detail(
     id: usize,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<EntryDetail, String> {
+) -> Result<EntryDetail, DokhanError> {
     get_entry_detail_impl(&app, id, zip_path)
 }
 
@@ -174,7 +351,7 @@ fn get_content_page(
     source_path: Option<String>,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<ContentPage, String> {
+) -> Result<ContentPage, DokhanError> {
     get_content_page_impl(&app, &local, source_path.as_deref(), zip_path)
 }
 
@@ -190,7 +367,7 @@ fn resolve_link_target(
     current_local: Option<String>,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<LinkTarget, String> {
+) -> Result<LinkTarget, DokhanError> {
     resolve_link_target_impl(
         &app,
         &href,
@@ -212,7 +389,7 @@ fn resolve_media_data_url(
     current_local: Option<String>,
     zip_path: Option<String>,
     app: tauri::AppHandle,
-) -> Result<String, String> {
+) -> Result<String, DokhanError> {
     resolve_media_data_url_impl(
         &app,
         &href,
@@ -222,6 +399,16 @@ fn resolve_media_data_url(
     )
 }
 
+/// Report hit/miss/eviction statistics of the CHM block and archive caches.
+///
+/// # Errors
+///
+/// Returns an error when a cache lock is poisoned.
+#[tauri::command]
+fn get_cache_stats() -> Result<CacheStatsReport, DokhanError> {
+    get_cache_stats_impl()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 /// Start the Tauri application and register all frontend-invokable commands.
 pub fn run() {
@@ -235,13 +422,26 @@ pub fn run() {
             prepare_zip_source,
             start_master_build,
             get_master_build_status,
+            cancel_master_build,
+            verify_runtime_cache,
             get_master_contents,
+            list_dictionaries,
+            set_dictionary_enabled,
+            rename_dictionary,
+            set_dictionary_pinned,
+            delete_dictionary,
+            gc_runtime_cache,
             get_index_entries,
+            lookup_headwords,
+            suggest_headwords,
+            split_compound,
             search_entries,
+            search_reverse,
             get_entry_detail,
             get_content_page,
             resolve_link_target,
-            resolve_media_data_url
+            resolve_media_data_url,
+            get_cache_stats
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
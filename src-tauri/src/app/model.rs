@@ -1,7 +1,12 @@
 //! Serializable API models and runtime data structures shared across modules.
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::error::ErrorPayload;
+use crate::runtime::entries::EntryTable;
+use crate::runtime::headwords::HeadwordIndex;
+use crate::runtime::pages::PageStore;
 
 #[cfg(test)]
 #[derive(Debug, Serialize)]
@@ -66,6 +71,34 @@ pub(crate) struct DictionaryIndexEntry {
     pub(crate) headword_highlights: Vec<TextSpan>,
     pub(crate) aliases: Vec<String>,
     pub(crate) source_path: String,
+    /// Registry id of the dictionary this row belongs to.
+    pub(crate) dictionary_id: String,
+    pub(crate) dictionary_name: String,
+}
+
+/// Automaton used by `lookup_headwords`.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum HeadwordLookupMode {
+    /// Normalized headword or alias starts with the query.
+    #[default]
+    Prefix,
+    /// Normalized headword or alias is within a small edit distance of the query.
+    Fuzzy,
+    /// Normalized headword or alias fully matches the query as a regular expression.
+    Regex,
+}
+
+/// "Did you mean" candidate for a query without hits.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HeadwordSuggestion {
+    pub(crate) id: usize,
+    pub(crate) headword: String,
+    /// Weighted edit distance in edits; umlaut and `ß` spelling variants count as a fraction of one.
+    pub(crate) distance: f32,
+    pub(crate) dictionary_id: String,
+    pub(crate) dictionary_name: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -76,6 +109,104 @@ pub(crate) struct SearchHit {
     pub(crate) source_path: String,
     pub(crate) score: usize,
     pub(crate) snippet: String,
+    /// Registry id of the dictionary this hit belongs to.
+    pub(crate) dictionary_id: String,
+    pub(crate) dictionary_name: String,
+    /// `"lemma"` for the base form of an inflected query, `"gloss"` for a Korean → German match.
+    pub(crate) matched_via: Option<&'static str>,
+}
+
+/// One part of a split compound, linked to the entry it resolved to.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CompoundPart {
+    /// Part as spelled in the query.
+    pub(crate) text: String,
+    /// Linking element (`s`, `es`, `n`, `en`) between this part and the next.
+    pub(crate) linking: Option<String>,
+    pub(crate) headword: String,
+    pub(crate) link: LinkTarget,
+}
+
+/// Compound split of a query against one dictionary's headwords.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CompoundSplit {
+    pub(crate) dictionary_id: String,
+    pub(crate) dictionary_name: String,
+    pub(crate) parts: Vec<CompoundPart>,
+}
+
+/// Managed dictionary as listed by the registry.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DictionaryInfo {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) zip_path: String,
+    pub(crate) enabled: bool,
+    /// Pinned dictionaries are listed first and cannot be deleted.
+    pub(crate) pinned: bool,
+    /// Registration time in seconds since the Unix epoch.
+    pub(crate) added_at: u64,
+    /// Last build/open time in seconds since the Unix epoch (0 when never opened).
+    pub(crate) last_used_at: u64,
+    pub(crate) size_bytes: u64,
+    pub(crate) fingerprint: String,
+    pub(crate) build_state: DictionaryBuildState,
+}
+
+/// Coarse build state of a managed dictionary for library listings.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DictionaryBuildState {
+    /// A build worker is running.
+    Building,
+    /// The runtime is loaded in memory.
+    Ready,
+    /// The last build was cancelled.
+    Cancelled,
+    /// The last build failed.
+    Error,
+    /// A runtime cache is on disk but not loaded.
+    Cached,
+    /// Never built.
+    Idle,
+}
+
+/// Result of a runtime cache garbage-collection pass.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CacheGcReport {
+    pub(crate) removed: Vec<String>,
+    pub(crate) freed_bytes: u64,
+}
+
+/// Checksum result for one runtime cache data file.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CacheFileCheck {
+    pub(crate) name: String,
+    pub(crate) expected_bytes: u64,
+    /// `None` when the file is missing.
+    pub(crate) actual_bytes: Option<u64>,
+    pub(crate) ok: bool,
+}
+
+/// Integrity and version report for one dictionary's runtime cache.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuntimeCacheReport {
+    pub(crate) cache_key: String,
+    /// `ok`, `missing`, `outdated`, or `corrupt`.
+    pub(crate) status: &'static str,
+    pub(crate) version: Option<u32>,
+    pub(crate) parser_version: Option<u32>,
+    pub(crate) search_schema_version: Option<u32>,
+    /// `ok`, `missing`, or `corrupt`.
+    pub(crate) search_index: &'static str,
+    pub(crate) files: Vec<CacheFileCheck>,
+    pub(crate) problems: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -88,9 +219,10 @@ pub(crate) struct EntryDetail {
     pub(crate) target_local: String,
     pub(crate) definition_text: String,
     pub(crate) definition_html: String,
+    pub(crate) encoding: String,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ContentPage {
     pub(crate) local: String,
@@ -98,6 +230,7 @@ pub(crate) struct ContentPage {
     pub(crate) title: String,
     pub(crate) body_text: String,
     pub(crate) body_html: String,
+    pub(crate) encoding: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -138,16 +271,68 @@ pub(crate) struct BuildStatus {
     pub(crate) message: String,
     pub(crate) done: bool,
     pub(crate) success: bool,
-    pub(crate) error: Option<String>,
+    pub(crate) error: Option<ErrorPayload>,
     pub(crate) summary: Option<MasterFeatureSummary>,
+    /// Sequence number of the last build event mirrored into this snapshot (0 when idle).
+    pub(crate) seq: u64,
+}
+
+/// Payload of `build://progress`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BuildProgressEvent {
+    pub(crate) key: String,
+    pub(crate) seq: u64,
+    pub(crate) progress: BuildProgress,
+}
+
+/// Payload of `build://done`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BuildDoneEvent {
+    pub(crate) key: String,
+    pub(crate) seq: u64,
+    pub(crate) message: String,
+    pub(crate) summary: MasterFeatureSummary,
+}
+
+/// Payload of `build://error`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BuildErrorEvent {
+    pub(crate) key: String,
+    pub(crate) seq: u64,
+    pub(crate) message: String,
+    pub(crate) error: Option<ErrorPayload>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CacheUsage {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) evictions: u64,
+    pub(crate) entries: usize,
+    pub(crate) bytes: usize,
+    pub(crate) budget_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CacheStatsReport {
+    pub(crate) lzx_blocks: CacheUsage,
+    pub(crate) archives: CacheUsage,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct RuntimeIndex {
     pub(crate) contents: Vec<ContentItem>,
-    pub(crate) entries: Vec<EntryDetail>,
-    pub(crate) content_pages: BTreeMap<String, ContentPage>,
-    pub(crate) entry_keys: Vec<EntrySearchKey>,
+    /// Entries with precomputed search keys, owned or mapped from the runtime cache.
+    pub(crate) entries: EntryTable,
+    /// FST over normalized headwords and aliases.
+    pub(crate) headwords: Arc<HeadwordIndex>,
+    /// Decoded content and entry pages persisted next to the runtime cache.
+    pub(crate) pages: Option<Arc<PageStore>>,
 }
 
 #[derive(Debug, Clone)]
@@ -158,6 +343,13 @@ pub(crate) struct EntrySearchKey {
     pub(crate) body_loose: String,
     pub(crate) aliases: Vec<String>,
     pub(crate) aliases_loose: Vec<String>,
+    /// Jamo and chosung keys of each alias; empty for aliases without Hangul.
+    pub(crate) aliases_jamo: Vec<String>,
+    pub(crate) aliases_chosung: Vec<String>,
+    /// Korean glosses of the definition and their jamo and chosung keys, newline-separated.
+    pub(crate) glosses: String,
+    pub(crate) glosses_jamo: String,
+    pub(crate) glosses_chosung: String,
 }
 
 #[derive(Debug, Clone)]
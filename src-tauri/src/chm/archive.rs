@@ -1,13 +1,38 @@
+use super::cache::{CacheStats, LruCache};
 use super::lzx;
-use std::collections::BTreeMap;
-use std::sync::Arc;
+use super::source::{ChmSource, MemorySource, ReadSeekSource};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 mod compression;
 mod directory;
 mod headers;
 
 use compression::{parse_compression_context, CompressionContext};
-use headers::parse_container_layout;
+use directory::{find_directory_entry, DirectoryLayout};
+use headers::{parse_container_layout, read_source};
+
+/// Byte budget for decompressed LZX blocks across all open archives.
+const LZX_BLOCK_CACHE_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Decompressed blocks keyed by `(archive id, block index)`.
+type BlockCache = LruCache<(u64, u64), Arc<[u8]>>;
+
+static LZX_BLOCK_CACHE: OnceLock<Mutex<BlockCache>> = OnceLock::new();
+static NEXT_ARCHIVE_ID: AtomicU64 = AtomicU64::new(1);
+
+fn lzx_block_cache() -> &'static Mutex<BlockCache> {
+    LZX_BLOCK_CACHE.get_or_init(|| Mutex::new(LruCache::new(LZX_BLOCK_CACHE_BUDGET)))
+}
+
+/// Hit, miss and eviction counters of the shared LZX block cache.
+pub fn lzx_block_cache_stats() -> CacheStats {
+    lzx_block_cache()
+        .lock()
+        .map(|cache| cache.stats())
+        .unwrap_or_default()
+}
 
 #[derive(Debug, Clone)]
 pub struct DirectoryEntry {
@@ -17,18 +42,28 @@ pub struct DirectoryEntry {
     pub length: u64,
 }
 
-#[derive(Debug, Clone)]
+/// CHM container reader that pulls headers, directory chunks and compressed blocks
+/// from a [`ChmSource`] on demand.
+///
+/// Reads take `&self`, so one archive can be shared behind an `Arc` by concurrent readers.
+#[derive(Debug)]
 pub struct ChmArchive {
-    data: Arc<[u8]>,
+    /// Identity in the shared block cache.
+    id: u64,
+    source: Arc<dyn ChmSource>,
     data_offset: u64,
-    entries: Vec<DirectoryEntry>,
-    by_path: BTreeMap<String, usize>,
+    /// Directory chunks read once at open; `directory.blocks_offset` is relative to this buffer.
+    chunks: Arc<[u8]>,
+    directory: DirectoryLayout,
+    listing: OnceLock<Vec<DirectoryEntry>>,
+    /// Heap bytes of `listing`, summed once when it is materialised.
+    listing_bytes: OnceLock<usize>,
     compression: Option<CompressionContext>,
-    block_cache: BTreeMap<u64, Arc<[u8]>>,
-    native_streams: BTreeMap<u64, NativeStream>,
+    /// LZX decoder state per reset interval, each behind its own lock.
+    streams: Box<[Mutex<Option<NativeStream>>]>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct NativeStream {
     next_block: u64,
     state: lzx::LzxState,
@@ -40,7 +75,9 @@ pub enum ChmError {
     OutOfBounds,
     UnsupportedCompressedObject,
     DecompressionFailed(String),
+    CompressionFailed(String),
     Utf8Path,
+    Io(std::io::Error),
 }
 
 impl std::fmt::Display for ChmError {
@@ -50,7 +87,9 @@ impl std::fmt::Display for ChmError {
             ChmError::OutOfBounds => write!(f, "CHM read out of bounds"),
             ChmError::UnsupportedCompressedObject => write!(f, "compressed object is not supported yet"),
             ChmError::DecompressionFailed(s) => write!(f, "CHM decompression failed: {s}"),
+            ChmError::CompressionFailed(s) => write!(f, "CHM compression failed: {s}"),
             ChmError::Utf8Path => write!(f, "invalid UTF-8 path in PMGL entry"),
+            ChmError::Io(e) => write!(f, "CHM source read failed: {e}"),
         }
     }
 }
@@ -58,71 +97,151 @@ impl std::fmt::Display for ChmError {
 impl std::error::Error for ChmError {}
 
 impl ChmArchive {
+    /// Open a CHM held fully in memory.
     pub fn open(data: impl Into<Arc<[u8]>>) -> Result<Self, ChmError> {
-        let data = data.into();
-        let layout = parse_container_layout(&data)?;
-        let entries = directory::parse_directory_entries(
-            &data,
-            layout.blocks_offset,
-            layout.block_len,
-            layout.num_blocks,
-        )?;
+        Self::open_source(Arc::new(MemorySource::from(data.into())))
+    }
 
-        let mut by_path = BTreeMap::new();
-        for (i, e) in entries.iter().enumerate() {
-            by_path.insert(e.path.to_ascii_lowercase(), i);
-        }
-        let compression = parse_compression_context(&data, layout.data_offset, &entries, &by_path)?;
+    /// Open a CHM file on disk without loading it into memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be opened or is not a valid CHM container.
+    pub fn open_file(path: &Path) -> Result<Self, ChmError> {
+        Self::open_source(Arc::new(ReadSeekSource::open(path).map_err(ChmError::Io)?))
+    }
+
+    /// Open a CHM from any positional-read backend.
+    ///
+    /// Only the headers, the directory chunks and the compression tables are read here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the source cannot be read or is not a valid CHM container.
+    pub fn open_source(source: Arc<dyn ChmSource>) -> Result<Self, ChmError> {
+        let layout = parse_container_layout(source.as_ref())?;
+        let chunks_len = (layout.num_blocks as u64)
+            .checked_mul(layout.block_len as u64)
+            .ok_or(ChmError::OutOfBounds)?;
+        let chunks: Arc<[u8]> = read_source(source.as_ref(), layout.blocks_offset as u64, chunks_len)?.into();
+        let directory = DirectoryLayout {
+            blocks_offset: 0,
+            ..layout.directory()
+        };
+        let compression = parse_compression_context(source.as_ref(), layout.data_offset, &chunks, &directory)?;
+
+        let intervals = compression.as_ref().map_or(0, |ctx| {
+            let reset_blkcount = std::cmp::max(ctx.lzx_params.reset_blkcount as u64, 1);
+            (ctx.block_offsets.len() as u64).div_ceil(reset_blkcount) as usize
+        });
+        let streams = (0..intervals).map(|_| Mutex::new(None)).collect();
 
         Ok(Self {
-            data,
+            id: NEXT_ARCHIVE_ID.fetch_add(1, Ordering::Relaxed),
+            source,
             data_offset: layout.data_offset,
-            entries,
-            by_path,
+            chunks,
+            directory,
+            listing: OnceLock::new(),
+            listing_bytes: OnceLock::new(),
             compression,
-            block_cache: BTreeMap::new(),
-            native_streams: BTreeMap::new(),
+            streams,
         })
     }
 
-    pub fn entries(&self) -> &[DirectoryEntry] {
-        &self.entries
+    /// Approximate heap footprint: resident source bytes, directory chunks and the listing.
+    pub fn resident_bytes(&self) -> usize {
+        let listing = self.listing_bytes.get().copied().unwrap_or(0);
+        self.source.resident_len() as usize + self.chunks.len() + listing
     }
 
-    pub fn find_entry(&self, path: &str) -> Option<&DirectoryEntry> {
-        let raw = path.to_ascii_lowercase();
-        if let Some(idx) = self.by_path.get(&raw) {
-            return self.entries.get(*idx);
+    /// Full directory listing, materialised on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a directory chunk is corrupt; the failure is not cached.
+    pub fn entries(&self) -> Result<&[DirectoryEntry], ChmError> {
+        if let Some(listing) = self.listing.get() {
+            return Ok(listing);
+        }
+        let listing = directory::parse_directory_entries(
+            &self.chunks,
+            self.directory.blocks_offset,
+            self.directory.block_len,
+            self.directory.num_blocks,
+        )?;
+        let listing = self.listing.get_or_init(|| listing);
+        self.listing_bytes.get_or_init(|| {
+            listing
+                .iter()
+                .map(|e| e.path.len() + std::mem::size_of::<DirectoryEntry>())
+                .sum()
+        });
+        Ok(listing)
+    }
+
+    /// Look up a single object through the PMGI index without listing the directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a directory chunk on the lookup path is corrupt.
+    pub fn find_entry(&self, path: &str) -> Result<Option<DirectoryEntry>, ChmError> {
+        if let Some(found) = self.lookup_directory(path)? {
+            return Ok(Some(found));
+        }
+        let trimmed = path.trim_start_matches('/');
+        if trimmed.len() == path.len() {
+            return Ok(None);
         }
-        let trimmed = path.trim_start_matches('/').to_ascii_lowercase();
-        let idx = self.by_path.get(&trimmed)?;
-        self.entries.get(*idx)
+        self.lookup_directory(trimmed)
     }
 
-    pub fn read_object(&mut self, path: &str) -> Result<Vec<u8>, ChmError> {
+    fn lookup_directory(&self, path: &str) -> Result<Option<DirectoryEntry>, ChmError> {
+        if let Some(found) = find_directory_entry(&self.chunks, &self.directory, path)? {
+            return Ok(Some(found));
+        }
+        // Non-ASCII names are not reliably ordered bytewise by CHM compilers.
+        if path.is_ascii() {
+            return Ok(None);
+        }
+        Ok(self.entries()?.iter().find(|e| e.path.eq_ignore_ascii_case(path)).cloned())
+    }
+
+    pub fn read_object(&self, path: &str) -> Result<Vec<u8>, ChmError> {
+        self.read_object_range(path, 0, u64::MAX)
+    }
+
+    /// Read `len` bytes starting at `offset` inside an object, clamped to the object end.
+    ///
+    /// Compressed objects only decompress the blocks covering the requested range.
+    pub fn read_object_range(&self, path: &str, offset: u64, len: u64) -> Result<Vec<u8>, ChmError> {
         let entry = self
-            .find_entry(path)
+            .find_entry(path)?
             .ok_or(ChmError::InvalidFormat("entry not found"))?;
+        let offset = offset.min(entry.length);
+        let len = len.min(entry.length - offset);
+        let start = entry.start.checked_add(offset).ok_or(ChmError::OutOfBounds)?;
         if entry.space != 0 {
-            return self.read_compressed_object(entry.start, entry.length);
+            return self.read_compressed_object(start, len);
         }
-        let start = self.data_offset as usize + entry.start as usize;
-        let end = start + entry.length as usize;
-        let bytes = self.data.get(start..end).ok_or(ChmError::OutOfBounds)?;
-        Ok(bytes.to_vec())
+        let start = self.data_offset.checked_add(start).ok_or(ChmError::OutOfBounds)?;
+        read_source(self.source.as_ref(), start, len)
     }
 
-    fn read_compressed_object(&mut self, start: u64, len: u64) -> Result<Vec<u8>, ChmError> {
+    fn read_compressed_object(&self, start: u64, len: u64) -> Result<Vec<u8>, ChmError> {
         let ctx = self
             .compression
             .as_ref()
-            .ok_or(ChmError::UnsupportedCompressedObject)?
-            .clone();
+            .ok_or(ChmError::UnsupportedCompressedObject)?;
         if ctx.block_len == 0 {
             return Err(ChmError::UnsupportedCompressedObject);
         }
+        if start.checked_add(len).is_none_or(|end| end > ctx.uncompressed_len) {
+            return Err(ChmError::OutOfBounds);
+        }
 
-        let mut out = Vec::with_capacity(len as usize);
+        // Directory lengths are untrusted; grow with decoded blocks instead of reserving `len`.
+        let mut out = Vec::new();
         let mut remaining = len;
         let mut pos = start;
 
@@ -130,7 +249,7 @@ impl ChmArchive {
             let block = pos / ctx.block_len;
             let offset_in_block = (pos % ctx.block_len) as usize;
             let take = std::cmp::min(remaining, ctx.block_len - (offset_in_block as u64)) as usize;
-            let block_data = self.decompress_block(&ctx, block)?;
+            let block_data = self.decompress_block(ctx, block)?;
             if offset_in_block + take > block_data.len() {
                 return Err(ChmError::OutOfBounds);
             }
@@ -142,45 +261,70 @@ impl ChmArchive {
         Ok(out)
     }
 
-    fn decompress_block(&mut self, ctx: &CompressionContext, block: u64) -> Result<Arc<[u8]>, ChmError> {
-        if let Some(v) = self.block_cache.get(&block) {
-            return Ok(Arc::clone(v));
+    fn decompress_block(&self, ctx: &CompressionContext, block: u64) -> Result<Arc<[u8]>, ChmError> {
+        if let Some(v) = self.cached_block(block) {
+            return Ok(v);
         }
 
-        let out = self.decompress_block_native(ctx, block)?;
+        let reset_blkcount = std::cmp::max(ctx.lzx_params.reset_blkcount as u64, 1);
+        let slot = self
+            .streams
+            .get((block / reset_blkcount) as usize)
+            .ok_or(ChmError::OutOfBounds)?;
+        // Blocks of one reset interval decode sequentially; other intervals proceed in parallel.
+        let mut stream = slot
+            .lock()
+            .map_err(|_| ChmError::DecompressionFailed("LZX stream lock poisoned".to_string()))?;
+        // Another reader may have produced the block while this one waited for the lock.
+        if let Some(v) = self.cached_block(block) {
+            return Ok(v);
+        }
+
+        let out = self.decompress_block_native(ctx, block, &mut stream)?;
 
-        self.block_cache.insert(block, Arc::clone(&out));
+        self.cache_block(block, &out);
         Ok(out)
     }
 
-    fn decompress_block_native(&mut self, ctx: &CompressionContext, block: u64) -> Result<Arc<[u8]>, ChmError> {
+    fn cached_block(&self, block: u64) -> Option<Arc<[u8]>> {
+        lzx_block_cache().lock().ok()?.get(&(self.id, block))
+    }
+
+    fn cache_block(&self, block: u64, data: &Arc<[u8]>) {
+        if let Ok(mut cache) = lzx_block_cache().lock() {
+            cache.insert((self.id, block), Arc::clone(data), data.len());
+        }
+    }
+
+    fn decompress_block_native(
+        &self,
+        ctx: &CompressionContext,
+        block: u64,
+        slot: &mut Option<NativeStream>,
+    ) -> Result<Arc<[u8]>, ChmError> {
         let window_size = ctx.lzx_params.window_size;
         let window_bits = (32 - window_size.leading_zeros()) as u8 - 1;
         let reset_blkcount = std::cmp::max(ctx.lzx_params.reset_blkcount as u64, 1);
         let reset_base = block - (block % reset_blkcount);
-        let mut stream = self.native_streams.remove(&reset_base).unwrap_or(NativeStream {
-            next_block: reset_base,
-            state: lzx::LzxState::new(window_bits).map_err(ChmError::DecompressionFailed)?,
-        });
-
-        // If the requested block is behind stream progress, rebuild from reset base.
-        if stream.next_block > block {
-            stream = NativeStream {
+        let mut stream = match slot.take() {
+            // If the requested block is behind stream progress, rebuild from reset base.
+            Some(stream) if stream.next_block <= block => stream,
+            _ => NativeStream {
                 next_block: reset_base,
                 state: lzx::LzxState::new(window_bits).map_err(ChmError::DecompressionFailed)?,
-            };
-        }
+            },
+        };
 
         let mut target = None::<Arc<[u8]>>;
         let mut failed = None;
         for b in stream.next_block..=block {
             let cmp = self.read_compressed_block_bytes(ctx, b)?;
             let out_len = compression::block_output_len(ctx, b) as usize;
-            let padded_cmp = pad_for_lzx(cmp);
+            let padded_cmp = pad_for_lzx(&cmp);
             match lzx::decompress_block(&mut stream.state, &padded_cmp, out_len) {
                 Ok(out) => {
                     let out: Arc<[u8]> = Arc::from(out.into_boxed_slice());
-                    self.block_cache.insert(b, Arc::clone(&out));
+                    self.cache_block(b, &out);
                     if b == block {
                         target = Some(out);
                     }
@@ -196,13 +340,10 @@ impl ChmArchive {
             let fresh = self.decompress_block_native_fresh(ctx, block, window_bits);
             match fresh {
                 Ok((v, fresh_state)) => {
-                    self.native_streams.insert(
-                        reset_base,
-                        NativeStream {
-                            next_block: block.saturating_add(1),
-                            state: fresh_state,
-                        },
-                    );
+                    *slot = Some(NativeStream {
+                        next_block: block.saturating_add(1),
+                        state: fresh_state,
+                    });
                     return Ok(v);
                 }
                 Err(fresh_err) => {
@@ -214,12 +355,12 @@ impl ChmArchive {
         }
 
         stream.next_block = block.saturating_add(1);
-        self.native_streams.insert(reset_base, stream);
+        *slot = Some(stream);
         Ok(target.unwrap_or_else(|| Arc::<[u8]>::from(&[][..])))
     }
 
     fn decompress_block_native_fresh(
-        &mut self,
+        &self,
         ctx: &CompressionContext,
         block: u64,
         bits: u8,
@@ -233,11 +374,11 @@ impl ChmArchive {
                 .read_compressed_block_bytes(ctx, b)
                 .map_err(|e| format!("read compressed block {b} failed: {e}"))?;
             let out_len = compression::block_output_len(ctx, b) as usize;
-            let padded_cmp = pad_for_lzx(cmp);
+            let padded_cmp = pad_for_lzx(&cmp);
             let out = lzx::decompress_block(&mut state, &padded_cmp, out_len)
                 .map_err(|e| format!("fresh decode failed at block {b}: {e}"))?;
             let out: Arc<[u8]> = Arc::from(out.into_boxed_slice());
-            self.block_cache.insert(b, Arc::clone(&out));
+            self.cache_block(b, &out);
             if b == block {
                 target = Some(out);
             }
@@ -245,7 +386,7 @@ impl ChmArchive {
         Ok((target.unwrap_or_else(|| Arc::<[u8]>::from(&[][..])), state))
     }
 
-    fn read_compressed_block_bytes<'a>(&'a self, ctx: &CompressionContext, block: u64) -> Result<&'a [u8], ChmError> {
+    fn read_compressed_block_bytes(&self, ctx: &CompressionContext, block: u64) -> Result<Vec<u8>, ChmError> {
         if block >= ctx.block_count as u64 {
             return Err(ChmError::OutOfBounds);
         }
@@ -261,13 +402,12 @@ impl ChmArchive {
         if end_off < start_off {
             return Err(ChmError::InvalidFormat("invalid block offset ordering"));
         }
-        let abs_start = self.data_offset + ctx.content_start + start_off;
-        let abs_end = self.data_offset + ctx.content_start + end_off;
-        let bytes = self
-            .data
-            .get(abs_start as usize..abs_end as usize)
+        let abs_start = self
+            .data_offset
+            .checked_add(ctx.content_start)
+            .and_then(|content| content.checked_add(start_off))
             .ok_or(ChmError::OutOfBounds)?;
-        Ok(bytes)
+        read_source(self.source.as_ref(), abs_start, end_off - start_off)
     }
 }
 
@@ -281,6 +421,11 @@ fn pad_for_lzx(bytes: &[u8]) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chm::system::{ChmSystem, SystemInfo};
+    use crate::chm::test_support::{build_indexed_container, build_raw_listing_container};
+    use crate::chm::writer::{ChmWriter, ChmWriterOptions};
+    use proptest::prelude::*;
+    use proptest::sample::Index;
     use std::collections::BTreeMap;
     use std::fs;
     use std::fs::File;
@@ -324,19 +469,164 @@ mod tests {
 
     fn entry_path_map_by_basename(chm: &ChmArchive) -> BTreeMap<String, String> {
         let mut m = BTreeMap::new();
-        for e in chm.entries() {
+        for e in chm.entries().expect("listing") {
             let base = e.path.rsplit('/').next().unwrap_or(&e.path).to_ascii_lowercase();
             m.entry(base).or_insert_with(|| e.path.clone());
         }
         m
     }
 
+    #[test]
+    fn pmgi_lookup_matches_full_listing() {
+        let names = (0..60).map(|i| format!("/Page{i:03}.htm")).collect::<Vec<_>>();
+        let bodies = names.iter().map(|n| format!("<p>{n}</p>")).collect::<Vec<_>>();
+        let objects = names
+            .iter()
+            .zip(bodies.iter())
+            .map(|(n, b)| (n.as_str(), b.as_bytes()))
+            .collect::<Vec<_>>();
+        let bytes = build_indexed_container(&objects, 0x100);
+        let layout = headers::parse_container_layout(&MemorySource::from(bytes.clone())).expect("layout");
+        assert!(layout.index_root.is_some());
+        assert!(layout.num_blocks > 3, "test needs several listing chunks");
+
+        let chm = ChmArchive::open(bytes).expect("open synthetic chm");
+        assert_eq!(chm.entries().expect("listing").len(), names.len());
+        for listed in chm.entries().expect("listing").to_vec() {
+            let found = chm.find_entry(&listed.path).expect("indexed lookup").expect("listed entry");
+            assert_eq!(found.path, listed.path);
+            assert_eq!(found.start, listed.start);
+            assert_eq!(found.length, listed.length);
+        }
+
+        let find = |path: &str| chm.find_entry(path).expect("lookup");
+        assert!(find("/PAGE007.HTM").is_some());
+        assert!(find("Page007.htm").is_none());
+        assert!(find("/Page0075.htm").is_none());
+        assert!(find("/").is_none());
+        assert!(find("/zzz.htm").is_none());
+        assert_eq!(chm.read_object("/page042.htm").expect("read"), b"<p>/Page042.htm</p>");
+        assert_eq!(chm.read_object_range("/page042.htm", 3, 4).expect("range"), b"/Pag");
+        assert_eq!(chm.read_object_range("/page042.htm", 14, 64).expect("clamped"), b"m</p>");
+        assert!(chm.read_object_range("/page042.htm", 999, 4).expect("past end").is_empty());
+
+        // A listing name that is not UTF-8 fails the listing instead of emptying it.
+        let mut corrupt = build_indexed_container(&objects, 0x100);
+        let at = corrupt
+            .windows(12)
+            .position(|w| w == b"/Page030.htm")
+            .expect("listed name");
+        corrupt[at + 1] = 0xff;
+        let chm = ChmArchive::open(corrupt).expect("open corrupt listing");
+        assert!(chm.entries().is_err());
+
+        // A damaged chunk on the lookup path is an error, not a missing object.
+        let mut damaged = build_indexed_container(&objects, 0x100);
+        let at = damaged
+            .windows(12)
+            .position(|w| w == b"/Page030.htm")
+            .expect("listed name");
+        let chunk = damaged[..at]
+            .windows(4)
+            .rposition(|w| w == b"PMGL")
+            .expect("listing chunk");
+        damaged[chunk..chunk + 4].copy_from_slice(b"XXXX");
+        let chm = ChmArchive::open(damaged).expect("open damaged index");
+        assert!(chm.find_entry("/Page030.htm").is_err());
+    }
+
+    #[test]
+    fn hostile_directory_offsets_are_errors() {
+        let bytes = build_raw_listing_container(
+            &[
+                ("/huge", 0, u64::MAX - 4, 16),
+                ("/long", 0, 0, u64::MAX),
+                ("/packed", 1, u64::MAX - 1, 8),
+            ],
+            b"abc",
+            0x100,
+        );
+        let chm = ChmArchive::open(bytes).expect("open synthetic chm");
+        assert!(matches!(chm.read_object("/huge"), Err(ChmError::OutOfBounds)));
+        assert!(matches!(chm.read_object("/long"), Err(ChmError::OutOfBounds)));
+        assert!(matches!(chm.read_object_range("/long", u64::MAX - 1, 4), Err(ChmError::OutOfBounds)));
+        assert!(matches!(
+            chm.read_object("/packed"),
+            Err(ChmError::UnsupportedCompressedObject)
+        ));
+    }
+
+    fn written_sample() -> &'static [u8] {
+        static SAMPLE: OnceLock<Vec<u8>> = OnceLock::new();
+        SAMPLE.get_or_init(|| {
+            let mut writer = ChmWriter::new(ChmWriterOptions {
+                directory_block_len: 0x100,
+                reset_blkcount: 1,
+                ..ChmWriterOptions::default()
+            });
+            writer.set_system_info(SystemInfo {
+                title: Some("sample".to_string()),
+                lcid: Some(0x0412),
+                ..SystemInfo::default()
+            });
+            for i in 0..40 {
+                let body = format!("<p>Wort {i}: 단어 {}</p>", i * 31).repeat(60);
+                writer.add_object(&format!("/w/{i:02}.htm"), body).expect("add");
+                writer.add_topic(Some(&format!("Wort {i}")), &format!("w/{i:02}.htm"), true);
+            }
+            writer.finish().expect("finish sample")
+        })
+    }
+
+    /// Touch every parser reachable from an opened container; only panics fail the caller.
+    fn exercise_container(bytes: Vec<u8>) {
+        let Ok(chm) = ChmArchive::open(bytes) else {
+            return;
+        };
+        let _ = ChmSystem::read(&chm);
+        let listing = chm.entries().map(<[_]>::to_vec).unwrap_or_default();
+        for entry in listing.iter().take(64) {
+            let _ = chm.find_entry(&entry.path);
+            let _ = chm.read_object_range(&entry.path, 0, 1 << 20);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        #[test]
+        fn mutated_containers_fail_without_panicking(
+            edits in prop::collection::vec((any::<bool>(), any::<Index>(), any::<u8>()), 1..12),
+            cut in prop::option::of(any::<Index>()),
+        ) {
+            let mut bytes = written_sample().to_vec();
+            // Half of the edits land in the headers and directory, where offsets are parsed.
+            let header_len = 0x60 + 0x18 + 0x54 + 4 * 0x100;
+            for (in_header, at, value) in edits {
+                let limit = if in_header { header_len } else { bytes.len() };
+                let i = at.index(limit);
+                bytes[i] = value;
+            }
+            if let Some(cut) = cut {
+                bytes.truncate(cut.index(bytes.len()));
+            }
+            exercise_container(bytes);
+        }
+
+        #[test]
+        fn arbitrary_bytes_after_itsf_signature_fail_cleanly(tail in prop::collection::vec(any::<u8>(), 0..2048)) {
+            let mut bytes = b"ITSF".to_vec();
+            bytes.extend(tail);
+            exercise_container(bytes);
+        }
+    }
+
     #[test]
     fn can_open_real_chm_and_parse_entries() {
         let Some(bytes) = load_chm_bytes_from_zip("merge36.chm") else {
             return;
         };
-        let layout = headers::parse_container_layout(&bytes).expect("layout");
+        let layout = headers::parse_container_layout(&MemorySource::from(bytes.clone())).expect("layout");
         let entries = directory::parse_directory_entries(
             &bytes,
             layout.blocks_offset,
@@ -347,7 +637,7 @@ mod tests {
         assert!(!entries.is_empty());
 
         let chm = ChmArchive::open(bytes).expect("open chm");
-        assert!(!chm.entries().is_empty());
+        assert!(!chm.entries().expect("listing").is_empty());
     }
 
     #[test]
@@ -355,7 +645,7 @@ mod tests {
         let Some(bytes) = load_chm_bytes_from_zip("merge36.chm") else {
             return;
         };
-        let mut chm = ChmArchive::open(bytes).expect("open chm");
+        let chm = ChmArchive::open(bytes).expect("open chm");
         let ctl = chm.read_object("::DataSpace/Storage/MSCompressed/ControlData");
         assert!(ctl.is_ok());
         let ctl = ctl.expect("control data");
@@ -367,9 +657,10 @@ mod tests {
         let Some(bytes) = load_chm_bytes_from_zip("merge36.chm") else {
             return;
         };
-        let mut chm = ChmArchive::open(bytes).expect("open chm");
+        let chm = ChmArchive::open(bytes).expect("open chm");
         let candidates = chm
             .entries()
+            .expect("listing")
             .iter()
             .filter(|e| e.space != 0 && e.path.to_ascii_lowercase().ends_with(".htm"))
             .take(64)
@@ -401,6 +692,89 @@ mod tests {
         );
     }
 
+    /// Extra coverage on the real dataset, when it is available.
+    #[test]
+    fn concurrent_readers_share_one_archive() {
+        let Some(bytes) = load_chm_bytes_from_zip("merge36.chm") else {
+            return;
+        };
+        let chm = Arc::new(ChmArchive::open(bytes).expect("open chm"));
+        let paths = chm
+            .entries()
+            .expect("listing")
+            .iter()
+            .filter(|e| e.space != 0 && e.path.to_ascii_lowercase().ends_with(".htm"))
+            .take(32)
+            .map(|e| e.path.clone())
+            .collect::<Vec<_>>();
+        let readers = (0..4)
+            .map(|i| {
+                let chm = Arc::clone(&chm);
+                let mut paths = paths.clone();
+                let shift = i * paths.len() / 4;
+                paths.rotate_left(shift);
+                std::thread::spawn(move || {
+                    paths
+                        .into_iter()
+                        .map(|p| chm.read_object(&p).map(|v| (p, v)))
+                        .collect::<Result<BTreeMap<_, _>, _>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = readers
+            .into_iter()
+            .map(|h| h.join().expect("reader thread").expect("concurrent read"))
+            .collect::<Vec<_>>();
+        for other in &results[1..] {
+            assert_eq!(&results[0], other);
+        }
+    }
+
+    #[test]
+    fn concurrent_readers_decode_overlapping_reset_intervals() {
+        let objects = (0..24)
+            .map(|i| {
+                let body = (0..400)
+                    .map(|j| format!("<p>{i}-{j}: Wort {} 단어</p>", (i * 7919 + j * 104_729) % 100_003))
+                    .collect::<String>();
+                (format!("/t/{i:02}.htm"), body)
+            })
+            .collect::<Vec<_>>();
+        let mut writer = ChmWriter::new(ChmWriterOptions {
+            reset_blkcount: 2,
+            ..ChmWriterOptions::default()
+        });
+        for (path, body) in &objects {
+            writer.add_object(path, body.clone()).expect("add");
+        }
+        let chm = Arc::new(ChmArchive::open(writer.finish().expect("finish")).expect("open chm"));
+        assert!(chm.streams.len() > 2, "objects must span several reset intervals");
+
+        let objects = Arc::new(objects);
+        let readers = (0..8)
+            .map(|i| {
+                let chm = Arc::clone(&chm);
+                let objects = Arc::clone(&objects);
+                std::thread::spawn(move || {
+                    // Each reader walks the objects in its own order, so threads meet in the
+                    // same intervals and blocks from different starting points.
+                    for round in 0..3 {
+                        for k in 0..objects.len() {
+                            let (path, body) = &objects[(k * (i + 1) + i + round) % objects.len()];
+                            assert_eq!(chm.read_object(path).expect("read object"), body.as_bytes());
+                            let mid = body.len() / 2;
+                            let range = chm.read_object_range(path, mid as u64, 64).expect("read range");
+                            assert_eq!(range, &body.as_bytes()[mid..mid + 64]);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for reader in readers {
+            reader.join().expect("reader thread");
+        }
+    }
+
     #[test]
     fn golden_merge36_html_matches_debug_extraction() {
         let Some(bytes) = load_chm_bytes_from_zip("merge36.chm") else {
@@ -409,7 +783,7 @@ mod tests {
         let Some(debug_dir) = find_debug_dir("merge36") else {
             return;
         };
-        let mut chm = ChmArchive::open(bytes).expect("open chm");
+        let chm = ChmArchive::open(bytes).expect("open chm");
 
         let path_map = entry_path_map_by_basename(&chm);
         let mut golden_files = fs::read_dir(&debug_dir)
@@ -470,7 +844,7 @@ mod tests {
         let Some(debug_dir) = find_debug_dir("master") else {
             return;
         };
-        let mut chm = ChmArchive::open(bytes).expect("open master chm");
+        let chm = ChmArchive::open(bytes).expect("open master chm");
 
         let path_map = entry_path_map_by_basename(&chm);
         let mut golden_files = fs::read_dir(&debug_dir)
@@ -531,7 +905,7 @@ mod tests {
         let Some(debug_dir) = find_debug_dir("merge36") else {
             return;
         };
-        let mut chm = ChmArchive::open(bytes).expect("open chm");
+        let chm = ChmArchive::open(bytes).expect("open chm");
         let path_map = entry_path_map_by_basename(&chm);
         let mut mismatches = Vec::new();
         let mut decode_errors = Vec::new();
@@ -626,15 +1000,18 @@ mod tests {
                     continue;
                 }
             };
-            let mut chm = match ChmArchive::open(bytes) {
+            let chm = match ChmArchive::open(bytes) {
                 Ok(v) => v,
                 Err(e) => {
                     failures.push(format!("{name}: open failed: {e}"));
                     continue;
                 }
             };
-            let html_paths = chm
-                .entries()
+            let Ok(listing) = chm.entries() else {
+                failures.push(format!("{name}: directory listing failed"));
+                continue;
+            };
+            let html_paths = listing
                 .iter()
                 .filter(|e| {
                     let p = e.path.to_ascii_lowercase();
@@ -1,8 +1,7 @@
-use std::collections::BTreeMap;
-
 use super::{
-    headers::{read_u32_le, read_u64_le},
-    ChmError, DirectoryEntry,
+    directory::{find_directory_entry, DirectoryLayout},
+    headers::{read_source, read_u32_le, read_u64_le},
+    ChmError, ChmSource, DirectoryEntry,
 };
 
 const CHMU_RESET_TABLE: &str =
@@ -27,32 +26,38 @@ pub(crate) struct LzxParams {
     pub(crate) reset_blkcount: u32,
 }
 
+/// Locate the MSCompressed section and read its control data and reset table.
+///
+/// `chunks` holds the directory chunks described by `directory`.
 pub(crate) fn parse_compression_context(
-    data: &[u8],
+    source: &dyn ChmSource,
     data_offset: u64,
-    entries: &[DirectoryEntry],
-    by_path: &BTreeMap<String, usize>,
+    chunks: &[u8],
+    directory: &DirectoryLayout,
 ) -> Result<Option<CompressionContext>, ChmError> {
-    let rt = by_path.get(&CHMU_RESET_TABLE.to_ascii_lowercase()).copied();
-    let cd = by_path.get(&CHMU_LZXC_CONTROLDATA.to_ascii_lowercase()).copied();
-    let cn = by_path.get(&CHMU_CONTENT.to_ascii_lowercase()).copied();
-    let (Some(rt_idx), Some(cd_idx), Some(cn_idx)) = (rt, cd, cn) else {
+    let rt = find_directory_entry(chunks, directory, CHMU_RESET_TABLE)?;
+    let cd = find_directory_entry(chunks, directory, CHMU_LZXC_CONTROLDATA)?;
+    let cn = find_directory_entry(chunks, directory, CHMU_CONTENT)?;
+    let (Some(rt_entry), Some(cd_entry), Some(cn_entry)) = (rt, cd, cn) else {
         return Ok(None);
     };
 
-    let rt_entry = entries.get(rt_idx).ok_or(ChmError::OutOfBounds)?;
-    let cd_entry = entries.get(cd_idx).ok_or(ChmError::OutOfBounds)?;
-    let cn_entry = entries.get(cn_idx).ok_or(ChmError::OutOfBounds)?;
     if rt_entry.space != 0 || cd_entry.space != 0 || cn_entry.space != 0 {
         return Ok(None);
     }
 
-    let rt_bytes = read_uncompressed_bytes(data, data_offset, rt_entry)?;
-    let cd_bytes = read_uncompressed_bytes(data, data_offset, cd_entry)?;
+    let rt_bytes = read_uncompressed_bytes(source, data_offset, &rt_entry)?;
+    let cd_bytes = read_uncompressed_bytes(source, data_offset, &cd_entry)?;
 
-    let (window_size, reset_blkcount) = parse_lzxc_control_data(cd_bytes)?;
+    let (window_size, reset_blkcount) = parse_lzxc_control_data(&cd_bytes)?;
     let (block_len, uncompressed_len, compressed_len, block_count, block_offsets) =
-        parse_lzxc_reset_table(rt_bytes)?;
+        parse_lzxc_reset_table(&rt_bytes)?;
+    if block_len > u64::from(window_size) {
+        return Err(ChmError::InvalidFormat("LZX block longer than window"));
+    }
+    if u64::from(block_count).checked_mul(block_len).is_none_or(|total| total < uncompressed_len) {
+        return Err(ChmError::InvalidFormat("ResetTable blocks do not cover content"));
+    }
 
     Ok(Some(CompressionContext {
         content_start: cn_entry.start,
@@ -76,14 +81,13 @@ pub(crate) fn block_output_len(ctx: &CompressionContext, block: u64) -> u64 {
     std::cmp::min(ctx.block_len, ctx.uncompressed_len - start)
 }
 
-fn read_uncompressed_bytes<'a>(
-    data: &'a [u8],
+fn read_uncompressed_bytes(
+    source: &dyn ChmSource,
     data_offset: u64,
     entry: &DirectoryEntry,
-) -> Result<&'a [u8], ChmError> {
-    let start = data_offset as usize + entry.start as usize;
-    let end = start + entry.length as usize;
-    data.get(start..end).ok_or(ChmError::OutOfBounds)
+) -> Result<Vec<u8>, ChmError> {
+    let start = data_offset.checked_add(entry.start).ok_or(ChmError::OutOfBounds)?;
+    read_source(source, start, entry.length)
 }
 
 fn parse_lzxc_control_data(bytes: &[u8]) -> Result<(u32, u32), ChmError> {
@@ -136,8 +140,11 @@ fn parse_lzxc_reset_table(bytes: &[u8]) -> Result<(u64, u64, u64, u32, Vec<u64>)
     if block_count == 0 || block_len == 0 {
         return Err(ChmError::InvalidFormat("invalid ResetTable values"));
     }
-    let table_bytes = (block_count as usize) * 8;
-    if table_offset + table_bytes > bytes.len() {
+    let table_end = (block_count as usize)
+        .checked_mul(8)
+        .and_then(|table_bytes| table_offset.checked_add(table_bytes))
+        .ok_or(ChmError::OutOfBounds)?;
+    if table_end > bytes.len() {
         return Err(ChmError::OutOfBounds);
     }
     let mut block_offsets = Vec::with_capacity(block_count as usize);
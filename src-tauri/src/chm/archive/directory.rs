@@ -1,10 +1,28 @@
+use std::cmp::Ordering;
+
 use super::{headers::read_u32_le, ChmError, DirectoryEntry};
 
+const PMGL_ENTRIES_OFFSET: usize = 0x14;
+const PMGI_ENTRIES_OFFSET: usize = 0x08;
+
+/// Location of the directory chunks and the optional PMGI index root.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DirectoryLayout {
+    pub(crate) blocks_offset: usize,
+    pub(crate) block_len: usize,
+    pub(crate) num_blocks: usize,
+    pub(crate) index_root: Option<usize>,
+    pub(crate) first_listing: usize,
+}
+
 fn parse_cword(buf: &[u8], pos: &mut usize) -> Result<u64, ChmError> {
     let mut accum = 0u64;
     loop {
         let b = *buf.get(*pos).ok_or(ChmError::OutOfBounds)?;
         *pos += 1;
+        if accum > u64::MAX >> 7 {
+            return Err(ChmError::InvalidFormat("oversized directory integer"));
+        }
         if b < 0x80 {
             return Ok((accum << 7) + b as u64);
         }
@@ -12,6 +30,136 @@ fn parse_cword(buf: &[u8], pos: &mut usize) -> Result<u64, ChmError> {
     }
 }
 
+/// Compare a directory name with an already lowercased lookup key (chmlib `strcasecmp` order).
+fn cmp_path_ci(name: &[u8], key_lower: &[u8]) -> Ordering {
+    name.iter()
+        .map(u8::to_ascii_lowercase)
+        .cmp(key_lower.iter().copied())
+}
+
+fn directory_chunk<'a>(data: &'a [u8], layout: &DirectoryLayout, chunk: usize) -> Result<&'a [u8], ChmError> {
+    if chunk >= layout.num_blocks {
+        return Err(ChmError::OutOfBounds);
+    }
+    chunk
+        .checked_mul(layout.block_len)
+        .and_then(|offset| offset.checked_add(layout.blocks_offset))
+        .and_then(|base| data.get(base..base.checked_add(layout.block_len)?))
+        .ok_or(ChmError::OutOfBounds)
+}
+
+/// Return the end of the entry area (chunk length minus quickref/free space).
+fn chunk_entries_end(page: &[u8], entries_offset: usize) -> Result<Option<usize>, ChmError> {
+    let free_space = read_u32_le(page, 0x04)? as usize;
+    let Some(end) = page.len().checked_sub(free_space) else {
+        return Ok(None);
+    };
+    if end < entries_offset {
+        return Ok(None);
+    }
+    Ok(Some(end))
+}
+
+fn read_name<'a>(page: &'a [u8], pos: &mut usize, end: usize) -> Result<Option<&'a [u8]>, ChmError> {
+    let name_len = parse_cword(page, pos)?;
+    let name_len = usize::try_from(name_len).map_err(|_| ChmError::OutOfBounds)?;
+    if name_len == 0 || name_len > end.saturating_sub(*pos) {
+        return Ok(None);
+    }
+    let name = &page[*pos..*pos + name_len];
+    *pos += name_len;
+    Ok(Some(name))
+}
+
+/// Pick the child chunk whose first name is the greatest one not after `key_lower`.
+fn find_in_pmgi(page: &[u8], key_lower: &[u8]) -> Result<Option<usize>, ChmError> {
+    let Some(end) = chunk_entries_end(page, PMGI_ENTRIES_OFFSET)? else {
+        return Ok(None);
+    };
+    let mut pos = PMGI_ENTRIES_OFFSET;
+    let mut child = None;
+    while pos < end {
+        let Some(name) = read_name(page, &mut pos, end)? else {
+            break;
+        };
+        let chunk = usize::try_from(parse_cword(page, &mut pos)?).map_err(|_| ChmError::OutOfBounds)?;
+        if cmp_path_ci(name, key_lower) == Ordering::Greater {
+            break;
+        }
+        child = Some(chunk);
+    }
+    Ok(child)
+}
+
+fn find_in_pmgl(page: &[u8], key_lower: &[u8]) -> Result<Option<DirectoryEntry>, ChmError> {
+    let Some(end) = chunk_entries_end(page, PMGL_ENTRIES_OFFSET)? else {
+        return Ok(None);
+    };
+    let mut pos = PMGL_ENTRIES_OFFSET;
+    while pos < end {
+        let Some(name) = read_name(page, &mut pos, end)? else {
+            break;
+        };
+        let space = parse_cword(page, &mut pos)?;
+        let start = parse_cword(page, &mut pos)?;
+        let length = parse_cword(page, &mut pos)?;
+        match cmp_path_ci(name, key_lower) {
+            Ordering::Less => {}
+            Ordering::Equal => {
+                let path = std::str::from_utf8(name).map_err(|_| ChmError::Utf8Path)?;
+                return Ok(Some(DirectoryEntry {
+                    path: path.to_string(),
+                    space,
+                    start,
+                    length,
+                }));
+            }
+            Ordering::Greater => break,
+        }
+    }
+    Ok(None)
+}
+
+/// Look up one directory entry by walking the PMGI index tree down to a PMGL chunk.
+///
+/// Without an index root every listing chunk is scanned, matching chmlib behavior.
+pub(crate) fn find_directory_entry(
+    data: &[u8],
+    layout: &DirectoryLayout,
+    path: &str,
+) -> Result<Option<DirectoryEntry>, ChmError> {
+    let key = path.to_ascii_lowercase();
+    let key = key.as_bytes();
+
+    let Some(root) = layout.index_root else {
+        for chunk in layout.first_listing..layout.num_blocks {
+            let page = directory_chunk(data, layout, chunk)?;
+            if page.get(0..4) != Some(b"PMGL") {
+                continue;
+            }
+            if let Some(found) = find_in_pmgl(page, key)? {
+                return Ok(Some(found));
+            }
+        }
+        return Ok(None);
+    };
+
+    let mut chunk = root;
+    // Each step descends one tree level; more steps than chunks means a cycle.
+    for _ in 0..layout.num_blocks {
+        let page = directory_chunk(data, layout, chunk)?;
+        match page.get(0..4) {
+            Some(b"PMGI") => match find_in_pmgi(page, key)? {
+                Some(child) => chunk = child,
+                None => return Ok(None),
+            },
+            Some(b"PMGL") => return find_in_pmgl(page, key),
+            _ => return Err(ChmError::InvalidFormat("unexpected directory chunk signature")),
+        }
+    }
+    Err(ChmError::InvalidFormat("PMGI index tree does not terminate"))
+}
+
 pub(crate) fn parse_directory_entries(
     data: &[u8],
     blocks_offset: usize,
@@ -21,8 +169,11 @@ pub(crate) fn parse_directory_entries(
     let mut entries = Vec::new();
 
     for block_idx in 0..num_blocks {
-        let base = blocks_offset + block_idx * block_len;
-        let page = data.get(base..base + block_len).ok_or(ChmError::OutOfBounds)?;
+        let page = block_idx
+            .checked_mul(block_len)
+            .and_then(|offset| offset.checked_add(blocks_offset))
+            .and_then(|base| data.get(base..base.checked_add(block_len)?))
+            .ok_or(ChmError::OutOfBounds)?;
         if page.get(0..4) != Some(b"PMGL") {
             continue;
         }
@@ -30,11 +181,11 @@ pub(crate) fn parse_directory_entries(
         if free_space > block_len {
             continue;
         }
-        let mut pos = 0x14usize;
+        let mut pos = PMGL_ENTRIES_OFFSET;
         let end = block_len - free_space;
         while pos < end {
-            let name_len = parse_cword(page, &mut pos)? as usize;
-            if name_len == 0 || pos + name_len > end {
+            let name_len = usize::try_from(parse_cword(page, &mut pos)?).map_err(|_| ChmError::OutOfBounds)?;
+            if name_len == 0 || name_len > end.saturating_sub(pos) {
                 break;
             }
             let name = std::str::from_utf8(&page[pos..pos + name_len]).map_err(|_| ChmError::Utf8Path)?;
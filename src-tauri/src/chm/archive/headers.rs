@@ -1,4 +1,7 @@
-use super::ChmError;
+use super::{directory::DirectoryLayout, ChmError, ChmSource};
+
+const ITSF_MAX_HEADER_LEN: u64 = 0x60;
+const ITSP_HEADER_LEN: u64 = 0x54;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct ContainerLayout {
@@ -6,10 +9,25 @@ pub(crate) struct ContainerLayout {
     pub(crate) blocks_offset: usize,
     pub(crate) block_len: usize,
     pub(crate) num_blocks: usize,
+    pub(crate) index_root: Option<usize>,
+    pub(crate) first_listing: usize,
+}
+
+impl ContainerLayout {
+    pub(crate) fn directory(&self) -> DirectoryLayout {
+        DirectoryLayout {
+            blocks_offset: self.blocks_offset,
+            block_len: self.block_len,
+            num_blocks: self.num_blocks,
+            index_root: self.index_root,
+            first_listing: self.first_listing,
+        }
+    }
 }
 
 pub(crate) fn read_u32_le(buf: &[u8], off: usize) -> Result<u32, ChmError> {
-    let s = buf.get(off..off + 4).ok_or(ChmError::OutOfBounds)?;
+    let end = off.checked_add(4).ok_or(ChmError::OutOfBounds)?;
+    let s = buf.get(off..end).ok_or(ChmError::OutOfBounds)?;
     Ok(u32::from_le_bytes([s[0], s[1], s[2], s[3]]))
 }
 
@@ -18,13 +36,30 @@ pub(crate) fn read_i32_le(buf: &[u8], off: usize) -> Result<i32, ChmError> {
 }
 
 pub(crate) fn read_u64_le(buf: &[u8], off: usize) -> Result<u64, ChmError> {
-    let s = buf.get(off..off + 8).ok_or(ChmError::OutOfBounds)?;
+    let end = off.checked_add(8).ok_or(ChmError::OutOfBounds)?;
+    let s = buf.get(off..end).ok_or(ChmError::OutOfBounds)?;
     Ok(u64::from_le_bytes([
         s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7],
     ]))
 }
 
-pub(crate) fn parse_container_layout(data: &[u8]) -> Result<ContainerLayout, ChmError> {
+/// Read `len` bytes at `offset` of `source`, rejecting ranges past its end before allocating.
+pub(crate) fn read_source(source: &dyn ChmSource, offset: u64, len: u64) -> Result<Vec<u8>, ChmError> {
+    match offset.checked_add(len) {
+        Some(end) if end <= source.len() => {}
+        _ => return Err(ChmError::OutOfBounds),
+    }
+    let mut buf = vec![0u8; usize::try_from(len).map_err(|_| ChmError::OutOfBounds)?];
+    source.read_exact_at(offset, &mut buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => ChmError::OutOfBounds,
+        _ => ChmError::Io(e),
+    })?;
+    Ok(buf)
+}
+
+pub(crate) fn parse_container_layout(source: &dyn ChmSource) -> Result<ContainerLayout, ChmError> {
+    let data = read_source(source, 0, ITSF_MAX_HEADER_LEN.min(source.len()))?;
+    let data = data.as_slice();
     if data.get(0..4) != Some(b"ITSF") {
         return Err(ChmError::InvalidFormat("missing ITSF signature"));
     }
@@ -37,25 +72,31 @@ pub(crate) fn parse_container_layout(data: &[u8]) -> Result<ContainerLayout, Chm
         return Err(ChmError::InvalidFormat("invalid ITSF header length"));
     }
 
-    let dir_offset = read_u64_le(data, 0x48)? as usize;
-    let dir_len = read_u64_le(data, 0x50)? as usize;
+    let dir_offset = read_u64_le(data, 0x48)?;
+    let dir_len = read_u64_le(data, 0x50)?;
+    let dir_end = dir_offset
+        .checked_add(dir_len)
+        .ok_or(ChmError::InvalidFormat("directory range overflows"))?;
     let mut data_offset = if itsf_version == 3 {
         read_u64_le(data, 0x58)?
     } else {
-        (dir_offset + dir_len) as u64
+        dir_end
     };
     if data_offset == 0 {
-        data_offset = (dir_offset + dir_len) as u64;
+        data_offset = dir_end;
     }
+    let dir_offset = usize::try_from(dir_offset).map_err(|_| ChmError::OutOfBounds)?;
+    let dir_len = usize::try_from(dir_len).map_err(|_| ChmError::OutOfBounds)?;
 
-    let itsp = data
-        .get(dir_offset..dir_offset + 0x54)
-        .ok_or(ChmError::OutOfBounds)?;
+    let itsp = read_source(source, dir_offset as u64, ITSP_HEADER_LEN)?;
+    let itsp = itsp.as_slice();
     if itsp.get(0..4) != Some(b"ITSP") {
         return Err(ChmError::InvalidFormat("missing ITSP signature"));
     }
     let block_len = read_u32_le(itsp, 0x10)? as usize;
     let header_len = read_i32_le(itsp, 0x08)? as usize;
+    let index_root_raw = read_i32_le(itsp, 0x1c)?;
+    let first_listing_raw = read_u32_le(itsp, 0x20)? as usize;
     let num_blocks_raw = read_u32_le(itsp, 0x28)?;
     if block_len == 0 {
         return Err(ChmError::InvalidFormat("invalid directory block info"));
@@ -71,11 +112,23 @@ pub(crate) fn parse_container_layout(data: &[u8]) -> Result<ContainerLayout, Chm
     if num_blocks == 0 {
         return Err(ChmError::InvalidFormat("invalid directory block info"));
     }
+    let index_root = usize::try_from(index_root_raw)
+        .ok()
+        .filter(|root| *root < num_blocks);
+    let first_listing = if first_listing_raw < num_blocks {
+        first_listing_raw
+    } else {
+        0
+    };
 
     Ok(ContainerLayout {
         data_offset,
-        blocks_offset: dir_offset + header_len,
+        blocks_offset: dir_offset
+            .checked_add(header_len)
+            .ok_or(ChmError::InvalidFormat("directory range overflows"))?,
         block_len,
         num_blocks,
+        index_root,
+        first_listing,
     })
 }
@@ -0,0 +1,226 @@
+//! Byte-budgeted LRU cache shared by the CHM reader and the runtime caches.
+use std::collections::BTreeMap;
+
+/// Counters and occupancy of one cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    pub budget_bytes: usize,
+}
+
+#[derive(Debug)]
+struct Slot<V> {
+    value: V,
+    weight: usize,
+    tick: u64,
+}
+
+/// Least-recently-used cache bounded by the summed weight of its values and, optionally,
+/// by its number of values.
+///
+/// Recency is a monotonically increasing tick per access; the oldest tick is
+/// evicted first until the total weight and count fit again.
+#[derive(Debug)]
+pub struct LruCache<K, V> {
+    budget_bytes: usize,
+    max_entries: usize,
+    slots: BTreeMap<K, Slot<V>>,
+    recency: BTreeMap<u64, K>,
+    next_tick: u64,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl<K: Ord + Clone, V: Clone> LruCache<K, V> {
+    /// Create an empty cache holding at most `budget_bytes` of weighted values.
+    pub fn new(budget_bytes: usize) -> Self {
+        Self::with_entry_limit(budget_bytes, usize::MAX)
+    }
+
+    /// Create an empty cache that also holds at most `max_entries` values.
+    pub fn with_entry_limit(budget_bytes: usize, max_entries: usize) -> Self {
+        Self {
+            budget_bytes,
+            max_entries,
+            slots: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            next_tick: 0,
+            bytes: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    fn bump(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+
+    /// Return a clone of the cached value and mark it most recently used.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let tick = self.bump();
+        let Some(slot) = self.slots.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        self.recency.remove(&slot.tick);
+        slot.tick = tick;
+        self.recency.insert(tick, key.clone());
+        Some(slot.value.clone())
+    }
+
+    /// Insert or replace `key`, evicting least recently used values to stay within budget.
+    ///
+    /// Values heavier than the whole budget are not stored.
+    pub fn insert(&mut self, key: K, value: V, weight: usize) {
+        self.remove(&key);
+        if weight > self.budget_bytes || self.max_entries == 0 {
+            return;
+        }
+        while self.bytes + weight > self.budget_bytes || self.slots.len() >= self.max_entries {
+            if !self.evict_oldest(None) {
+                break;
+            }
+        }
+        let tick = self.bump();
+        self.recency.insert(tick, key.clone());
+        self.slots.insert(key, Slot { value, weight, tick });
+        self.bytes += weight;
+    }
+
+    /// Update the weight of a cached `key`, evicting least recently used values to fit.
+    ///
+    /// A value that has grown heavier than the whole budget is dropped.
+    pub fn reweigh(&mut self, key: &K, weight: usize) {
+        let Some(slot) = self.slots.get_mut(key) else {
+            return;
+        };
+        self.bytes = self.bytes - slot.weight + weight;
+        slot.weight = weight;
+        let tick = slot.tick;
+        if weight > self.budget_bytes {
+            self.remove(key);
+            return;
+        }
+        while self.bytes > self.budget_bytes {
+            if !self.evict_oldest(Some(tick)) {
+                break;
+            }
+        }
+    }
+
+    /// Evict the least recently used value other than the one at `keep`; false when none is left.
+    fn evict_oldest(&mut self, keep: Option<u64>) -> bool {
+        let Some(tick) = self.recency.keys().copied().find(|tick| Some(*tick) != keep) else {
+            return false;
+        };
+        if let Some(oldest) = self.recency.remove(&tick) {
+            if let Some(slot) = self.slots.remove(&oldest) {
+                self.bytes -= slot.weight;
+                self.evictions += 1;
+            }
+        }
+        true
+    }
+
+    /// Drop `key` without counting an eviction.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.slots.remove(key)?;
+        self.recency.remove(&slot.tick);
+        self.bytes -= slot.weight;
+        Some(slot.value)
+    }
+
+    /// Drop every key matching `pred` without counting evictions; returns how many were dropped.
+    pub fn remove_matching(&mut self, mut pred: impl FnMut(&K) -> bool) -> usize {
+        let keys = self.slots.keys().filter(|k| pred(k)).cloned().collect::<Vec<_>>();
+        for key in &keys {
+            self.remove(key);
+        }
+        keys.len()
+    }
+
+    /// Snapshot of counters and current occupancy.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            entries: self.slots.len(),
+            bytes: self.bytes,
+            budget_bytes: self.budget_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_by_weight() {
+        let mut cache = LruCache::new(10);
+        cache.insert("a", 1, 4);
+        cache.insert("b", 2, 4);
+        assert_eq!(cache.get(&"a"), Some(1));
+        cache.insert("c", 3, 4);
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"c"), Some(3));
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 3,
+                misses: 1,
+                evictions: 1,
+                entries: 2,
+                bytes: 8,
+                budget_bytes: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn replacing_and_oversized_values_keep_accounting_exact() {
+        let mut cache = LruCache::new(10);
+        cache.insert("a", 1, 6);
+        cache.insert("a", 2, 3);
+        assert_eq!(cache.stats().bytes, 3);
+        cache.insert("huge", 9, 11);
+        assert_eq!(cache.get(&"huge"), None);
+        assert_eq!(cache.get(&"a"), Some(2));
+        assert_eq!(cache.remove(&"a"), Some(2));
+        cache.insert("zip1::a", 1, 1);
+        cache.insert("zip1::b", 2, 1);
+        assert_eq!(cache.remove_matching(|k| k.starts_with("zip1::")), 2);
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.evictions), (0, 0, 0));
+    }
+
+    #[test]
+    fn entry_limit_and_reweighing_evict_least_recently_used() {
+        let mut cache = LruCache::with_entry_limit(100, 2);
+        cache.insert("a", 1, 1);
+        cache.insert("b", 2, 1);
+        cache.insert("c", 3, 1);
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.stats().entries, 2);
+
+        assert_eq!(cache.get(&"b"), Some(2));
+        cache.reweigh(&"b", 100);
+        assert_eq!(cache.get(&"c"), None);
+        assert_eq!(cache.get(&"b"), Some(2));
+        cache.reweigh(&"b", 101);
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.evictions), (0, 0, 2));
+    }
+}
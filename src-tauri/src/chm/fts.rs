@@ -0,0 +1,559 @@
+//! Reader for the compiler-generated full-text index (`$FIftiMain`).
+//!
+//! The index is a B-tree of prefix-compressed words. Each leaf entry points at a
+//! word location code (WLC) list that stores topic numbers and in-topic word positions
+//! as scale/root (`s`/`r`) encoded bit strings.
+use encoding_rs::Encoding;
+
+use super::archive::{ChmArchive, ChmError};
+
+pub const FTS_FILE: &str = "/$FIftiMain";
+
+const HEADER_LEN: u64 = 0x32;
+const LEAF_ENTRIES_OFFSET: usize = 8;
+const INDEX_ENTRIES_OFFSET: usize = 2;
+const MAX_NODE_LEN: u32 = 0x10000;
+
+/// Scale/root pair of one `s`/`r` encoded integer stream.
+#[derive(Debug, Clone, Copy)]
+struct SrParams {
+    scale: u8,
+    root: u8,
+}
+
+/// Parsed `$FIftiMain` header; nodes and WLC lists are read on demand.
+#[derive(Debug, Clone)]
+pub struct FullTextIndex {
+    document_count: u32,
+    object_len: u64,
+    root: u32,
+    depth: u16,
+    node_len: u32,
+    doc_index: SrParams,
+    code_count: SrParams,
+    location_codes: SrParams,
+    encoding: &'static Encoding,
+}
+
+/// One indexed word together with every topic it occurs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMatch {
+    pub word: String,
+    pub in_title: bool,
+    pub topics: Vec<TopicHit>,
+}
+
+/// Topic number (`#TOPICS` row) and word positions inside that topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicHit {
+    pub topic: u32,
+    pub locations: Vec<u32>,
+}
+
+/// Leaf entry fields needed to fetch its WLC list.
+struct LeafEntry {
+    in_title: bool,
+    wlc_count: u64,
+    wlc_offset: u32,
+    wlc_size: u64,
+}
+
+/// MSB-first bit cursor used by the WLC lists.
+struct BitReader<'a> {
+    data: &'a [u8],
+    byte: usize,
+    bit: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, byte: 0, bit: 7 }
+    }
+
+    fn read_bit(&mut self) -> Option<bool> {
+        let value = (*self.data.get(self.byte)? >> self.bit) & 1;
+        if self.bit == 0 {
+            self.byte += 1;
+            self.bit = 7;
+        } else {
+            self.bit -= 1;
+        }
+        Some(value == 1)
+    }
+
+    /// Skip to the next byte boundary; every document record starts byte aligned.
+    fn align(&mut self) {
+        if self.bit != 7 {
+            self.byte += 1;
+            self.bit = 7;
+        }
+    }
+
+    /// Decode one scale-2 `s`/`r` integer: a unary bit count, then the low bits.
+    fn read_sr(&mut self, params: SrParams) -> Option<u64> {
+        if params.scale != 2 {
+            return None;
+        }
+        let mut count = 0u32;
+        while self.read_bit()? {
+            count += 1;
+            if count > 64 {
+                return None;
+            }
+        }
+        let n_bits = u32::from(params.root) + count.saturating_sub(1);
+        if n_bits > 63 {
+            return None;
+        }
+        let mut value = 0u64;
+        for _ in 0..n_bits {
+            value = (value << 1) | u64::from(self.read_bit()?);
+        }
+        if count > 0 {
+            value |= 1 << n_bits;
+        }
+        Some(value)
+    }
+}
+
+fn read_u16_at(buf: &[u8], off: usize) -> Result<u16, ChmError> {
+    let s = buf.get(off..off + 2).ok_or(ChmError::OutOfBounds)?;
+    Ok(u16::from_le_bytes([s[0], s[1]]))
+}
+
+fn read_u32_at(buf: &[u8], off: usize) -> Result<u32, ChmError> {
+    let s = buf.get(off..off + 4).ok_or(ChmError::OutOfBounds)?;
+    Ok(u32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+}
+
+/// Little-endian base-128 integer used inside leaf entries.
+fn read_fts_encint(buf: &[u8], pos: &mut usize) -> Result<u64, ChmError> {
+    let mut value = 0u64;
+    let mut shift = 0u32;
+    loop {
+        let b = *buf.get(*pos).ok_or(ChmError::OutOfBounds)?;
+        *pos += 1;
+        if shift > 63 {
+            return Err(ChmError::InvalidFormat("oversized $FIftiMain integer"));
+        }
+        value |= u64::from(b & 0x7f) << shift;
+        shift += 7;
+        if b & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Apply one prefix-compressed word (`len`, `pos`, `len - 1` bytes) to `word`.
+fn read_word(node: &[u8], pos: &mut usize, word: &mut Vec<u8>) -> Result<(), ChmError> {
+    let word_len = *node.get(*pos).ok_or(ChmError::OutOfBounds)? as usize;
+    let prefix = *node.get(*pos + 1).ok_or(ChmError::OutOfBounds)? as usize;
+    if word_len == 0 || prefix > word.len() {
+        return Err(ChmError::InvalidFormat("invalid $FIftiMain word prefix"));
+    }
+    let start = *pos + 2;
+    let suffix = node.get(start..start + word_len - 1).ok_or(ChmError::OutOfBounds)?;
+    word.truncate(prefix);
+    word.extend_from_slice(suffix);
+    *pos = start + word_len - 1;
+    Ok(())
+}
+
+/// Read the fields following a leaf word: title flag, WLC count, offset, unknown u16 and size.
+fn read_leaf_entry(node: &[u8], pos: &mut usize) -> Result<LeafEntry, ChmError> {
+    let in_title = *node.get(*pos).ok_or(ChmError::OutOfBounds)? != 0;
+    *pos += 1;
+    let wlc_count = read_fts_encint(node, pos)?;
+    let wlc_offset = read_u32_at(node, *pos)?;
+    *pos += 6;
+    let wlc_size = read_fts_encint(node, pos)?;
+    Ok(LeafEntry {
+        in_title,
+        wlc_count,
+        wlc_offset,
+        wlc_size,
+    })
+}
+
+impl FullTextIndex {
+    /// Parse the `$FIftiMain` header of `chm`, or `None` when the CHM was compiled without one.
+    ///
+    /// `encoding` must be the CHM codepage (see `SystemInfo::encoding`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the header is truncated or uses an unsupported encoding scale.
+    pub fn open(chm: &ChmArchive, encoding: &'static Encoding) -> Result<Option<Self>, ChmError> {
+        let Some(entry) = chm.find_entry(FTS_FILE)? else {
+            return Ok(None);
+        };
+        let header = chm.read_object_range(FTS_FILE, 0, HEADER_LEN)?;
+        if header.len() < HEADER_LEN as usize {
+            return Err(ChmError::InvalidFormat("truncated $FIftiMain header"));
+        }
+        let sr = |off: usize| SrParams {
+            scale: header[off],
+            root: header[off + 1],
+        };
+        let index = Self {
+            document_count: read_u32_at(&header, 0x04)?,
+            object_len: entry.length,
+            root: read_u32_at(&header, 0x14)?,
+            depth: read_u16_at(&header, 0x18)?,
+            node_len: read_u32_at(&header, 0x2e)?,
+            doc_index: sr(0x1e),
+            code_count: sr(0x20),
+            location_codes: sr(0x22),
+            encoding,
+        };
+        if [index.doc_index, index.code_count, index.location_codes]
+            .iter()
+            .any(|p| p.scale != 2)
+        {
+            return Err(ChmError::InvalidFormat("unsupported $FIftiMain scale"));
+        }
+        if index.depth == 0 || !(LEAF_ENTRIES_OFFSET as u32..=MAX_NODE_LEN).contains(&index.node_len) {
+            return Err(ChmError::InvalidFormat("invalid $FIftiMain tree header"));
+        }
+        Ok(Some(index))
+    }
+
+    /// Number of topics the compiler indexed.
+    pub fn document_count(&self) -> u32 {
+        self.document_count
+    }
+
+    /// Look up `term` (case-insensitive) and return matching words with their topic hits.
+    ///
+    /// With `whole_word` unset every indexed word starting with `term` matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a tree node or WLC list is truncated or malformed.
+    pub fn search(&self, chm: &ChmArchive, term: &str, whole_word: bool) -> Result<Vec<WordMatch>, ChmError> {
+        let lowered = term.trim().to_lowercase();
+        if lowered.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (key, _, unmappable) = self.encoding.encode(&lowered);
+        if unmappable {
+            return Ok(Vec::new());
+        }
+        let Some(mut offset) = self.find_leaf(chm, &key)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut out = Vec::new();
+        // A well-formed leaf chain is shorter than the number of nodes in the object.
+        for _ in 0..=self.object_len / u64::from(self.node_len) {
+            let node = self.read_node(chm, offset)?;
+            let next = read_u32_at(&node, 0)?;
+            let end = node.len().saturating_sub(read_u16_at(&node, 6)? as usize);
+            let mut pos = LEAF_ENTRIES_OFFSET;
+            let mut word = Vec::new();
+            while pos < end {
+                read_word(&node, &mut pos, &mut word)?;
+                let entry = read_leaf_entry(&node, &mut pos)?;
+
+                let matched = if whole_word {
+                    word == *key
+                } else {
+                    word.starts_with(&key)
+                };
+                if matched {
+                    out.push(WordMatch {
+                        word: self.encoding.decode(&word).0.into_owned(),
+                        in_title: entry.in_title,
+                        topics: self.read_wlc(chm, &entry)?,
+                    });
+                    if whole_word {
+                        return Ok(out);
+                    }
+                } else if word.as_slice() > &*key {
+                    return Ok(out);
+                }
+            }
+            if next == 0 {
+                break;
+            }
+            offset = next;
+        }
+        Ok(out)
+    }
+
+    fn read_node(&self, chm: &ChmArchive, offset: u32) -> Result<Vec<u8>, ChmError> {
+        let node = chm.read_object_range(FTS_FILE, u64::from(offset), u64::from(self.node_len))?;
+        if node.len() < self.node_len as usize {
+            return Err(ChmError::OutOfBounds);
+        }
+        Ok(node)
+    }
+
+    /// Descend the index nodes to the leaf whose last word is not before `key`.
+    fn find_leaf(&self, chm: &ChmArchive, key: &[u8]) -> Result<Option<u32>, ChmError> {
+        let mut offset = self.root;
+        for _ in 1..self.depth {
+            let node = self.read_node(chm, offset)?;
+            let end = node.len().saturating_sub(read_u16_at(&node, 0)? as usize);
+            let mut pos = INDEX_ENTRIES_OFFSET;
+            let mut word = Vec::new();
+            let mut child = None;
+            while pos < end {
+                read_word(&node, &mut pos, &mut word)?;
+                if key <= word.as_slice() {
+                    child = Some(read_u32_at(&node, pos)?);
+                    break;
+                }
+                pos += 6;
+            }
+            match child {
+                Some(next) if next != offset => offset = next,
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(offset))
+    }
+
+    /// Decode a WLC list: per topic a delta topic number, a location count and delta locations.
+    fn read_wlc(&self, chm: &ChmArchive, entry: &LeafEntry) -> Result<Vec<TopicHit>, ChmError> {
+        let data = chm.read_object_range(FTS_FILE, u64::from(entry.wlc_offset), entry.wlc_size)?;
+        let mut bits = BitReader::new(&data);
+        let invalid = || ChmError::InvalidFormat("invalid $FIftiMain location codes");
+        let mut topic = 0u64;
+        let mut hits = Vec::new();
+        for _ in 0..entry.wlc_count.min(data.len() as u64 * 8) {
+            bits.align();
+            topic = topic.checked_add(bits.read_sr(self.doc_index).ok_or_else(invalid)?).ok_or_else(invalid)?;
+            let count = bits.read_sr(self.code_count).ok_or_else(invalid)?;
+            let mut location = 0u64;
+            let mut locations = Vec::new();
+            for _ in 0..count.min(data.len() as u64 * 8) {
+                location = location
+                    .checked_add(bits.read_sr(self.location_codes).ok_or_else(invalid)?)
+                    .ok_or_else(invalid)?;
+                locations.push(u32::try_from(location).map_err(|_| invalid())?);
+            }
+            hits.push(TopicHit {
+                topic: u32::try_from(topic).map_err(|_| invalid())?,
+                locations,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chm::test_support::build_indexed_container;
+
+    const NODE_LEN: usize = 0x100;
+    const SR_ROOT: u8 = 2;
+
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        used: u8,
+    }
+
+    impl BitWriter {
+        fn push(&mut self, bit: bool) {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            if bit {
+                *self.bytes.last_mut().expect("byte") |= 0x80 >> self.used;
+            }
+            self.used = (self.used + 1) % 8;
+        }
+
+        fn align(&mut self) {
+            self.used = 0;
+        }
+
+        fn push_sr(&mut self, value: u64, root: u8) {
+            let root = u32::from(root);
+            if value < 1 << root {
+                self.push(false);
+                for i in (0..root).rev() {
+                    self.push(value >> i & 1 == 1);
+                }
+                return;
+            }
+            let n_bits = 63 - value.leading_zeros();
+            for _ in 0..n_bits - root + 1 {
+                self.push(true);
+            }
+            self.push(false);
+            for i in (0..n_bits).rev() {
+                self.push(value >> i & 1 == 1);
+            }
+        }
+    }
+
+    fn fts_encint(mut v: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let b = (v & 0x7f) as u8;
+            v >>= 7;
+            if v == 0 {
+                out.push(b);
+                return out;
+            }
+            out.push(b | 0x80);
+        }
+    }
+
+    fn wlc(topics: &[(u64, &[u64])]) -> Vec<u8> {
+        let mut bits = BitWriter::default();
+        let mut prev = 0;
+        for (topic, locations) in topics {
+            bits.align();
+            bits.push_sr(topic - prev, SR_ROOT);
+            prev = *topic;
+            bits.push_sr(locations.len() as u64, SR_ROOT);
+            let mut last = 0;
+            for loc in *locations {
+                bits.push_sr(loc - last, SR_ROOT);
+                last = *loc;
+            }
+        }
+        bits.bytes
+    }
+
+    fn push_word(node: &mut Vec<u8>, prev: &str, word: &str) {
+        let shared = prev.bytes().zip(word.bytes()).take_while(|(a, b)| a == b).count();
+        node.push((word.len() - shared + 1) as u8);
+        node.push(shared as u8);
+        node.extend_from_slice(&word.as_bytes()[shared..]);
+    }
+
+    fn finish_node(mut node: Vec<u8>, free_at: usize) -> Vec<u8> {
+        let free = (NODE_LEN - node.len()) as u16;
+        node[free_at..free_at + 2].copy_from_slice(&free.to_le_bytes());
+        node.resize(NODE_LEN, 0);
+        node
+    }
+
+    /// Word and its `(topic, locations)` postings.
+    type Posting<'a> = (&'a str, &'a [(u64, &'a [u64])]);
+
+    /// Build a two-level `$FIftiMain`: one index node over chained leaves.
+    fn build_fts(leaves: &[&[Posting<'_>]]) -> Vec<u8> {
+        let header_len = 0x400;
+        let index_off = header_len + leaves.len() * NODE_LEN;
+        let mut wlc_data = Vec::new();
+        let wlc_base = index_off + NODE_LEN;
+
+        let mut nodes = Vec::new();
+        let mut index = vec![0u8; INDEX_ENTRIES_OFFSET];
+        let mut prev_last = String::new();
+        for (i, words) in leaves.iter().enumerate() {
+            let next = if i + 1 < leaves.len() {
+                header_len + (i + 1) * NODE_LEN
+            } else {
+                0
+            };
+            let mut node = (next as u32).to_le_bytes().to_vec();
+            node.extend_from_slice(&[0; 4]);
+            let mut prev = String::new();
+            for (word, topics) in *words {
+                push_word(&mut node, &prev, word);
+                node.push(u8::from(word.starts_with('h')));
+                let list = wlc(topics);
+                node.extend(fts_encint(topics.len() as u64));
+                node.extend_from_slice(&((wlc_base + wlc_data.len()) as u32).to_le_bytes());
+                node.extend_from_slice(&[0; 2]);
+                node.extend(fts_encint(list.len() as u64));
+                wlc_data.extend(list);
+                prev = word.to_string();
+            }
+            nodes.push(finish_node(node, 6));
+            push_word(&mut index, &prev_last, &prev);
+            index.extend_from_slice(&((header_len + i * NODE_LEN) as u32).to_le_bytes());
+            index.extend_from_slice(&[0; 2]);
+            prev_last = prev;
+        }
+
+        let mut out = vec![0u8; header_len];
+        out[0x04..0x08].copy_from_slice(&7u32.to_le_bytes());
+        out[0x14..0x18].copy_from_slice(&(index_off as u32).to_le_bytes());
+        out[0x18..0x1a].copy_from_slice(&2u16.to_le_bytes());
+        out[0x1e..0x24].copy_from_slice(&[2, SR_ROOT, 2, SR_ROOT, 2, SR_ROOT]);
+        out[0x2e..0x32].copy_from_slice(&(NODE_LEN as u32).to_le_bytes());
+        for node in nodes {
+            out.extend(node);
+        }
+        out.extend(finish_node(index, 0));
+        out.extend(wlc_data);
+        out
+    }
+
+    #[test]
+    fn sr_codes_round_trip() {
+        let values = [0u64, 1, 3, 4, 5, 17, 255, 4096, 1 << 40];
+        let mut bits = BitWriter::default();
+        for v in values {
+            bits.push_sr(v, SR_ROOT);
+        }
+        let mut reader = BitReader::new(&bits.bytes);
+        let params = SrParams {
+            scale: 2,
+            root: SR_ROOT,
+        };
+        for v in values {
+            assert_eq!(reader.read_sr(params), Some(v));
+        }
+    }
+
+    #[test]
+    fn searches_words_across_leaves() {
+        let fts = build_fts(&[
+            &[("aal", &[(0, &[3])]), ("haus", &[(1, &[0, 9]), (6, &[2])])],
+            &[("hausen", &[(2, &[5])]), ("zug", &[(4, &[1, 2, 3])])],
+        ]);
+        let bytes = build_indexed_container(&[(FTS_FILE, &fts)], 0x200);
+        let chm = ChmArchive::open(bytes).expect("open synthetic chm");
+        let index = FullTextIndex::open(&chm, encoding_rs::WINDOWS_1252)
+            .expect("parse header")
+            .expect("index present");
+        assert_eq!(index.document_count(), 7);
+
+        let exact = index.search(&chm, "Haus", true).expect("exact search");
+        assert_eq!(
+            exact,
+            vec![WordMatch {
+                word: "haus".to_string(),
+                in_title: true,
+                topics: vec![
+                    TopicHit {
+                        topic: 1,
+                        locations: vec![0, 9],
+                    },
+                    TopicHit {
+                        topic: 6,
+                        locations: vec![2],
+                    },
+                ],
+            }]
+        );
+
+        let prefix = index.search(&chm, "haus", false).expect("prefix search");
+        let words = prefix.iter().map(|m| m.word.as_str()).collect::<Vec<_>>();
+        assert_eq!(words, vec!["haus", "hausen"]);
+
+        let zug = index.search(&chm, "zug", true).expect("second leaf");
+        assert_eq!(zug[0].topics[0].locations, vec![1, 2, 3]);
+        assert!(index.search(&chm, "aa", true).expect("miss").is_empty());
+        assert!(index.search(&chm, "zzz", false).expect("past end").is_empty());
+    }
+
+    #[test]
+    fn missing_index_is_none() {
+        let bytes = build_indexed_container(&[("/index.htm", b"<p></p>")], 0x200);
+        let chm = ChmArchive::open(bytes).expect("open synthetic chm");
+        assert!(FullTextIndex::open(&chm, encoding_rs::WINDOWS_1252)
+            .expect("no error")
+            .is_none());
+    }
+}
@@ -1,3 +1,7 @@
+mod encoder;
+
+pub use encoder::{compress_block, LzxBlockMode, LzxEncoder, LZX_FRAME_LEN};
+
 const LZX_MIN_MATCH: usize = 2;
 const LZX_NUM_CHARS: usize = 256;
 
@@ -236,6 +240,10 @@ fn read_huffsym(
         }
     }
     let n = *lentable.get(i).ok_or(DECR_ILLEGALDATA)? as u32;
+    // Lengths and table disagree only after a failed table rebuild left stale state.
+    if n > br.bitsleft {
+        return Err(DECR_ILLEGALDATA);
+    }
     br.remove_bits(n);
     Ok(i)
 }
@@ -294,7 +302,7 @@ fn lzx_read_lens(
                 pretree_len,
                 pretree_table,
             )? as i32;
-            if x >= lens.len() {
+            if x >= lens.len() || z2 > 16 {
                 return Err(DECR_ILLEGALDATA);
             }
             let mut v = lens[x] as i32 - z2;
@@ -680,6 +688,9 @@ pub fn decompress_block(
     if expected_out_len == 0 {
         return Ok(Vec::new());
     }
+    if expected_out_len > state.window_size {
+        return Err(format!("frame of {expected_out_len} bytes exceeds the LZX window"));
+    }
 
     let mut br = BitReader::new(input);
 
@@ -774,10 +785,11 @@ fn apply_e8_transform(state: &mut LzxState, out: &mut [u8]) {
         for i in 0..last {
             if out[i] == 0xE8 {
                 let abs_off = i32::from_le_bytes([out[i + 1], out[i + 2], out[i + 3], out[i + 4]]);
+                // Wrapping matches the reference decoder's 32-bit arithmetic on hostile offsets.
                 let rel_off = if abs_off >= -curpos && abs_off < filesize {
-                    abs_off - curpos
+                    abs_off.wrapping_sub(curpos)
                 } else {
-                    abs_off + filesize
+                    abs_off.wrapping_add(filesize)
                 };
                 let b = rel_off.to_le_bytes();
                 out[i + 1] = b[0];
@@ -790,3 +802,69 @@ fn apply_e8_transform(state: &mut LzxState, out: &mut [u8]) {
     }
     state.intel_curpos = state.intel_curpos.saturating_add(out.len() as i32);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    fn sample_frames() -> Vec<Vec<u8>> {
+        let text = "der Bahnhof 기차역 die Bahnhöfe 역들 ".repeat(2000).into_bytes();
+        let mut encoder = LzxEncoder::new(16).expect("encoder").with_block_mode(LzxBlockMode::Aligned);
+        text.chunks(LZX_FRAME_LEN)
+            .take(3)
+            .map(|frame| compress_block(&mut encoder, frame).expect("compress"))
+            .collect()
+    }
+
+    #[test]
+    fn oversized_frames_and_hostile_e8_offsets_are_errors_not_panics() {
+        let mut state = LzxState::new(15).expect("state");
+        assert!(decompress_block(&mut state, &[0; 64], 0x8001).is_err());
+
+        let mut state = LzxState::new(16).expect("state");
+        state.intel_started = true;
+        state.intel_filesize = i32::MAX;
+        state.intel_curpos = i32::MAX - 4;
+        let mut out = vec![0u8; 16];
+        out[0] = 0xE8;
+        out[1..5].copy_from_slice(&i32::MAX.to_le_bytes());
+        apply_e8_transform(&mut state, &mut out);
+        assert_eq!(&out[1..5], &i32::MAX.wrapping_add(i32::MAX).to_le_bytes());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn arbitrary_blocks_never_panic(
+            window_bits in 15u8..=21,
+            blocks in prop::collection::vec((prop::collection::vec(any::<u8>(), 0..256), 1usize..=LZX_FRAME_LEN), 1..4),
+        ) {
+            let mut state = LzxState::new(window_bits).expect("state");
+            for (input, out_len) in blocks {
+                let _ = decompress_block(&mut state, &input, out_len);
+            }
+        }
+
+        #[test]
+        fn corrupted_frames_never_panic(
+            flips in prop::collection::vec((any::<Index>(), any::<Index>(), 0u8..8), 1..8),
+        ) {
+            let mut frames = sample_frames();
+            for (frame, at, bit) in flips {
+                let frame = frame.get_mut(&mut frames);
+                let i = at.index(frame.len());
+                frame[i] ^= 1 << bit;
+            }
+            let mut state = LzxState::new(16).expect("state");
+            for frame in frames {
+                let mut padded = frame;
+                padded.extend_from_slice(&[0, 0]);
+                // Keep decoding after failures to reach the partially rebuilt tables.
+                let _ = decompress_block(&mut state, &padded, LZX_FRAME_LEN);
+            }
+        }
+    }
+}
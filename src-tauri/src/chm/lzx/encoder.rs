@@ -0,0 +1,624 @@
+//! LZX encoder producing frames that [`super::decompress_block`] reads back.
+//!
+//! Every frame is emitted as one verbatim or aligned block and padded to a 16-bit
+//! boundary, so each CHM block starts on its own byte offset in the content stream.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{
+    EXTRA_BITS, LZX_ALIGNED_NUM_ELEMENTS, LZX_BLOCKTYPE_ALIGNED, LZX_BLOCKTYPE_VERBATIM, LZX_MIN_MATCH,
+    LZX_NUM_CHARS, LZX_NUM_PRIMARY_LENGTHS, LZX_NUM_SECONDARY_LENGTHS, LZX_PRETREE_NUM_ELEMENTS,
+    POSITION_BASE,
+};
+
+/// Uncompressed bytes per LZX frame (one CHM block).
+pub const LZX_FRAME_LEN: usize = 0x8000;
+
+const LZX_MAX_MATCH: usize = LZX_MIN_MATCH + LZX_NUM_PRIMARY_LENGTHS + LZX_NUM_SECONDARY_LENGTHS - 1;
+const MAIN_TREE_MAX_BITS: u8 = 16;
+const PRETREE_MAX_BITS: u8 = 15;
+const ALIGNED_MAX_BITS: u8 = 7;
+const ALIGNED_TREE_COST: u64 = 3 * LZX_ALIGNED_NUM_ELEMENTS as u64;
+
+const HASH_BITS: u32 = 15;
+const MAX_CHAIN: usize = 64;
+const LAZY_LIMIT: usize = 32;
+const NO_POS: usize = usize::MAX;
+
+/// Block type chosen for each frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LzxBlockMode {
+    /// Pick whichever of verbatim and aligned encodes the frame smaller.
+    #[default]
+    Auto,
+    Verbatim,
+    Aligned,
+}
+
+/// Encoder state carried across the frames of one reset interval.
+#[derive(Debug, Clone)]
+pub struct LzxEncoder {
+    window_size: usize,
+    main_elements: usize,
+    block_mode: LzxBlockMode,
+    header_written: bool,
+    repeats: [usize; 3],
+    main_lens: Vec<u8>,
+    length_lens: Vec<u8>,
+    /// Bytes since the last reset, trimmed to one window before the current frame.
+    history: Vec<u8>,
+    /// Stream position of `history[0]`.
+    base: usize,
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Literal(u8),
+    Match { len: usize, slot: usize, extra: u32 },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    len: usize,
+    slot: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    /// Append the low `n` bits of `value`, most significant first, in 16-bit little-endian words.
+    fn write(&mut self, value: u32, n: u32) {
+        if n == 0 {
+            return;
+        }
+        self.acc = (self.acc << n) | (u64::from(value) & ((1u64 << n) - 1));
+        self.bits += n;
+        while self.bits >= 16 {
+            let word = (self.acc >> (self.bits - 16)) as u16;
+            self.out.extend_from_slice(&word.to_le_bytes());
+            self.bits -= 16;
+            self.acc &= (1u64 << self.bits) - 1;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.write(0, 16 - self.bits);
+        }
+        self.out
+    }
+}
+
+/// Canonical Huffman code in the bit order `make_decode_table` expects.
+#[derive(Debug)]
+struct HuffmanCode {
+    lens: Vec<u8>,
+    codes: Vec<u32>,
+}
+
+impl HuffmanCode {
+    fn build(freqs: &[u32], max_bits: u8) -> Self {
+        let lens = huffman_lengths(freqs, max_bits);
+        let codes = canonical_codes(&lens);
+        Self { lens, codes }
+    }
+
+    fn write(&self, bw: &mut BitWriter, symbol: usize) {
+        bw.write(self.codes[symbol], u32::from(self.lens[symbol]));
+    }
+
+    fn cost(&self, symbol: usize) -> u64 {
+        u64::from(self.lens[symbol])
+    }
+}
+
+/// Depth of each leaf in a Huffman tree over `weights` (at least two leaves).
+fn tree_depths(weights: &[u64]) -> Vec<u8> {
+    let leaves = weights.len();
+    let mut parent = vec![0usize; 2 * leaves - 1];
+    let mut heap = weights
+        .iter()
+        .enumerate()
+        .map(|(i, w)| Reverse((*w, i)))
+        .collect::<BinaryHeap<_>>();
+    let mut next = leaves;
+    while let (Some(Reverse((wa, a))), Some(Reverse((wb, b)))) = (heap.pop(), heap.pop()) {
+        parent[a] = next;
+        parent[b] = next;
+        heap.push(Reverse((wa + wb, next)));
+        next += 1;
+    }
+    // Parents always have larger ids than their children, so walk from the root down.
+    let mut depth = vec![0u8; next];
+    for node in (0..next - 1).rev() {
+        depth[node] = depth[parent[node]].saturating_add(1);
+    }
+    depth.truncate(leaves);
+    depth
+}
+
+/// Code lengths limited to `max_bits`, always describing a complete code unless no symbol is used.
+fn huffman_lengths(freqs: &[u32], max_bits: u8) -> Vec<u8> {
+    let mut lens = vec![0u8; freqs.len()];
+    let used = (0..freqs.len()).filter(|&s| freqs[s] > 0).collect::<Vec<_>>();
+    match used.as_slice() {
+        [] => return lens,
+        [only] => {
+            // A lone symbol still needs a complete code; pair it with an unused one.
+            lens[*only] = 1;
+            lens[usize::from(*only == 0)] = 1;
+            return lens;
+        }
+        _ => {}
+    }
+    let mut weights = used.iter().map(|&s| u64::from(freqs[s])).collect::<Vec<_>>();
+    loop {
+        let depths = tree_depths(&weights);
+        if depths.iter().all(|&d| d <= max_bits) {
+            for (&s, d) in used.iter().zip(depths) {
+                lens[s] = d;
+            }
+            return lens;
+        }
+        // Flatten the distribution until the tree fits; all-ones weights always do.
+        for w in &mut weights {
+            *w = (*w >> 1) | 1;
+        }
+    }
+}
+
+fn canonical_codes(lens: &[u8]) -> Vec<u32> {
+    let mut bl_count = [0u32; 17];
+    for &len in lens.iter().filter(|&&len| len > 0) {
+        bl_count[len as usize] += 1;
+    }
+    let mut next_code = [0u32; 17];
+    let mut code = 0u32;
+    for bits in 1..=16 {
+        code = (code + bl_count[bits - 1]) << 1;
+        next_code[bits] = code;
+    }
+    lens.iter()
+        .map(|&len| {
+            if len == 0 {
+                return 0;
+            }
+            let code = next_code[len as usize];
+            next_code[len as usize] += 1;
+            code
+        })
+        .collect()
+}
+
+/// Write `lens` as pretree-coded deltas against `prev`, the mirror of `lzx_read_lens`.
+fn write_lens(bw: &mut BitWriter, prev: &[u8], lens: &[u8]) {
+    let mut symbols = Vec::<(usize, u32, u32)>::new();
+    let mut x = 0usize;
+    while x < lens.len() {
+        if lens[x] == 0 {
+            let run = lens[x..].iter().take_while(|&&len| len == 0).count();
+            if run >= 20 {
+                let run = run.min(51);
+                symbols.push((18, (run - 20) as u32, 5));
+                x += run;
+                continue;
+            }
+            if run >= 4 {
+                let run = run.min(19);
+                symbols.push((17, (run - 4) as u32, 4));
+                x += run;
+                continue;
+            }
+        }
+        let delta = (usize::from(prev[x]) + 17 - usize::from(lens[x])) % 17;
+        symbols.push((delta, 0, 0));
+        x += 1;
+    }
+
+    let mut freqs = [0u32; LZX_PRETREE_NUM_ELEMENTS];
+    for (symbol, _, _) in &symbols {
+        freqs[*symbol] += 1;
+    }
+    let pretree = HuffmanCode::build(&freqs, PRETREE_MAX_BITS);
+    for &len in &pretree.lens {
+        bw.write(u32::from(len), 4);
+    }
+    for (symbol, extra, extra_bits) in symbols {
+        pretree.write(bw, symbol);
+        bw.write(extra, extra_bits);
+    }
+}
+
+fn hash3(bytes: &[u8]) -> usize {
+    let v = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+    (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+impl LzxEncoder {
+    /// Create an encoder for a `1 << window_bits` byte window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `window_bits` is outside the LZX range (15..=21).
+    pub fn new(window_bits: u8) -> Result<Self, String> {
+        if !(15..=21).contains(&window_bits) {
+            return Err(format!("unsupported LZX window bits: {window_bits}"));
+        }
+        let window_size = 1usize << window_bits;
+        let posn_slots = match window_bits {
+            20 => 42,
+            21 => 50,
+            _ => (window_bits as usize) << 1,
+        };
+        let main_elements = LZX_NUM_CHARS + (posn_slots << 3);
+        Ok(Self {
+            window_size,
+            main_elements,
+            block_mode: LzxBlockMode::Auto,
+            header_written: false,
+            repeats: [1; 3],
+            main_lens: vec![0; main_elements],
+            length_lens: vec![0; LZX_NUM_SECONDARY_LENGTHS],
+            history: Vec::new(),
+            base: 0,
+            head: vec![NO_POS; 1 << HASH_BITS],
+            prev: vec![NO_POS; window_size],
+        })
+    }
+
+    /// Use `mode` for every following frame.
+    pub fn with_block_mode(mut self, mode: LzxBlockMode) -> Self {
+        self.block_mode = mode;
+        self
+    }
+
+    /// Start a new reset interval, matching a fresh `LzxState` on the decoder side.
+    pub fn reset(&mut self) {
+        self.header_written = false;
+        self.repeats = [1; 3];
+        self.main_lens.fill(0);
+        self.length_lens.fill(0);
+        self.history.clear();
+        self.base = 0;
+        self.head.fill(NO_POS);
+        self.prev.fill(NO_POS);
+    }
+
+    fn byte_at(&self, pos: usize) -> u8 {
+        self.history[pos - self.base]
+    }
+
+    fn match_len(&self, earlier: usize, pos: usize, max_len: usize) -> usize {
+        (0..max_len)
+            .take_while(|&i| self.byte_at(earlier + i) == self.byte_at(pos + i))
+            .count()
+    }
+
+    fn insert(&mut self, pos: usize, end: usize) {
+        if pos + 3 > end {
+            return;
+        }
+        let at = pos - self.base;
+        let h = hash3(&self.history[at..at + 3]);
+        self.prev[pos & (self.window_size - 1)] = self.head[h];
+        self.head[h] = pos;
+    }
+
+    fn chain_match(&self, pos: usize, max_len: usize) -> Option<(usize, usize)> {
+        let at = pos - self.base;
+        let mut candidate = self.head[hash3(&self.history[at..at + 3])];
+        let mut best: Option<(usize, usize)> = None;
+        for _ in 0..MAX_CHAIN {
+            if candidate == NO_POS || candidate >= pos || pos - candidate > self.window_size - 3 {
+                break;
+            }
+            let len = self.match_len(candidate, pos, max_len);
+            if best.is_none_or(|(best_len, _)| len > best_len) {
+                best = Some((len, pos - candidate));
+                if len == max_len {
+                    break;
+                }
+            }
+            let next = self.prev[candidate & (self.window_size - 1)];
+            if next >= candidate {
+                break;
+            }
+            candidate = next;
+        }
+        best.filter(|(len, _)| *len >= 3)
+    }
+
+    fn best_match(&self, pos: usize, end: usize) -> Option<Candidate> {
+        let max_len = (end - pos).min(LZX_MAX_MATCH);
+        if max_len < LZX_MIN_MATCH {
+            return None;
+        }
+        let mut best: Option<Candidate> = None;
+        for (slot, &offset) in self.repeats.iter().enumerate() {
+            if offset > pos {
+                continue;
+            }
+            let len = self.match_len(pos - offset, pos, max_len);
+            if len >= LZX_MIN_MATCH && best.is_none_or(|b| len > b.len) {
+                best = Some(Candidate { len, slot, offset });
+            }
+        }
+        if max_len >= 3 {
+            if let Some((len, offset)) = self.chain_match(pos, max_len) {
+                // Repeat offsets cost no extra bits, so a new offset must win clearly.
+                if best.is_none_or(|b| len > b.len + 1) {
+                    let formatted = offset + 2;
+                    let slot = POSITION_BASE.partition_point(|&b| b as usize <= formatted) - 1;
+                    best = Some(Candidate { len, slot, offset });
+                }
+            }
+        }
+        best
+    }
+
+    fn push_match(&mut self, tokens: &mut Vec<Token>, candidate: Candidate) {
+        let extra = match candidate.slot {
+            0 => 0,
+            1 => {
+                self.repeats.swap(0, 1);
+                0
+            }
+            2 => {
+                self.repeats.swap(0, 2);
+                0
+            }
+            slot => {
+                self.repeats = [candidate.offset, self.repeats[0], self.repeats[1]];
+                (candidate.offset + 2 - POSITION_BASE[slot] as usize) as u32
+            }
+        };
+        tokens.push(Token::Match {
+            len: candidate.len,
+            slot: candidate.slot,
+            extra,
+        });
+    }
+
+    /// Greedy parse with one step of lazy evaluation.
+    fn tokenize(&mut self, frame: &[u8]) -> Vec<Token> {
+        if self.history.len() > self.window_size {
+            let drop = self.history.len() - self.window_size;
+            self.history.drain(..drop);
+            self.base += drop;
+        }
+        let start = self.base + self.history.len();
+        self.history.extend_from_slice(frame);
+        let end = start + frame.len();
+
+        let mut tokens = Vec::with_capacity(frame.len() / 2);
+        let mut pos = start;
+        while pos < end {
+            let found = self.best_match(pos, end);
+            self.insert(pos, end);
+            let Some(candidate) = found else {
+                tokens.push(Token::Literal(self.byte_at(pos)));
+                pos += 1;
+                continue;
+            };
+            if candidate.slot >= 3 && candidate.len < LAZY_LIMIT && pos + 1 < end {
+                if let Some(next) = self.best_match(pos + 1, end) {
+                    if next.len > candidate.len {
+                        tokens.push(Token::Literal(self.byte_at(pos)));
+                        pos += 1;
+                        continue;
+                    }
+                }
+            }
+            self.push_match(&mut tokens, candidate);
+            for p in pos + 1..pos + candidate.len {
+                self.insert(p, end);
+            }
+            pos += candidate.len;
+        }
+        tokens
+    }
+}
+
+/// Compress one frame of at most [`LZX_FRAME_LEN`] bytes as a single LZX block.
+///
+/// Frames of one reset interval must be passed in order; call [`LzxEncoder::reset`]
+/// at every interval boundary.
+///
+/// # Errors
+///
+/// Returns an error when the frame is longer than [`LZX_FRAME_LEN`].
+pub fn compress_block(encoder: &mut LzxEncoder, frame: &[u8]) -> Result<Vec<u8>, String> {
+    if frame.is_empty() {
+        return Ok(Vec::new());
+    }
+    if frame.len() > LZX_FRAME_LEN {
+        return Err(format!("LZX frame too long: {} bytes", frame.len()));
+    }
+    let tokens = encoder.tokenize(frame);
+
+    let mut main_freq = vec![0u32; encoder.main_elements];
+    let mut length_freq = vec![0u32; LZX_NUM_SECONDARY_LENGTHS];
+    let mut aligned_freq = [0u32; LZX_ALIGNED_NUM_ELEMENTS];
+    for token in &tokens {
+        match *token {
+            Token::Literal(b) => main_freq[b as usize] += 1,
+            Token::Match { len, slot, extra } => {
+                let len_header = (len - LZX_MIN_MATCH).min(LZX_NUM_PRIMARY_LENGTHS);
+                main_freq[LZX_NUM_CHARS + (slot << 3) + len_header] += 1;
+                if len_header == LZX_NUM_PRIMARY_LENGTHS {
+                    length_freq[len - LZX_MIN_MATCH - LZX_NUM_PRIMARY_LENGTHS] += 1;
+                }
+                if slot >= 3 && EXTRA_BITS[slot] >= 3 {
+                    aligned_freq[(extra & 7) as usize] += 1;
+                }
+            }
+        }
+    }
+    let main = HuffmanCode::build(&main_freq, MAIN_TREE_MAX_BITS);
+    let length = HuffmanCode::build(&length_freq, MAIN_TREE_MAX_BITS);
+    let aligned = HuffmanCode::build(&aligned_freq, ALIGNED_MAX_BITS);
+
+    let aligned_block = match encoder.block_mode {
+        LzxBlockMode::Verbatim => false,
+        LzxBlockMode::Aligned => true,
+        LzxBlockMode::Auto => {
+            let (verbatim_cost, aligned_cost) = aligned_freq
+                .iter()
+                .enumerate()
+                .map(|(sym, &n)| (3 * u64::from(n), aligned.cost(sym) * u64::from(n)))
+                .fold((0, ALIGNED_TREE_COST), |(v, a), (dv, da)| (v + dv, a + da));
+            aligned_cost < verbatim_cost
+        }
+    };
+
+    let mut bw = BitWriter::default();
+    if !encoder.header_written {
+        // No E8 call translation.
+        bw.write(0, 1);
+        encoder.header_written = true;
+    }
+    let block_type = if aligned_block {
+        LZX_BLOCKTYPE_ALIGNED
+    } else {
+        LZX_BLOCKTYPE_VERBATIM
+    };
+    bw.write(u32::from(block_type), 3);
+    bw.write((frame.len() >> 8) as u32, 16);
+    bw.write((frame.len() & 0xff) as u32, 8);
+    if aligned_block {
+        for &len in &aligned.lens {
+            bw.write(u32::from(len), 3);
+        }
+    }
+    write_lens(&mut bw, &encoder.main_lens[..LZX_NUM_CHARS], &main.lens[..LZX_NUM_CHARS]);
+    write_lens(&mut bw, &encoder.main_lens[LZX_NUM_CHARS..], &main.lens[LZX_NUM_CHARS..]);
+    write_lens(&mut bw, &encoder.length_lens, &length.lens);
+    encoder.main_lens.copy_from_slice(&main.lens);
+    encoder.length_lens.copy_from_slice(&length.lens);
+
+    for token in tokens {
+        match token {
+            Token::Literal(b) => main.write(&mut bw, b as usize),
+            Token::Match { len, slot, extra } => {
+                let len_header = (len - LZX_MIN_MATCH).min(LZX_NUM_PRIMARY_LENGTHS);
+                main.write(&mut bw, LZX_NUM_CHARS + (slot << 3) + len_header);
+                if len_header == LZX_NUM_PRIMARY_LENGTHS {
+                    length.write(&mut bw, len - LZX_MIN_MATCH - LZX_NUM_PRIMARY_LENGTHS);
+                }
+                if slot < 3 {
+                    continue;
+                }
+                let extra_bits = u32::from(EXTRA_BITS[slot]);
+                if aligned_block && extra_bits >= 3 {
+                    bw.write(extra >> 3, extra_bits - 3);
+                    aligned.write(&mut bw, (extra & 7) as usize);
+                } else {
+                    bw.write(extra, extra_bits);
+                }
+            }
+        }
+    }
+    Ok(bw.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decompress_block, LzxState};
+    use super::*;
+    use proptest::prelude::*;
+
+    fn sample_text(len: usize) -> Vec<u8> {
+        let words = ["Haus", "häuslich", "der Zug", "집", "기차", "<p>", "</p>", "Bahnhof", "\n"];
+        let mut seed = 0x2545_F491u32;
+        let mut out = Vec::with_capacity(len);
+        while out.len() < len {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            out.extend_from_slice(words[seed as usize % words.len()].as_bytes());
+            if seed.is_multiple_of(7) {
+                out.extend_from_slice(&seed.to_le_bytes());
+            }
+        }
+        out.truncate(len);
+        out
+    }
+
+    fn noise(len: usize) -> Vec<u8> {
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 7;
+                seed ^= seed >> 9;
+                seed as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8], window_bits: u8, reset_frames: usize, mode: LzxBlockMode) -> usize {
+        let mut encoder = LzxEncoder::new(window_bits).expect("encoder").with_block_mode(mode);
+        let mut state = LzxState::new(window_bits).expect("decoder");
+        let mut compressed_len = 0;
+        for (i, frame) in data.chunks(LZX_FRAME_LEN).enumerate() {
+            if i % reset_frames == 0 {
+                encoder.reset();
+                state = LzxState::new(window_bits).expect("decoder");
+            }
+            let mut packed = compress_block(&mut encoder, frame).expect("compress");
+            compressed_len += packed.len();
+            packed.extend_from_slice(&[0, 0]);
+            let out = decompress_block(&mut state, &packed, frame.len()).expect("decompress");
+            assert!(out == frame, "frame {i} differs ({mode:?}, window bits {window_bits})");
+        }
+        compressed_len
+    }
+
+    #[test]
+    fn verbatim_and_aligned_frames_round_trip() {
+        let text = sample_text(5 * LZX_FRAME_LEN + 1234);
+        for mode in [LzxBlockMode::Verbatim, LzxBlockMode::Aligned, LzxBlockMode::Auto] {
+            let packed = round_trip(&text, 16, 2, mode);
+            assert!(packed < text.len() / 2, "{mode:?} barely compressed: {packed}");
+        }
+        round_trip(&text, 21, 4, LzxBlockMode::Auto);
+    }
+
+    #[test]
+    fn degenerate_inputs_round_trip() {
+        round_trip(&noise(2 * LZX_FRAME_LEN + 17), 16, 2, LzxBlockMode::Auto);
+        round_trip(&vec![b'a'; 3 * LZX_FRAME_LEN], 17, 3, LzxBlockMode::Aligned);
+        round_trip(b"x", 16, 1, LzxBlockMode::Verbatim);
+        round_trip(b"abababababababab", 15, 1, LzxBlockMode::Auto);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn arbitrary_data_round_trips(
+            data in prop::collection::vec(prop::sample::select(b"aab\xE8\x00z".to_vec()), 0..3 * LZX_FRAME_LEN),
+            window_bits in 15u8..=17,
+            reset_frames in 1usize..4,
+            mode in prop::sample::select(vec![LzxBlockMode::Auto, LzxBlockMode::Verbatim, LzxBlockMode::Aligned]),
+        ) {
+            round_trip(&data, window_bits, reset_frames, mode);
+        }
+    }
+
+    #[test]
+    fn huffman_lengths_respect_limit_and_stay_complete() {
+        let freqs = (0..40).map(|i| 1u32 << (i % 31)).collect::<Vec<_>>();
+        let lens = huffman_lengths(&freqs, 7);
+        assert!(lens.iter().all(|&l| (1..=7).contains(&l)));
+        let kraft = lens.iter().map(|&l| 1u64 << (16 - l)).sum::<u64>();
+        assert_eq!(kraft, 1 << 16);
+    }
+}
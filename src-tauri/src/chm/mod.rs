@@ -1,4 +1,16 @@
 pub mod archive;
+pub mod cache;
+pub mod fts;
 pub mod lzx;
+pub mod source;
+pub mod system;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod writer;
 
-pub use archive::ChmArchive;
+pub use archive::{lzx_block_cache_stats, ChmArchive, ChmError};
+pub use cache::{CacheStats, LruCache};
+pub use fts::FullTextIndex;
+pub use source::{ChmSource, MemorySource, ReadSeekSource};
+pub use system::ChmSystem;
+pub use writer::{ChmWriter, ChmWriterOptions};
@@ -0,0 +1,151 @@
+//! Random-access byte backends for `ChmArchive`.
+//!
+//! An archive only reads its headers, directory, reset table and the compressed
+//! blocks a lookup touches, so the backing CHM never has to be fully in memory.
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Positional-read backend for a CHM container.
+pub trait ChmSource: Send + Sync + fmt::Debug {
+    /// Total length of the container in bytes.
+    fn len(&self) -> u64;
+
+    /// Whether the container is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fill `buf` with the bytes starting at `offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the range extends past the end or the backend read fails.
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()>;
+
+    /// Bytes of the container held in memory by this backend.
+    fn resident_len(&self) -> u64 {
+        0
+    }
+}
+
+fn read_from_slice(data: &[u8], offset: u64, buf: &mut [u8]) -> io::Result<()> {
+    let range = usize::try_from(offset)
+        .ok()
+        .and_then(|start| Some(start..start.checked_add(buf.len())?))
+        .and_then(|range| data.get(range))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "read past end of CHM"))?;
+    buf.copy_from_slice(range);
+    Ok(())
+}
+
+/// Shared in-memory CHM bytes.
+#[derive(Debug, Clone)]
+pub struct MemorySource(Arc<[u8]>);
+
+impl<T: Into<Arc<[u8]>>> From<T> for MemorySource {
+    fn from(data: T) -> Self {
+        Self(data.into())
+    }
+}
+
+impl ChmSource for MemorySource {
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        read_from_slice(&self.0, offset, buf)
+    }
+
+    fn resident_len(&self) -> u64 {
+        self.0.len() as u64
+    }
+}
+
+/// `Read + Seek` backend limited to a byte window, e.g. a stored member inside a ZIP file.
+pub struct ReadSeekSource<R> {
+    inner: Mutex<R>,
+    base: u64,
+    len: u64,
+}
+
+impl<R: Read + Seek> ReadSeekSource<R> {
+    /// Use the whole stream as the container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the stream length cannot be determined.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        Ok(Self::window(inner, 0, len))
+    }
+
+    /// Use `len` bytes starting at `base` of the stream as the container.
+    pub fn window(inner: R, base: u64, len: u64) -> Self {
+        Self {
+            inner: Mutex::new(inner),
+            base,
+            len,
+        }
+    }
+}
+
+impl ReadSeekSource<File> {
+    /// Open a CHM file on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be opened or sized.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R> fmt::Debug for ReadSeekSource<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadSeekSource")
+            .field("base", &self.base)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<R: Read + Seek + Send> ChmSource for ReadSeekSource<R> {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        if !matches!(offset.checked_add(buf.len() as u64), Some(end) if end <= self.len) {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "read past end of CHM"));
+        }
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| io::Error::other("CHM source lock poisoned"))?;
+        inner.seek(SeekFrom::Start(self.base + offset))?;
+        inner.read_exact(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn window_reads_stay_inside_member() {
+        let outer = b"zip-header|ITSF-member-bytes|central-dir".to_vec();
+        let source = ReadSeekSource::window(Cursor::new(outer), 11, 17);
+        let mut buf = [0u8; 4];
+        source.read_exact_at(0, &mut buf).expect("member start");
+        assert_eq!(&buf, b"ITSF");
+        source.read_exact_at(13, &mut buf).expect("member end");
+        assert_eq!(&buf, b"ytes");
+        assert!(source.read_exact_at(14, &mut buf).is_err());
+        assert!(source.read_exact_at(u64::MAX, &mut buf).is_err());
+    }
+}
@@ -0,0 +1,569 @@
+//! Typed readers for CHM internal system files (`#SYSTEM`, `#TOPICS`, `#URLTBL`, `#URLSTR`, `#STRINGS`).
+use encoding_rs::Encoding;
+
+use super::archive::{ChmArchive, ChmError};
+
+const SYSTEM_FILE: &str = "/#SYSTEM";
+const TOPICS_FILE: &str = "/#TOPICS";
+const URLTBL_FILE: &str = "/#URLTBL";
+const URLSTR_FILE: &str = "/#URLSTR";
+const STRINGS_FILE: &str = "/#STRINGS";
+
+const TOPIC_RECORD_LEN: usize = 16;
+const URLTBL_RECORD_LEN: usize = 12;
+const NO_OFFSET: u32 = u32::MAX;
+const DEFAULT_CODEPAGE: u32 = 1252;
+
+const SYSTEM_CONTENTS_FILE: u16 = 0;
+const SYSTEM_INDEX_FILE: u16 = 1;
+const SYSTEM_DEFAULT_TOPIC: u16 = 2;
+const SYSTEM_TITLE: u16 = 3;
+const SYSTEM_LOCALE: u16 = 4;
+const SYSTEM_DEFAULT_WINDOW: u16 = 5;
+const SYSTEM_COMPILED_FILE: u16 = 6;
+const SYSTEM_COMPILER_VERSION: u16 = 9;
+const SYSTEM_TIMESTAMP: u16 = 10;
+const SYSTEM_DEFAULT_FONT: u16 = 16;
+
+/// Decoded `#SYSTEM` metadata.
+#[derive(Debug, Clone, Default)]
+pub struct SystemInfo {
+    pub version: u32,
+    pub contents_file: Option<String>,
+    pub index_file: Option<String>,
+    pub default_topic: Option<String>,
+    pub title: Option<String>,
+    pub lcid: Option<u32>,
+    pub dbcs: bool,
+    pub full_text_search: bool,
+    pub default_window: Option<String>,
+    pub compiled_file: Option<String>,
+    pub compiler_version: Option<String>,
+    pub default_font: Option<String>,
+    pub timestamp: Option<u32>,
+}
+
+/// One `#TOPICS` row resolved through `#STRINGS` and `#URLTBL`/`#URLSTR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicEntry {
+    pub index: usize,
+    pub title: Option<String>,
+    pub local: Option<String>,
+    pub in_contents: bool,
+}
+
+/// One fixed-size `#URLTBL` record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UrlTableEntry {
+    pub id: u32,
+    pub topic: u32,
+    pub urlstr_offset: u32,
+}
+
+/// All decodable system files of one CHM.
+#[derive(Debug, Clone, Default)]
+pub struct ChmSystem {
+    pub info: SystemInfo,
+    pub topics: Vec<TopicEntry>,
+}
+
+fn read_u16_at(buf: &[u8], off: usize) -> Option<u16> {
+    let s = buf.get(off..off + 2)?;
+    Some(u16::from_le_bytes([s[0], s[1]]))
+}
+
+fn read_u32_at(buf: &[u8], off: usize) -> Option<u32> {
+    let s = buf.get(off..off + 4)?;
+    Some(u32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+}
+
+/// Slice a NUL-terminated string starting at `off`.
+fn nt_bytes_at(buf: &[u8], off: usize) -> Option<&[u8]> {
+    let tail = buf.get(off..)?;
+    let end = tail.iter().position(|b| *b == 0).unwrap_or(tail.len());
+    Some(&tail[..end])
+}
+
+fn decode_nt(bytes: &[u8], encoding: &'static Encoding) -> Option<String> {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    if end == 0 {
+        return None;
+    }
+    let (text, _, _) = encoding.decode(&bytes[..end]);
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+/// Map a Windows LCID to its ANSI codepage.
+pub fn codepage_for_lcid(lcid: u32) -> u32 {
+    match lcid & 0xffff {
+        0x0404 | 0x0c04 | 0x1404 => return 950,
+        0x0804 | 0x1004 => return 936,
+        _ => {}
+    }
+    match lcid & 0x3ff {
+        0x11 => 932,
+        0x12 => 949,
+        0x1e => 874,
+        0x2a => 1258,
+        0x05 | 0x0e | 0x15 | 0x18 | 0x1a | 0x1b | 0x1c | 0x24 => 1250,
+        0x02 | 0x19 | 0x22 | 0x23 | 0x2f => 1251,
+        0x08 => 1253,
+        0x1f => 1254,
+        0x0d => 1255,
+        0x01 | 0x20 | 0x29 => 1256,
+        0x25..=0x27 => 1257,
+        _ => DEFAULT_CODEPAGE,
+    }
+}
+
+/// Resolve a Windows codepage to an `encoding_rs` encoding.
+pub fn encoding_for_codepage(codepage: u32) -> Option<&'static Encoding> {
+    let label = match codepage {
+        932 => "shift_jis",
+        936 => "gbk",
+        949 => "euc-kr",
+        950 => "big5",
+        874 => "windows-874",
+        1250..=1258 => return Encoding::for_label(format!("windows-{codepage}").as_bytes()),
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+impl SystemInfo {
+    /// Parse `#SYSTEM` bytes (version dword followed by code/length records).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the header is truncated.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ChmError> {
+        let version = read_u32_at(bytes, 0).ok_or(ChmError::InvalidFormat("#SYSTEM too short"))?;
+        let mut records = Vec::<(u16, &[u8])>::new();
+        let mut pos = 4usize;
+        while let (Some(code), Some(len)) = (read_u16_at(bytes, pos), read_u16_at(bytes, pos + 2)) {
+            let start = pos + 4;
+            let Some(data) = bytes.get(start..start + len as usize) else {
+                break;
+            };
+            records.push((code, data));
+            pos = start + len as usize;
+        }
+
+        let mut info = SystemInfo {
+            version,
+            ..Self::default()
+        };
+        for (code, data) in &records {
+            if *code == SYSTEM_LOCALE {
+                info.lcid = read_u32_at(data, 0);
+                info.dbcs = read_u32_at(data, 4).is_some_and(|v| v != 0);
+                info.full_text_search = read_u32_at(data, 8).is_some_and(|v| v != 0);
+            }
+        }
+
+        let encoding = info.encoding();
+        for (code, data) in records {
+            match code {
+                SYSTEM_CONTENTS_FILE => info.contents_file = decode_nt(data, encoding),
+                SYSTEM_INDEX_FILE => info.index_file = decode_nt(data, encoding),
+                SYSTEM_DEFAULT_TOPIC => info.default_topic = decode_nt(data, encoding),
+                SYSTEM_TITLE => info.title = decode_nt(data, encoding),
+                SYSTEM_DEFAULT_WINDOW => info.default_window = decode_nt(data, encoding),
+                SYSTEM_COMPILED_FILE => info.compiled_file = decode_nt(data, encoding),
+                SYSTEM_COMPILER_VERSION => info.compiler_version = decode_nt(data, encoding),
+                SYSTEM_DEFAULT_FONT => info.default_font = decode_nt(data, encoding),
+                SYSTEM_TIMESTAMP => info.timestamp = read_u32_at(data, 0),
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    /// ANSI codepage implied by the LCID (Windows-1252 when absent).
+    pub fn codepage(&self) -> u32 {
+        self.lcid.map_or(DEFAULT_CODEPAGE, codepage_for_lcid)
+    }
+
+    /// Text encoding used by strings stored in this CHM.
+    pub fn encoding(&self) -> &'static Encoding {
+        encoding_for_codepage(self.codepage()).unwrap_or(encoding_rs::WINDOWS_1252)
+    }
+
+    /// Encoding named by the LCID, or `None` when `#SYSTEM` declares no locale.
+    pub fn declared_encoding(&self) -> Option<&'static Encoding> {
+        self.lcid.map(|_| self.encoding())
+    }
+
+    /// Serialize to `#SYSTEM` bytes that [`SystemInfo::parse`] reads back.
+    ///
+    /// Strings are encoded with [`SystemInfo::encoding`]; the version defaults to 3 when unset.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        fn push_record(out: &mut Vec<u8>, code: u16, data: &[u8]) {
+            out.extend_from_slice(&code.to_le_bytes());
+            out.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            out.extend_from_slice(data);
+        }
+
+        let encoding = self.encoding();
+        let version = if self.version == 0 { 3 } else { self.version };
+        let mut out = version.to_le_bytes().to_vec();
+        let strings = [
+            (SYSTEM_CONTENTS_FILE, &self.contents_file),
+            (SYSTEM_INDEX_FILE, &self.index_file),
+            (SYSTEM_DEFAULT_TOPIC, &self.default_topic),
+            (SYSTEM_TITLE, &self.title),
+            (SYSTEM_DEFAULT_WINDOW, &self.default_window),
+            (SYSTEM_COMPILED_FILE, &self.compiled_file),
+            (SYSTEM_COMPILER_VERSION, &self.compiler_version),
+            (SYSTEM_DEFAULT_FONT, &self.default_font),
+        ];
+        for (code, value) in strings {
+            if let Some(value) = value {
+                push_record(&mut out, code, &encode_nt(value, encoding));
+            }
+        }
+        if let Some(lcid) = self.lcid {
+            let mut locale = [0u8; 28];
+            locale[0..4].copy_from_slice(&lcid.to_le_bytes());
+            locale[4..8].copy_from_slice(&u32::from(self.dbcs).to_le_bytes());
+            locale[8..12].copy_from_slice(&u32::from(self.full_text_search).to_le_bytes());
+            push_record(&mut out, SYSTEM_LOCALE, &locale);
+        }
+        if let Some(timestamp) = self.timestamp {
+            push_record(&mut out, SYSTEM_TIMESTAMP, &timestamp.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn encode_nt(text: &str, encoding: &'static Encoding) -> Vec<u8> {
+    let (bytes, _, _) = encoding.encode(text);
+    let mut out = bytes.into_owned();
+    out.push(0);
+    out
+}
+
+/// Serialize topics to `#TOPICS`, `#URLTBL`, `#URLSTR` and `#STRINGS`, in that order.
+///
+/// Each topic's `index` is ignored; rows are numbered by position.
+pub(crate) fn encode_topic_files(topics: &[TopicEntry], encoding: &'static Encoding) -> [(&'static str, Vec<u8>); 4] {
+    const URLTBL_BLOCK_LEN: usize = 0x1000;
+    const URLTBL_RECORDS_PER_BLOCK: usize = 341;
+    let mut topic_rows = Vec::with_capacity(topics.len() * TOPIC_RECORD_LEN);
+    let mut urltbl = Vec::new();
+    let mut urlstr = vec![0u8];
+    let mut strings = vec![0u8];
+    let mut urls = 0usize;
+    for (index, topic) in topics.iter().enumerate() {
+        let title_offset = topic.title.as_deref().map_or(NO_OFFSET, |title| {
+            let offset = strings.len() as u32;
+            strings.extend(encode_nt(title, encoding));
+            offset
+        });
+        let url_offset = topic.local.as_deref().map_or(NO_OFFSET, |local| {
+            if urls > 0 && urls.is_multiple_of(URLTBL_RECORDS_PER_BLOCK) {
+                urltbl.resize(urltbl.len().next_multiple_of(URLTBL_BLOCK_LEN), 0);
+            }
+            urls += 1;
+            let offset = urltbl.len() as u32;
+            urltbl.extend_from_slice(&(index as u32).to_le_bytes());
+            urltbl.extend_from_slice(&(index as u32).to_le_bytes());
+            urltbl.extend_from_slice(&(urlstr.len() as u32).to_le_bytes());
+            urlstr.extend_from_slice(&[0; 8]);
+            urlstr.extend(encode_nt(local, encoding));
+            offset
+        });
+        let flags: u16 = if topic.in_contents { 0x6 } else { 0x2 };
+        topic_rows.extend_from_slice(&0u32.to_le_bytes());
+        topic_rows.extend_from_slice(&title_offset.to_le_bytes());
+        topic_rows.extend_from_slice(&url_offset.to_le_bytes());
+        topic_rows.extend_from_slice(&flags.to_le_bytes());
+        topic_rows.extend_from_slice(&0u16.to_le_bytes());
+    }
+    [
+        (TOPICS_FILE, topic_rows),
+        (URLTBL_FILE, urltbl),
+        (URLSTR_FILE, urlstr),
+        (STRINGS_FILE, strings),
+    ]
+}
+
+/// Parse every `#URLTBL` record, skipping the 4-byte padding at the end of each 4 KiB block.
+pub fn parse_url_table(bytes: &[u8]) -> Vec<UrlTableEntry> {
+    const BLOCK_LEN: usize = 0x1000;
+    const RECORDS_PER_BLOCK: usize = 341;
+    let mut out = Vec::with_capacity(bytes.len() / URLTBL_RECORD_LEN);
+    for block in bytes.chunks(BLOCK_LEN) {
+        for record in block.chunks_exact(URLTBL_RECORD_LEN).take(RECORDS_PER_BLOCK) {
+            let (Some(id), Some(topic), Some(urlstr_offset)) =
+                (read_u32_at(record, 0), read_u32_at(record, 4), read_u32_at(record, 8))
+            else {
+                continue;
+            };
+            out.push(UrlTableEntry {
+                id,
+                topic,
+                urlstr_offset,
+            });
+        }
+    }
+    out
+}
+
+/// Read the `Local` string of the `#URLSTR` record at `offset`.
+fn urlstr_local(urlstr: &[u8], offset: u32) -> Option<&[u8]> {
+    nt_bytes_at(urlstr, (offset as usize).checked_add(8)?)
+}
+
+/// Resolve `#TOPICS` rows to titles and local paths.
+pub fn parse_topics(
+    topics: &[u8],
+    urltbl: &[u8],
+    urlstr: &[u8],
+    strings: &[u8],
+    encoding: &'static Encoding,
+) -> Vec<TopicEntry> {
+    topics
+        .chunks_exact(TOPIC_RECORD_LEN)
+        .enumerate()
+        .map(|(index, record)| {
+            let title = read_u32_at(record, 4)
+                .filter(|off| *off != NO_OFFSET)
+                .and_then(|off| nt_bytes_at(strings, off as usize))
+                .and_then(|raw| decode_nt(raw, encoding));
+            let local = read_u32_at(record, 8)
+                .filter(|off| *off != NO_OFFSET)
+                .and_then(|off| read_u32_at(urltbl, (off as usize).checked_add(8)?))
+                .and_then(|off| urlstr_local(urlstr, off))
+                .and_then(|raw| decode_nt(raw, encoding));
+            let in_contents = read_u16_at(record, 12).is_some_and(|flags| flags & 0x4 != 0);
+            TopicEntry {
+                index,
+                title,
+                local,
+                in_contents,
+            }
+        })
+        .collect()
+}
+
+fn read_optional(chm: &ChmArchive, path: &str) -> Result<Option<Vec<u8>>, ChmError> {
+    if chm.find_entry(path)?.is_none() {
+        return Ok(None);
+    }
+    chm.read_object(path).map(Some)
+}
+
+/// Read a NUL-terminated string at `offset` of `path` in small ranges.
+fn read_nt_range(
+    chm: &ChmArchive,
+    path: &str,
+    offset: u64,
+    encoding: &'static Encoding,
+) -> Result<Option<String>, ChmError> {
+    const STEP: u64 = 256;
+    const MAX_LEN: usize = 4096;
+    if chm.find_entry(path)?.is_none() {
+        return Ok(None);
+    }
+    let mut raw = Vec::new();
+    while raw.len() < MAX_LEN {
+        let chunk = chm.read_object_range(path, offset + raw.len() as u64, STEP)?;
+        let done = chunk.len() < STEP as usize || chunk.contains(&0);
+        raw.extend(chunk);
+        if done {
+            break;
+        }
+    }
+    Ok(decode_nt(&raw, encoding))
+}
+
+/// Resolve a single `#TOPICS` row without loading the whole topic table.
+///
+/// # Errors
+///
+/// Returns an error when a present system file cannot be read.
+pub fn read_topic(
+    chm: &ChmArchive,
+    index: u32,
+    encoding: &'static Encoding,
+) -> Result<Option<TopicEntry>, ChmError> {
+    if chm.find_entry(TOPICS_FILE)?.is_none() {
+        return Ok(None);
+    }
+    let record_len = TOPIC_RECORD_LEN as u64;
+    let record = chm.read_object_range(TOPICS_FILE, u64::from(index) * record_len, record_len)?;
+    if record.len() < TOPIC_RECORD_LEN {
+        return Ok(None);
+    }
+    let title = match read_u32_at(&record, 4).filter(|off| *off != NO_OFFSET) {
+        Some(off) => read_nt_range(chm, STRINGS_FILE, u64::from(off), encoding)?,
+        None => None,
+    };
+    let urlstr_offset = match read_u32_at(&record, 8).filter(|off| *off != NO_OFFSET) {
+        Some(off) if chm.find_entry(URLTBL_FILE)?.is_some() => {
+            let row = chm.read_object_range(URLTBL_FILE, u64::from(off), URLTBL_RECORD_LEN as u64)?;
+            read_u32_at(&row, 8)
+        }
+        _ => None,
+    };
+    let local = match urlstr_offset {
+        Some(off) => read_nt_range(chm, URLSTR_FILE, u64::from(off) + 8, encoding)?,
+        None => None,
+    };
+    Ok(Some(TopicEntry {
+        index: index as usize,
+        title,
+        local,
+        in_contents: read_u16_at(&record, 12).is_some_and(|flags| flags & 0x4 != 0),
+    }))
+}
+
+impl SystemInfo {
+    /// Read and decode `#SYSTEM`, or defaults when the file is missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `#SYSTEM` is present but cannot be read or is malformed.
+    pub fn read(chm: &ChmArchive) -> Result<Self, ChmError> {
+        match read_optional(chm, SYSTEM_FILE)? {
+            Some(bytes) => Self::parse(&bytes),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl ChmSystem {
+    /// Read and decode all system files present in `chm`.
+    ///
+    /// Missing files produce empty fields rather than errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a present system file cannot be read or `#SYSTEM` is malformed.
+    pub fn read(chm: &ChmArchive) -> Result<Self, ChmError> {
+        Self::read_with_fallback(chm, encoding_rs::WINDOWS_1252)
+    }
+
+    /// Like [`ChmSystem::read`], decoding topic strings with `fallback` when no LCID is declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a present system file cannot be read or `#SYSTEM` is malformed.
+    pub fn read_with_fallback(chm: &ChmArchive, fallback: &'static Encoding) -> Result<Self, ChmError> {
+        let info = SystemInfo::read(chm)?;
+        let Some(topics) = read_optional(chm, TOPICS_FILE)? else {
+            return Ok(Self {
+                info,
+                topics: Vec::new(),
+            });
+        };
+        let urltbl = read_optional(chm, URLTBL_FILE)?.unwrap_or_default();
+        let urlstr = read_optional(chm, URLSTR_FILE)?.unwrap_or_default();
+        let strings = read_optional(chm, STRINGS_FILE)?.unwrap_or_default();
+        let encoding = info.declared_encoding().unwrap_or(fallback);
+        let topics = parse_topics(&topics, &urltbl, &urlstr, &strings, encoding);
+        Ok(Self { info, topics })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chm::test_support::build_indexed_container;
+
+    fn record(code: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = code.to_le_bytes().to_vec();
+        out.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn parses_system_records_with_lcid_codepage() {
+        let mut bytes = 3u32.to_le_bytes().to_vec();
+        let (title, _, _) = encoding_rs::EUC_KR.encode("독한 사전");
+        let mut title = title.into_owned();
+        title.push(0);
+        bytes.extend(record(SYSTEM_TITLE, &title));
+        bytes.extend(record(SYSTEM_DEFAULT_TOPIC, b"master.html\0"));
+        let mut locale = 0x0412u32.to_le_bytes().to_vec();
+        locale.extend_from_slice(&1u32.to_le_bytes());
+        locale.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend(record(SYSTEM_LOCALE, &locale));
+        bytes.extend(record(SYSTEM_COMPILED_FILE, b"merge01\0"));
+        bytes.extend(record(SYSTEM_DEFAULT_WINDOW, b"main\0"));
+
+        let info = SystemInfo::parse(&bytes).expect("system");
+        assert_eq!(info.version, 3);
+        assert_eq!(info.lcid, Some(0x0412));
+        assert_eq!(info.codepage(), 949);
+        assert_eq!(info.encoding(), encoding_rs::EUC_KR);
+        assert_eq!(info.title.as_deref(), Some("독한 사전"));
+        assert_eq!(info.default_topic.as_deref(), Some("master.html"));
+        assert_eq!(info.compiled_file.as_deref(), Some("merge01"));
+        assert_eq!(info.default_window.as_deref(), Some("main"));
+        assert!(info.full_text_search);
+    }
+
+    #[test]
+    fn lcid_maps_to_expected_codepages() {
+        assert_eq!(codepage_for_lcid(0x0411), 932);
+        assert_eq!(codepage_for_lcid(0x0407), 1252);
+        assert_eq!(codepage_for_lcid(0x0804), 936);
+        assert_eq!(codepage_for_lcid(0x0419), 1251);
+        assert_eq!(encoding_for_codepage(1250), Some(encoding_rs::WINDOWS_1250));
+        assert_eq!(encoding_for_codepage(932), Some(encoding_rs::SHIFT_JIS));
+    }
+
+    #[test]
+    fn resolves_topic_title_and_local() {
+        let strings = b"\0Haus\0".to_vec();
+        let mut urlstr = vec![0u8];
+        let urlstr_off = urlstr.len() as u32;
+        urlstr.extend_from_slice(&0u32.to_le_bytes());
+        urlstr.extend_from_slice(&0u32.to_le_bytes());
+        urlstr.extend_from_slice(b"merge12/Haus.htm\0");
+        let mut urltbl = 0x1234u32.to_le_bytes().to_vec();
+        urltbl.extend_from_slice(&0u32.to_le_bytes());
+        urltbl.extend_from_slice(&urlstr_off.to_le_bytes());
+        let mut topics = 0u32.to_le_bytes().to_vec();
+        topics.extend_from_slice(&1u32.to_le_bytes());
+        topics.extend_from_slice(&0u32.to_le_bytes());
+        topics.extend_from_slice(&6u16.to_le_bytes());
+        topics.extend_from_slice(&0u16.to_le_bytes());
+
+        let rows = parse_topics(&topics, &urltbl, &urlstr, &strings, encoding_rs::WINDOWS_1252);
+        assert_eq!(
+            rows,
+            vec![TopicEntry {
+                index: 0,
+                title: Some("Haus".to_string()),
+                local: Some("merge12/Haus.htm".to_string()),
+                in_contents: true,
+            }]
+        );
+        assert_eq!(parse_url_table(&urltbl)[0].id, 0x1234);
+
+        let bytes = build_indexed_container(
+            &[
+                (TOPICS_FILE, &topics),
+                (URLTBL_FILE, &urltbl),
+                (URLSTR_FILE, &urlstr),
+                (STRINGS_FILE, &strings),
+            ],
+            0x200,
+        );
+        let chm = ChmArchive::open(bytes).expect("open synthetic chm");
+        let single = read_topic(&chm, 0, encoding_rs::WINDOWS_1252).expect("read topic");
+        assert_eq!(single.as_ref(), rows.first());
+        assert!(read_topic(&chm, 1, encoding_rs::WINDOWS_1252)
+            .expect("read missing topic")
+            .is_none());
+    }
+}
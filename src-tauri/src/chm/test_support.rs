@@ -0,0 +1,101 @@
+//! Synthetic CHM containers for unit tests.
+
+pub(crate) use super::writer::encint;
+
+pub(crate) fn finish_chunk(mut chunk: Vec<u8>, block_len: usize) -> Vec<u8> {
+    assert!(chunk.len() <= block_len, "synthetic chunk overflow");
+    let free = (block_len - chunk.len()) as u32;
+    chunk[4..8].copy_from_slice(&free.to_le_bytes());
+    chunk.resize(block_len, 0);
+    chunk
+}
+
+/// Build an uncompressed ITSF container whose directory has PMGL leaves under one PMGI root.
+pub(crate) fn build_indexed_container(objects: &[(&str, &[u8])], block_len: usize) -> Vec<u8> {
+    let mut sorted = objects.to_vec();
+    sorted.sort_by_key(|(name, _)| name.to_ascii_lowercase());
+
+    let mut content = Vec::new();
+    let mut leaves: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut current: Option<(String, Vec<u8>)> = None;
+    for (name, bytes) in &sorted {
+        let mut row = encint(name.len() as u64);
+        row.extend_from_slice(name.as_bytes());
+        row.extend(encint(0));
+        row.extend(encint(content.len() as u64));
+        row.extend(encint(bytes.len() as u64));
+        content.extend_from_slice(bytes);
+        if let Some((_, chunk)) = &current {
+            if chunk.len() + row.len() > block_len {
+                leaves.push(current.take().expect("current chunk"));
+            }
+        }
+        let (_, chunk) = current.get_or_insert_with(|| {
+            let mut head = b"PMGL".to_vec();
+            head.extend_from_slice(&[0; 8]);
+            head.extend_from_slice(&u32::MAX.to_le_bytes());
+            head.extend_from_slice(&u32::MAX.to_le_bytes());
+            (name.to_string(), head)
+        });
+        chunk.extend(row);
+    }
+    leaves.extend(current);
+
+    let mut root = b"PMGI".to_vec();
+    root.extend_from_slice(&[0; 4]);
+    for (i, (first, _)) in leaves.iter().enumerate() {
+        root.extend(encint(first.len() as u64));
+        root.extend_from_slice(first.as_bytes());
+        root.extend(encint(i as u64));
+    }
+    let root_idx = leaves.len() as u32;
+    let mut chunks = leaves
+        .into_iter()
+        .map(|(_, chunk)| finish_chunk(chunk, block_len))
+        .collect::<Vec<_>>();
+    chunks.push(finish_chunk(root, block_len));
+    wrap_directory(chunks, root_idx, block_len, &content)
+}
+
+/// Build a single-PMGL container from raw `(name, space, start, length)` rows, e.g. hostile ones.
+pub(crate) fn build_raw_listing_container(rows: &[(&str, u64, u64, u64)], content: &[u8], block_len: usize) -> Vec<u8> {
+    let mut chunk = b"PMGL".to_vec();
+    chunk.extend_from_slice(&[0; 8]);
+    chunk.extend_from_slice(&u32::MAX.to_le_bytes());
+    chunk.extend_from_slice(&u32::MAX.to_le_bytes());
+    for (name, space, start, length) in rows {
+        chunk.extend(encint(name.len() as u64));
+        chunk.extend_from_slice(name.as_bytes());
+        chunk.extend(encint(*space));
+        chunk.extend(encint(*start));
+        chunk.extend(encint(*length));
+    }
+    wrap_directory(vec![finish_chunk(chunk, block_len)], u32::MAX, block_len, content)
+}
+
+fn wrap_directory(chunks: Vec<Vec<u8>>, root_idx: u32, block_len: usize, content: &[u8]) -> Vec<u8> {
+    let dir_len = 0x54 + chunks.len() * block_len;
+    let mut out = vec![0u8; 0x60];
+    out[0..4].copy_from_slice(b"ITSF");
+    out[4..8].copy_from_slice(&3u32.to_le_bytes());
+    out[8..12].copy_from_slice(&0x60u32.to_le_bytes());
+    out[0x48..0x50].copy_from_slice(&0x60u64.to_le_bytes());
+    out[0x50..0x58].copy_from_slice(&(dir_len as u64).to_le_bytes());
+    out[0x58..0x60].copy_from_slice(&((0x60 + dir_len) as u64).to_le_bytes());
+
+    let mut itsp = vec![0xffu8; 0x54];
+    itsp[0..4].copy_from_slice(b"ITSP");
+    itsp[4..8].copy_from_slice(&1u32.to_le_bytes());
+    itsp[8..12].copy_from_slice(&0x54u32.to_le_bytes());
+    itsp[0x10..0x14].copy_from_slice(&(block_len as u32).to_le_bytes());
+    itsp[0x18..0x1c].copy_from_slice(&2u32.to_le_bytes());
+    itsp[0x1c..0x20].copy_from_slice(&root_idx.to_le_bytes());
+    itsp[0x20..0x24].copy_from_slice(&0u32.to_le_bytes());
+    itsp[0x2c..0x30].copy_from_slice(&(chunks.len() as u32).to_le_bytes());
+    out.extend(itsp);
+    for chunk in chunks {
+        out.extend(chunk);
+    }
+    out.extend_from_slice(content);
+    out
+}
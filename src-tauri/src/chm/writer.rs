@@ -0,0 +1,582 @@
+//! Builds ITSF/CHM containers that `ChmArchive` and the system-file readers read back.
+//!
+//! Compressed objects share one MSCompressed LZX stream; `#SYSTEM`, the directory
+//! metadata and objects added with [`ChmWriter::add_uncompressed_object`] stay in
+//! the uncompressed section.
+use std::collections::BTreeMap;
+
+use super::archive::ChmError;
+use super::lzx::{self, LzxBlockMode, LzxEncoder, LZX_FRAME_LEN};
+use super::system::{encode_topic_files, SystemInfo, TopicEntry};
+
+const ITSF_HEADER_LEN: usize = 0x60;
+const HEADER_SECTION0_LEN: usize = 0x18;
+const ITSP_HEADER_LEN: usize = 0x54;
+const PMGL_ENTRIES_OFFSET: usize = 0x14;
+const PMGI_ENTRIES_OFFSET: usize = 0x08;
+const QUICKREF_DENSITY: u32 = 2;
+const QUICKREF_SPACING: usize = 1 + (1 << QUICKREF_DENSITY);
+const DEFAULT_LCID: u32 = 0x0409;
+const NO_CHUNK: u32 = u32::MAX;
+
+const ITSF_GUID_1: [u8; 16] = guid(0x7C01_FD10, 0x7BAA, 0x11D0, [0x9E, 0x0C, 0x00, 0xA0, 0xC9, 0x22, 0xE6, 0xEC]);
+const ITSF_GUID_2: [u8; 16] = guid(0x7C01_FD11, 0x7BAA, 0x11D0, [0x9E, 0x0C, 0x00, 0xA0, 0xC9, 0x22, 0xE6, 0xEC]);
+const ITSP_GUID: [u8; 16] = guid(0x5D02_926A, 0x212E, 0x11D0, [0x9D, 0xF9, 0x00, 0xA0, 0xC9, 0x22, 0xE6, 0xEC]);
+const LZX_TRANSFORM_GUID: &str = "{7FC28940-9D31-11D0-9B27-00A0C91E9C7C}";
+
+const NAME_LIST: &str = "::DataSpace/NameList";
+const MSCOMPRESSED_CONTENT: &str = "::DataSpace/Storage/MSCompressed/Content";
+const MSCOMPRESSED_CONTROL_DATA: &str = "::DataSpace/Storage/MSCompressed/ControlData";
+const MSCOMPRESSED_SPAN_INFO: &str = "::DataSpace/Storage/MSCompressed/SpanInfo";
+const MSCOMPRESSED_TRANSFORM_LIST: &str = "::DataSpace/Storage/MSCompressed/Transform/List";
+const MSCOMPRESSED_RESET_TABLE: &str =
+    "::DataSpace/Storage/MSCompressed/Transform/{7FC28940-9D31-11D0-9B27-00A0C91E9C7C}/InstanceData/ResetTable";
+const SYSTEM_FILE: &str = "/#SYSTEM";
+
+/// Container and LZX settings for [`ChmWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChmWriterOptions {
+    /// LZX window size as a power of two (16..=21).
+    pub window_bits: u8,
+    /// Compressed blocks between LZX resets.
+    pub reset_blkcount: u32,
+    pub block_mode: LzxBlockMode,
+    /// Length of each PMGL/PMGI directory chunk.
+    pub directory_block_len: usize,
+}
+
+impl Default for ChmWriterOptions {
+    fn default() -> Self {
+        Self {
+            window_bits: 16,
+            reset_blkcount: 2,
+            block_mode: LzxBlockMode::Auto,
+            directory_block_len: 0x1000,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingObject {
+    path: String,
+    data: Vec<u8>,
+    compressed: bool,
+}
+
+/// Directory chunk being filled, with the first key and the offset of every row.
+#[derive(Debug)]
+struct PackedChunk {
+    first: String,
+    bytes: Vec<u8>,
+    starts: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct DirectoryRow {
+    path: String,
+    space: u64,
+    start: u64,
+    length: u64,
+}
+
+/// In-memory builder for a CHM container.
+#[derive(Debug, Default)]
+pub struct ChmWriter {
+    options: ChmWriterOptions,
+    system: Option<SystemInfo>,
+    topics: Vec<TopicEntry>,
+    /// Objects keyed by their ASCII-lowercased path, the directory sort order.
+    objects: BTreeMap<String, PendingObject>,
+}
+
+const fn guid(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> [u8; 16] {
+    let a = data1.to_le_bytes();
+    let b = data2.to_le_bytes();
+    let c = data3.to_le_bytes();
+    [
+        a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], data4[0], data4[1], data4[2], data4[3], data4[4],
+        data4[5], data4[6], data4[7],
+    ]
+}
+
+/// Encode `v` as a CHM variable-length integer (7 bits per byte, most significant first).
+pub(crate) fn encint(mut v: u64) -> Vec<u8> {
+    let mut out = vec![(v & 0x7f) as u8];
+    v >>= 7;
+    while v > 0 {
+        out.push(0x80 | (v & 0x7f) as u8);
+        v >>= 7;
+    }
+    out.reverse();
+    out
+}
+
+fn utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn put_u32(buf: &mut [u8], off: usize, v: u32) {
+    buf[off..off + 4].copy_from_slice(&v.to_le_bytes());
+}
+
+fn put_u64(buf: &mut [u8], off: usize, v: u64) {
+    buf[off..off + 8].copy_from_slice(&v.to_le_bytes());
+}
+
+impl ChmWriter {
+    /// Create an empty writer.
+    pub fn new(options: ChmWriterOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Store `info` as `#SYSTEM`; its LCID also picks the encoding of topic strings.
+    pub fn set_system_info(&mut self, info: SystemInfo) {
+        self.system = Some(info);
+    }
+
+    /// Add an object to the LZX-compressed section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the path is empty or already present (ASCII case-insensitively).
+    pub fn add_object(&mut self, path: &str, data: impl Into<Vec<u8>>) -> Result<(), ChmError> {
+        self.insert_object(path, data.into(), true)
+    }
+
+    /// Add an object stored verbatim in the uncompressed section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the path is empty or already present (ASCII case-insensitively).
+    pub fn add_uncompressed_object(&mut self, path: &str, data: impl Into<Vec<u8>>) -> Result<(), ChmError> {
+        self.insert_object(path, data.into(), false)
+    }
+
+    /// Append a `#TOPICS` row and return its topic index.
+    pub fn add_topic(&mut self, title: Option<&str>, local: &str, in_contents: bool) -> u32 {
+        let index = self.topics.len();
+        self.topics.push(TopicEntry {
+            index,
+            title: title.map(str::to_string),
+            local: Some(local.to_string()),
+            in_contents,
+        });
+        index as u32
+    }
+
+    fn insert_object(&mut self, path: &str, data: Vec<u8>, compressed: bool) -> Result<(), ChmError> {
+        if path.is_empty() {
+            return Err(ChmError::InvalidFormat("empty object path"));
+        }
+        let key = path.to_ascii_lowercase();
+        if self.objects.contains_key(&key) {
+            return Err(ChmError::InvalidFormat("duplicate object path"));
+        }
+        self.objects.insert(
+            key,
+            PendingObject {
+                path: path.to_string(),
+                data,
+                compressed,
+            },
+        );
+        Ok(())
+    }
+
+    /// Lay out and serialize the whole container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid options, a path that collides with a system file,
+    /// a directory entry longer than a chunk, or an LZX encoder failure.
+    pub fn finish(mut self) -> Result<Vec<u8>, ChmError> {
+        let options = self.options;
+        if !(16..=21).contains(&options.window_bits) {
+            return Err(ChmError::InvalidFormat("unsupported LZX window size"));
+        }
+        if options.reset_blkcount == 0 {
+            return Err(ChmError::InvalidFormat("invalid reset block count"));
+        }
+        if options.directory_block_len < 0x100 || u32::try_from(options.directory_block_len).is_err() {
+            return Err(ChmError::InvalidFormat("invalid directory block length"));
+        }
+
+        let system = self.system.take();
+        let lcid = system.as_ref().and_then(|info| info.lcid).unwrap_or(DEFAULT_LCID);
+        let timestamp = system.as_ref().and_then(|info| info.timestamp).unwrap_or(0);
+        if !self.topics.is_empty() {
+            let encoding = system.as_ref().map_or(encoding_rs::WINDOWS_1252, SystemInfo::encoding);
+            for (path, data) in encode_topic_files(&std::mem::take(&mut self.topics), encoding) {
+                self.add_object(path, data)?;
+            }
+        }
+        if let Some(info) = &system {
+            self.add_uncompressed_object(SYSTEM_FILE, info.to_bytes())?;
+        }
+
+        let mut rows = Vec::with_capacity(self.objects.len() + 6);
+        let mut section0 = Vec::new();
+        let mut stream = Vec::new();
+        for object in self.objects.into_values() {
+            let length = object.data.len() as u64;
+            // Empty objects live in section 0 so they never need the LZX tables.
+            let (space, start) = if object.compressed && !object.data.is_empty() {
+                let start = stream.len() as u64;
+                stream.extend(object.data);
+                (1, start)
+            } else {
+                let start = section0.len() as u64;
+                section0.extend(object.data);
+                (0, start)
+            };
+            rows.push(DirectoryRow {
+                path: object.path,
+                space,
+                start,
+                length,
+            });
+        }
+        if !stream.is_empty() {
+            let mut push = |path: &str, data: Vec<u8>| {
+                rows.push(DirectoryRow {
+                    path: path.to_string(),
+                    space: 0,
+                    start: section0.len() as u64,
+                    length: data.len() as u64,
+                });
+                section0.extend(data);
+            };
+            let (content, reset_table) = compress_stream(&stream, &options)?;
+            push(NAME_LIST, name_list());
+            push(MSCOMPRESSED_CONTROL_DATA, control_data(&options));
+            push(MSCOMPRESSED_SPAN_INFO, (stream.len() as u64).to_le_bytes().to_vec());
+            push(MSCOMPRESSED_TRANSFORM_LIST, utf16le(LZX_TRANSFORM_GUID));
+            push(MSCOMPRESSED_RESET_TABLE, reset_table);
+            push(MSCOMPRESSED_CONTENT, content);
+        }
+        rows.sort_by_cached_key(|row| row.path.to_ascii_lowercase());
+
+        let directory = build_directory(&rows, options.directory_block_len, lcid)?;
+        let dir_offset = ITSF_HEADER_LEN + HEADER_SECTION0_LEN;
+        let data_offset = dir_offset + directory.len();
+        let file_len = (data_offset + section0.len()) as u64;
+
+        let mut out = vec![0u8; ITSF_HEADER_LEN];
+        out[0..4].copy_from_slice(b"ITSF");
+        put_u32(&mut out, 0x04, 3);
+        put_u32(&mut out, 0x08, ITSF_HEADER_LEN as u32);
+        put_u32(&mut out, 0x0c, 1);
+        put_u32(&mut out, 0x10, timestamp);
+        put_u32(&mut out, 0x14, lcid);
+        out[0x18..0x28].copy_from_slice(&ITSF_GUID_1);
+        out[0x28..0x38].copy_from_slice(&ITSF_GUID_2);
+        put_u64(&mut out, 0x38, ITSF_HEADER_LEN as u64);
+        put_u64(&mut out, 0x40, HEADER_SECTION0_LEN as u64);
+        put_u64(&mut out, 0x48, dir_offset as u64);
+        put_u64(&mut out, 0x50, directory.len() as u64);
+        put_u64(&mut out, 0x58, data_offset as u64);
+
+        let mut section0_header = [0u8; HEADER_SECTION0_LEN];
+        put_u32(&mut section0_header, 0x00, 0x01fe);
+        put_u64(&mut section0_header, 0x08, file_len);
+        out.extend_from_slice(&section0_header);
+        out.extend(directory);
+        out.extend(section0);
+        Ok(out)
+    }
+}
+
+/// Compress `stream` frame by frame, returning the Content bytes and the ResetTable.
+fn compress_stream(stream: &[u8], options: &ChmWriterOptions) -> Result<(Vec<u8>, Vec<u8>), ChmError> {
+    let mut encoder = LzxEncoder::new(options.window_bits)
+        .map_err(ChmError::CompressionFailed)?
+        .with_block_mode(options.block_mode);
+    let mut content = Vec::new();
+    let mut offsets = Vec::new();
+    for (block, frame) in stream.chunks(LZX_FRAME_LEN).enumerate() {
+        if (block as u64).is_multiple_of(u64::from(options.reset_blkcount)) {
+            encoder.reset();
+        }
+        offsets.push(content.len() as u64);
+        content.extend(lzx::compress_block(&mut encoder, frame).map_err(ChmError::CompressionFailed)?);
+    }
+
+    let mut table = vec![0u8; 0x28];
+    put_u32(&mut table, 0x00, 2);
+    put_u32(&mut table, 0x04, offsets.len() as u32);
+    put_u32(&mut table, 0x08, 8);
+    put_u32(&mut table, 0x0c, 0x28);
+    put_u64(&mut table, 0x10, stream.len() as u64);
+    put_u64(&mut table, 0x18, content.len() as u64);
+    put_u64(&mut table, 0x20, LZX_FRAME_LEN as u64);
+    for offset in offsets {
+        table.extend_from_slice(&offset.to_le_bytes());
+    }
+    Ok((content, table))
+}
+
+/// LZXC control data; the reset interval is chosen so readers derive `reset_blkcount` blocks.
+fn control_data(options: &ChmWriterOptions) -> Vec<u8> {
+    let window_units = 1u32 << (options.window_bits - 15);
+    let reset_units = options.reset_blkcount * (window_units / 2);
+    let mut out = vec![0u8; 0x1c];
+    put_u32(&mut out, 0x00, 6);
+    out[0x04..0x08].copy_from_slice(b"LZXC");
+    put_u32(&mut out, 0x08, 2);
+    put_u32(&mut out, 0x0c, reset_units);
+    put_u32(&mut out, 0x10, window_units);
+    put_u32(&mut out, 0x14, 1);
+    out
+}
+
+fn name_list() -> Vec<u8> {
+    let names = ["Uncompressed", "MSCompressed"];
+    let mut body = (names.len() as u16).to_le_bytes().to_vec();
+    for name in names {
+        body.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        body.extend(utf16le(name));
+        body.extend_from_slice(&[0, 0]);
+    }
+    // The leading length counts 16-bit words, itself included.
+    let mut out = ((body.len() / 2 + 1) as u16).to_le_bytes().to_vec();
+    out.extend(body);
+    out
+}
+
+fn directory_row(row: &DirectoryRow) -> Vec<u8> {
+    let mut out = encint(row.path.len() as u64);
+    out.extend_from_slice(row.path.as_bytes());
+    out.extend(encint(row.space));
+    out.extend(encint(row.start));
+    out.extend(encint(row.length));
+    out
+}
+
+fn quickref_len(entries: usize) -> usize {
+    2 + 2 * (entries / QUICKREF_SPACING)
+}
+
+/// Pack `rows` into chunks of `block_len`, each starting with `header`.
+///
+/// The free-space field and quickref area are written later by [`finish_chunk`].
+fn pack_chunks(rows: Vec<(String, Vec<u8>)>, header: &[u8], block_len: usize) -> Result<Vec<PackedChunk>, ChmError> {
+    let mut chunks: Vec<PackedChunk> = Vec::new();
+    for (key, row) in rows {
+        if header.len() + row.len() + quickref_len(1) > block_len {
+            return Err(ChmError::InvalidFormat("directory entry does not fit a chunk"));
+        }
+        let fits = chunks.last().is_some_and(|chunk| {
+            chunk.bytes.len() + row.len() + quickref_len(chunk.starts.len() + 1) <= block_len
+        });
+        if !fits {
+            chunks.push(PackedChunk {
+                first: key,
+                bytes: header.to_vec(),
+                starts: Vec::new(),
+            });
+        }
+        let chunk = chunks.last_mut().expect("chunk just pushed");
+        chunk.starts.push(chunk.bytes.len());
+        chunk.bytes.extend(row);
+    }
+    Ok(chunks)
+}
+
+/// Write the free-space field and the quickref area, then pad to `block_len`.
+fn finish_chunk(mut chunk: Vec<u8>, starts: &[usize], entries_offset: usize, block_len: usize) -> Vec<u8> {
+    let free = block_len - chunk.len();
+    put_u32(&mut chunk, 0x04, free as u32);
+    chunk.resize(block_len, 0);
+    chunk[block_len - 2..].copy_from_slice(&(starts.len() as u16).to_le_bytes());
+    for (i, start) in starts.iter().step_by(QUICKREF_SPACING).skip(1).enumerate() {
+        let at = block_len - 4 - 2 * i;
+        chunk[at..at + 2].copy_from_slice(&((start - entries_offset) as u16).to_le_bytes());
+    }
+    chunk
+}
+
+/// Serialize the ITSP header, PMGL listing chunks and the PMGI index levels above them.
+fn build_directory(rows: &[DirectoryRow], block_len: usize, lcid: u32) -> Result<Vec<u8>, ChmError> {
+    let mut pmgl_header = vec![0u8; PMGL_ENTRIES_OFFSET];
+    pmgl_header[0..4].copy_from_slice(b"PMGL");
+    let leaves = pack_chunks(
+        rows.iter()
+            .map(|row| (row.path.clone(), directory_row(row)))
+            .collect(),
+        &pmgl_header,
+        block_len,
+    )?;
+    let leaf_count = leaves.len().max(1);
+
+    let mut chunks = Vec::new();
+    let mut level = Vec::with_capacity(leaves.len());
+    for (i, leaf) in leaves.into_iter().enumerate() {
+        let mut chunk = finish_chunk(leaf.bytes, &leaf.starts, PMGL_ENTRIES_OFFSET, block_len);
+        let prev = i.checked_sub(1).map_or(NO_CHUNK, |p| p as u32);
+        let next = if i + 1 < leaf_count { (i + 1) as u32 } else { NO_CHUNK };
+        put_u32(&mut chunk, 0x0c, prev);
+        put_u32(&mut chunk, 0x10, next);
+        level.push(leaf.first);
+        chunks.push(chunk);
+    }
+    if chunks.is_empty() {
+        let mut chunk = finish_chunk(pmgl_header.clone(), &[], PMGL_ENTRIES_OFFSET, block_len);
+        put_u32(&mut chunk, 0x0c, NO_CHUNK);
+        put_u32(&mut chunk, 0x10, NO_CHUNK);
+        chunks.push(chunk);
+    }
+
+    let mut depth = 1u32;
+    let mut root = NO_CHUNK;
+    let mut level_base = 0usize;
+    while level.len() > 1 {
+        let rows = level
+            .iter()
+            .enumerate()
+            .map(|(i, first)| {
+                let mut row = encint(first.len() as u64);
+                row.extend_from_slice(first.as_bytes());
+                row.extend(encint((level_base + i) as u64));
+                (first.clone(), row)
+            })
+            .collect();
+        level_base = chunks.len();
+        level = Vec::new();
+        for index in pack_chunks(rows, b"PMGI\0\0\0\0", block_len)? {
+            chunks.push(finish_chunk(index.bytes, &index.starts, PMGI_ENTRIES_OFFSET, block_len));
+            level.push(index.first);
+        }
+        depth += 1;
+        root = (chunks.len() - 1) as u32;
+    }
+
+    let mut itsp = vec![0u8; ITSP_HEADER_LEN];
+    itsp[0..4].copy_from_slice(b"ITSP");
+    put_u32(&mut itsp, 0x04, 1);
+    put_u32(&mut itsp, 0x08, ITSP_HEADER_LEN as u32);
+    put_u32(&mut itsp, 0x0c, 0x0a);
+    put_u32(&mut itsp, 0x10, block_len as u32);
+    put_u32(&mut itsp, 0x14, QUICKREF_DENSITY);
+    put_u32(&mut itsp, 0x18, depth);
+    put_u32(&mut itsp, 0x1c, root);
+    put_u32(&mut itsp, 0x20, 0);
+    put_u32(&mut itsp, 0x24, (leaf_count - 1) as u32);
+    put_u32(&mut itsp, 0x28, u32::MAX);
+    put_u32(&mut itsp, 0x2c, chunks.len() as u32);
+    put_u32(&mut itsp, 0x30, lcid);
+    itsp[0x34..0x44].copy_from_slice(&ITSP_GUID);
+    put_u32(&mut itsp, 0x44, ITSP_HEADER_LEN as u32);
+    itsp[0x48..0x54].fill(0xff);
+
+    let mut out = itsp;
+    for chunk in chunks {
+        out.extend(chunk);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chm::system::{read_topic, ChmSystem};
+    use crate::chm::ChmArchive;
+
+    fn page(i: usize) -> Vec<u8> {
+        format!("<html><body><h1>Eintrag {i}</h1><p>Haus {i} 집 {}</p></body></html>\n", i * 7919)
+            .repeat(1 + i % 5)
+            .into_bytes()
+    }
+
+    #[test]
+    fn written_container_round_trips_through_archive() {
+        let options = ChmWriterOptions {
+            directory_block_len: 0x100,
+            reset_blkcount: 2,
+            ..ChmWriterOptions::default()
+        };
+        let mut writer = ChmWriter::new(options);
+        let paths = (0..1200).map(|i| format!("/pages/Entry{i:04}.htm")).collect::<Vec<_>>();
+        for (i, path) in paths.iter().enumerate() {
+            writer.add_object(path, page(i)).expect("add page");
+        }
+        let binary = (0..70_000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect::<Vec<_>>();
+        writer.add_object("/img/noise.bin", binary.clone()).expect("add binary");
+        writer.add_object("/empty.txt", Vec::new()).expect("add empty");
+        writer.add_uncompressed_object("/raw/Stored.dat", b"stored bytes".to_vec()).expect("add stored");
+        assert!(writer.add_object("/PAGES/entry0000.HTM", b"dup".to_vec()).is_err());
+
+        let bytes = writer.finish().expect("finish");
+        let chm = ChmArchive::open(bytes).expect("open written CHM");
+
+        assert_eq!(chm.read_object("/img/noise.bin").expect("binary"), binary);
+        assert_eq!(chm.read_object("/empty.txt").expect("empty"), Vec::<u8>::new());
+        assert_eq!(chm.read_object("/raw/stored.dat").expect("stored"), b"stored bytes");
+        for (i, path) in paths.iter().enumerate().rev() {
+            assert_eq!(chm.read_object(path).expect("page"), page(i), "{path}");
+        }
+        assert_eq!(
+            chm.read_object_range("/img/noise.bin", 40_000, 16).expect("range"),
+            &binary[40_000..40_016]
+        );
+        assert_eq!(chm.entries().expect("listing").len(), paths.len() + 3 + 6);
+        assert!(chm.find_entry("/pages/missing.htm").expect("lookup").is_none());
+    }
+
+    #[test]
+    fn system_files_round_trip() {
+        let mut writer = ChmWriter::new(ChmWriterOptions {
+            block_mode: LzxBlockMode::Aligned,
+            window_bits: 17,
+            ..ChmWriterOptions::default()
+        });
+        writer.set_system_info(SystemInfo {
+            title: Some("독한 사전".to_string()),
+            lcid: Some(0x0412),
+            default_topic: Some("index.htm".to_string()),
+            timestamp: Some(0x6000_0000),
+            ..SystemInfo::default()
+        });
+        writer.add_object("/index.htm", b"<p>start</p>".to_vec()).expect("add index");
+        for i in 0..400 {
+            writer.add_topic(Some(&format!("항목 {i}")), &format!("pages/{i}.htm"), i % 2 == 0);
+        }
+
+        let chm = ChmArchive::open(writer.finish().expect("finish")).expect("open");
+        let system = ChmSystem::read(&chm).expect("system files");
+        assert_eq!(system.info.title.as_deref(), Some("독한 사전"));
+        assert_eq!(system.info.lcid, Some(0x0412));
+        assert_eq!(system.info.default_topic.as_deref(), Some("index.htm"));
+        assert_eq!(system.info.timestamp, Some(0x6000_0000));
+        assert_eq!(system.topics.len(), 400);
+        assert_eq!(system.topics[399].title.as_deref(), Some("항목 399"));
+        assert_eq!(system.topics[399].local.as_deref(), Some("pages/399.htm"));
+        assert!(system.topics[398].in_contents && !system.topics[399].in_contents);
+
+        let topic = read_topic(&chm, 350, system.info.encoding()).expect("topic").expect("row");
+        assert_eq!(topic.local.as_deref(), Some("pages/350.htm"));
+        assert_eq!(chm.read_object("/index.htm").expect("index"), b"<p>start</p>");
+    }
+
+    #[test]
+    fn rejects_invalid_options_and_oversized_paths() {
+        let window = ChmWriter::new(ChmWriterOptions {
+            window_bits: 22,
+            ..ChmWriterOptions::default()
+        });
+        assert!(window.finish().is_err());
+
+        let mut long = ChmWriter::new(ChmWriterOptions {
+            directory_block_len: 0x100,
+            ..ChmWriterOptions::default()
+        });
+        long.add_object(&format!("/{}", "a".repeat(300)), b"x".to_vec()).expect("add");
+        assert!(long.finish().is_err());
+
+        let empty = ChmWriter::default().finish().expect("empty container");
+        let chm = ChmArchive::open(empty).expect("open empty");
+        assert!(chm.entries().expect("listing").is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+//! Typed runtime errors returned to the frontend as `{ code, message, details }`.
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::{Serialize, Serializer};
+
+use crate::chm::archive::ChmError;
+
+/// Error raised by runtime helpers and Tauri commands.
+///
+/// `code` is stable so the UI can pick a recovery action (retry, re-pick the ZIP,
+/// rebuild the cache); `message` keeps the human-readable context.
+#[derive(Debug)]
+pub(crate) enum DokhanError {
+    /// Argument was empty or malformed.
+    InvalidInput(String),
+    /// No ZIP path was given and no managed copy exists yet.
+    ZipPathRequired,
+    /// Selected dataset path or URI cannot be located or opened.
+    SourceUnavailable(String),
+    /// Every enabled dictionary still needs its first build; holds their registry ids.
+    NotBuilt(Vec<String>),
+    /// Entry, page, CHM member, or asset lookup found nothing.
+    NotFound { kind: &'static str, key: String },
+    /// Link or platform feature the runtime does not handle.
+    Unsupported(String),
+    /// A runtime state mutex was poisoned by a panicking thread.
+    LockPoisoned(&'static str),
+    /// Build was stopped through `cancel_master_build`.
+    Cancelled,
+    Io { context: String, source: io::Error },
+    Zip { context: String, source: zip::result::ZipError },
+    Chm { context: String, source: ChmError },
+    Search { context: String, source: tantivy::TantivyError },
+    Cache { context: String, source: bincode::Error },
+    /// Worker failure not tied to a specific input.
+    Internal(String),
+}
+
+/// Serializable snapshot of a [`DokhanError`], also stored in build status.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ErrorPayload {
+    pub(crate) code: &'static str,
+    pub(crate) message: String,
+    pub(crate) details: Option<BTreeMap<&'static str, String>>,
+}
+
+impl DokhanError {
+    pub(crate) fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Self::Io { context: context.into(), source }
+    }
+
+    pub(crate) fn zip(context: impl Into<String>, source: zip::result::ZipError) -> Self {
+        Self::Zip { context: context.into(), source }
+    }
+
+    pub(crate) fn chm(context: impl Into<String>, source: ChmError) -> Self {
+        Self::Chm { context: context.into(), source }
+    }
+
+    pub(crate) fn search(context: impl Into<String>, source: tantivy::TantivyError) -> Self {
+        Self::Search { context: context.into(), source }
+    }
+
+    pub(crate) fn cache(context: impl Into<String>, source: bincode::Error) -> Self {
+        Self::Cache { context: context.into(), source }
+    }
+
+    pub(crate) fn not_found(kind: &'static str, key: impl Into<String>) -> Self {
+        Self::NotFound { kind, key: key.into() }
+    }
+
+    /// Stable machine-readable code for frontend recovery decisions.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidInput(_) => "invalid_input",
+            Self::ZipPathRequired => "zip_path_required",
+            Self::SourceUnavailable(_) => "source_unavailable",
+            Self::NotBuilt(_) => "not_built",
+            Self::NotFound { .. } => "not_found",
+            Self::Unsupported(_) => "unsupported",
+            Self::LockPoisoned(_) => "lock_poisoned",
+            Self::Cancelled => "cancelled",
+            Self::Io { .. } => "io",
+            Self::Zip { .. } => "zip",
+            Self::Chm { .. } => "chm",
+            Self::Search { .. } => "search_index",
+            Self::Cache { .. } => "cache",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    fn details(&self) -> Option<BTreeMap<&'static str, String>> {
+        let pairs: Vec<(&'static str, String)> = match self {
+            Self::NotBuilt(ids) => vec![("dictionaryIds", ids.join(","))],
+            Self::NotFound { kind, key } => vec![("kind", kind.to_string()), ("key", key.clone())],
+            Self::LockPoisoned(lock) => vec![("lock", lock.to_string())],
+            Self::Io { context, source } => vec![
+                ("context", context.clone()),
+                ("ioKind", format!("{:?}", source.kind())),
+            ],
+            Self::Zip { context, .. }
+            | Self::Chm { context, .. }
+            | Self::Search { context, .. }
+            | Self::Cache { context, .. } => vec![("context", context.clone())],
+            _ => return None,
+        };
+        Some(pairs.into_iter().filter(|(_, v)| !v.is_empty()).collect())
+    }
+
+    /// Snapshot code, message, and details for serialization.
+    pub(crate) fn payload(&self) -> ErrorPayload {
+        ErrorPayload {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+        }
+    }
+}
+
+fn write_wrapped(
+    f: &mut std::fmt::Formatter<'_>,
+    context: &str,
+    source: &dyn std::fmt::Display,
+) -> std::fmt::Result {
+    if context.is_empty() {
+        write!(f, "{source}")
+    } else {
+        write!(f, "{context}: {source}")
+    }
+}
+
+impl std::fmt::Display for DokhanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidInput(s) | Self::SourceUnavailable(s) | Self::Unsupported(s) | Self::Internal(s) => {
+                write!(f, "{s}")
+            }
+            Self::ZipPathRequired => write!(f, "zip path is required (no managed zip cache found)"),
+            Self::NotBuilt(ids) => write!(f, "no enabled dictionary has been built yet: {}", ids.join(", ")),
+            Self::NotFound { kind, key } => write!(f, "{kind} not found: {key}"),
+            Self::LockPoisoned(lock) => write!(f, "{lock} lock poisoned"),
+            Self::Cancelled => write!(f, "build cancelled"),
+            Self::Io { context, source } => write_wrapped(f, context, source),
+            Self::Zip { context, source } => write_wrapped(f, context, source),
+            Self::Chm { context, source } => write_wrapped(f, context, source),
+            Self::Search { context, source } => write_wrapped(f, context, source),
+            Self::Cache { context, source } => write_wrapped(f, context, source),
+        }
+    }
+}
+
+impl std::error::Error for DokhanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Zip { source, .. } => Some(source),
+            Self::Chm { source, .. } => Some(source),
+            Self::Search { source, .. } => Some(source),
+            Self::Cache { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for DokhanError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.payload().serialize(serializer)
+    }
+}
+
+impl From<io::Error> for DokhanError {
+    fn from(source: io::Error) -> Self {
+        Self::io("", source)
+    }
+}
+
+impl From<zip::result::ZipError> for DokhanError {
+    fn from(source: zip::result::ZipError) -> Self {
+        Self::zip("", source)
+    }
+}
+
+impl From<ChmError> for DokhanError {
+    fn from(source: ChmError) -> Self {
+        Self::chm("", source)
+    }
+}
+
+impl From<tantivy::TantivyError> for DokhanError {
+    fn from(source: tantivy::TantivyError) -> Self {
+        Self::search("", source)
+    }
+}
+
+impl From<bincode::Error> for DokhanError {
+    fn from(source: bincode::Error) -> Self {
+        Self::cache("", source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let err = DokhanError::not_found("entry", "42");
+        let json = serde_json::to_value(&err).expect("serialize");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "not_found",
+                "message": "entry not found: 42",
+                "details": { "kind": "entry", "key": "42" }
+            })
+        );
+
+        let json = serde_json::to_value(DokhanError::NotBuilt(vec!["a".to_string(), "b".to_string()])).expect("serialize");
+        assert_eq!(json["code"], "not_built");
+        assert_eq!(json["details"]["dictionaryIds"], "a,b");
+
+        let json = serde_json::to_value(DokhanError::ZipPathRequired).expect("serialize");
+        assert_eq!(json["code"], "zip_path_required");
+        assert!(json["details"].is_null());
+    }
+
+    #[test]
+    fn wrapped_sources_keep_context_and_chain() {
+        let err = DokhanError::chm("failed to open merge01.chm", ChmError::OutOfBounds);
+        assert_eq!(err.to_string(), "failed to open merge01.chm: CHM read out of bounds");
+        assert_eq!(err.payload().code, "chm");
+        assert!(std::error::Error::source(&err).is_some());
+
+        let bare: DokhanError = io::Error::new(io::ErrorKind::NotFound, "gone").into();
+        assert_eq!(bare.to_string(), "gone");
+        let details = bare.payload().details.expect("details");
+        assert_eq!(details.get("ioKind").map(String::as_str), Some("NotFound"));
+        assert!(!details.contains_key("context"));
+    }
+}
@@ -1,11 +1,13 @@
 //! Tauri backend crate entry for dictionary runtime/parsing layers.
-mod chm;
+pub mod chm;
 mod app;
+mod error;
 mod parsing;
 mod runtime;
 
 pub use app::commands::run;
 use crate::app::model::RuntimeSource;
+use crate::error::DokhanError;
 use tauri::AppHandle;
 
 /// Resolve runtime source from optional ZIP path argument.
@@ -13,18 +15,23 @@ use tauri::AppHandle;
 /// # Errors
 ///
 /// Returns an error when `zip_path` is missing or does not resolve to an existing ZIP file.
-fn resolve_runtime_source(app: &AppHandle, input: Option<String>) -> Result<RuntimeSource, String> {
+fn resolve_runtime_source(app: &AppHandle, input: Option<String>) -> Result<RuntimeSource, DokhanError> {
+    runtime::registry::ensure_dictionaries_migrated(app)?;
     match input {
         Some(raw) => {
             let resolved = parsing::dataset::resolve_zip_path(&raw)?;
             let managed = runtime::storage::ensure_managed_zip_copy(app, &resolved)?;
+            if managed != resolved {
+                // An imported ZIP may be new to the registry.
+                runtime::registry::invalidate_registry()?;
+            }
             Ok(RuntimeSource::ZipPath(managed))
         }
         None => {
             if let Some(found) = runtime::storage::latest_managed_zip(app)? {
                 return Ok(RuntimeSource::ZipPath(found));
             }
-            Err("zip path is required (no managed zip cache found)".to_string())
+            Err(DokhanError::ZipPathRequired)
         }
     }
 }
@@ -12,6 +12,7 @@ use zip::ZipArchive;
 
 #[cfg(test)]
 use crate::app::model::{DatasetSummary, FileTypeCount, MainVolumeCoverage};
+use crate::error::DokhanError;
 
 #[cfg(test)]
 fn basename_lower(name: &str) -> String {
@@ -27,9 +28,9 @@ fn basename_lower(name: &str) -> String {
 ///
 /// Returns an error when the ZIP file cannot be opened or parsed.
 #[cfg(test)]
-pub(crate) fn open_zip_archive(path: &Path) -> Result<ZipArchive<File>, String> {
-    let file = File::open(path).map_err(|e| format!("failed to open zip: {e}"))?;
-    ZipArchive::new(file).map_err(|e| format!("invalid zip archive: {e}"))
+pub(crate) fn open_zip_archive(path: &Path) -> Result<ZipArchive<File>, DokhanError> {
+    let file = File::open(path).map_err(|e| DokhanError::io("failed to open zip", e))?;
+    ZipArchive::new(file).map_err(|e| DokhanError::zip("invalid zip archive", e))
 }
 
 /// Compute coverage for merge01..merge36 including split volumes.
@@ -62,7 +63,7 @@ pub(crate) fn build_main_volume_coverage(names: &[String]) -> Vec<MainVolumeCove
 ///
 /// Returns an error when ZIP iteration fails.
 #[cfg(test)]
-pub(crate) fn summarize_zip(zip_path: &Path) -> Result<DatasetSummary, String> {
+pub(crate) fn summarize_zip(zip_path: &Path) -> Result<DatasetSummary, DokhanError> {
     let mut archive = open_zip_archive(zip_path)?;
 
     let mut total_files = 0usize;
@@ -75,7 +76,7 @@ pub(crate) fn summarize_zip(zip_path: &Path) -> Result<DatasetSummary, String> {
     for i in 0..archive.len() {
         let entry = archive
             .by_index(i)
-            .map_err(|e| format!("failed to read zip entry #{i}: {e}"))?;
+            .map_err(|e| DokhanError::zip(format!("failed to read zip entry #{i}"), e))?;
         let name = entry.name().to_string();
 
         if entry.is_dir() {
@@ -159,7 +160,7 @@ pub(crate) fn summarize_zip(zip_path: &Path) -> Result<DatasetSummary, String> {
 /// # Errors
 ///
 /// Returns an error when the path cannot be found from cwd/ancestor candidates.
-pub(crate) fn resolve_zip_path(input: &str) -> Result<PathBuf, String> {
+pub(crate) fn resolve_zip_path(input: &str) -> Result<PathBuf, DokhanError> {
     let raw = PathBuf::from(input);
     if raw.is_absolute() && raw.exists() {
         return Ok(raw);
@@ -168,7 +169,7 @@ pub(crate) fn resolve_zip_path(input: &str) -> Result<PathBuf, String> {
         return Ok(raw);
     }
 
-    let cwd = std::env::current_dir().map_err(|e| format!("failed to read current dir: {e}"))?;
+    let cwd = std::env::current_dir().map_err(|e| DokhanError::io("failed to read current dir", e))?;
     let mut attempts = Vec::new();
     for ancestor in cwd.ancestors() {
         let candidate = ancestor.join(&raw);
@@ -178,11 +179,11 @@ pub(crate) fn resolve_zip_path(input: &str) -> Result<PathBuf, String> {
         }
     }
 
-    Err(format!(
+    Err(DokhanError::SourceUnavailable(format!(
         "zip not found: '{input}'. searched current/ancestor paths from '{}'. attempts: {}",
         cwd.display(),
         attempts.join(", ")
-    ))
+    )))
 }
 
 #[cfg(test)]
@@ -0,0 +1,61 @@
+//! Korean gloss extraction from German–Korean definition text.
+
+use crate::parsing::text::is_hangul;
+
+/// Glosses kept per entry; reverse lookup only ranks the leading senses.
+const MAX_GLOSSES: usize = 16;
+/// Segments with more words are example translations rather than glosses.
+const MAX_GLOSS_WORDS: usize = 3;
+
+fn is_separator(c: char) -> bool {
+    matches!(c, ';' | ',' | '.' | ':' | '/' | '|' | '·' | '=' | '\u{2460}'..='\u{2473}' | '\u{2776}'..='\u{277F}')
+        || c.is_ascii_digit()
+}
+
+/// Korean glosses of `definition` in order of appearance, without duplicates.
+///
+/// The text is split at punctuation and sense markers (`1.`, `①`), and bracketed notes are
+/// skipped. A segment counts as a gloss when it holds one to three Hangul-only words, so
+/// German examples and their sentence translations are left out.
+pub(crate) fn extract_glosses(definition: &str) -> Vec<String> {
+    let mut glosses = Vec::<String>::new();
+    let mut segment = String::new();
+    let mut depth = 0usize;
+    let mut flush = |segment: &mut String| {
+        let words = segment.split_whitespace().collect::<Vec<_>>();
+        let is_gloss = (1..=MAX_GLOSS_WORDS).contains(&words.len())
+            && words.iter().all(|w| w.chars().all(is_hangul));
+        if is_gloss && glosses.len() < MAX_GLOSSES {
+            let gloss = words.join(" ");
+            if !glosses.contains(&gloss) {
+                glosses.push(gloss);
+            }
+        }
+        segment.clear();
+    };
+    for c in definition.chars() {
+        match c {
+            '(' | '[' | '{' | '<' | '〈' | '《' | '「' | '『' => depth += 1,
+            ')' | ']' | '}' | '>' | '〉' | '》' | '」' | '』' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            _ if is_separator(c) => flush(&mut segment),
+            _ => segment.push(c),
+        }
+    }
+    flush(&mut segment);
+    glosses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_hangul_glosses_in_sense_order() {
+        let definition = "Apfel m. -s, Äpfel ① 사과; 사과나무 ② (비유) 둥근 것. Er isst einen Apfel. \
+                          그는 매일 아침 사과를 먹는다. 1. 사과";
+        assert_eq!(extract_glosses(definition), ["사과", "사과나무", "둥근 것"]);
+        assert!(extract_glosses("Haus n. -es, Häuser").is_empty());
+        assert_eq!(extract_glosses("[건축] 집, 가옥 (pl. Häuser)"), ["집", "가옥"]);
+    }
+}
@@ -0,0 +1,141 @@
+//! Hangul decomposition for initial-consonant (chosung) and jamo-level matching.
+//!
+//! Jamo keys spell every syllable as basic compatibility jamo, splitting compound vowels and
+//! final clusters (`과` → `ㄱㅗㅏ`). A syllable still being composed in an IME is then a prefix
+//! of the finished word: `삭` (`ㅅㅏㄱ`) is a prefix of `사과` (`ㅅㅏㄱㅗㅏ`).
+
+use crate::parsing::text::is_hangul;
+
+const SYLLABLE_FIRST: u32 = 0xAC00;
+const SYLLABLE_LAST: u32 = 0xD7A3;
+const JUNGSUNG_COUNT: u32 = 21;
+const JONGSUNG_COUNT: u32 = 28;
+
+const CHOSUNG: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+const JUNGSUNG: [&str; 21] = [
+    "ㅏ", "ㅐ", "ㅑ", "ㅒ", "ㅓ", "ㅔ", "ㅕ", "ㅖ", "ㅗ", "ㅗㅏ", "ㅗㅐ", "ㅗㅣ", "ㅛ", "ㅜ", "ㅜㅓ", "ㅜㅔ", "ㅜㅣ", "ㅠ",
+    "ㅡ", "ㅡㅣ", "ㅣ",
+];
+const JONGSUNG: [&str; 28] = [
+    "", "ㄱ", "ㄲ", "ㄱㅅ", "ㄴ", "ㄴㅈ", "ㄴㅎ", "ㄷ", "ㄹ", "ㄹㄱ", "ㄹㅁ", "ㄹㅂ", "ㄹㅅ", "ㄹㅌ", "ㄹㅍ", "ㄹㅎ", "ㅁ",
+    "ㅂ", "ㅂㅅ", "ㅅ", "ㅆ", "ㅇ", "ㅈ", "ㅊ", "ㅋ", "ㅌ", "ㅍ", "ㅎ",
+];
+/// Compound compatibility jamo typed on their own, with their basic parts.
+const COMPOUND_JAMO: [(char, &str); 18] = [
+    ('ㄳ', "ㄱㅅ"),
+    ('ㄵ', "ㄴㅈ"),
+    ('ㄶ', "ㄴㅎ"),
+    ('ㄺ', "ㄹㄱ"),
+    ('ㄻ', "ㄹㅁ"),
+    ('ㄼ', "ㄹㅂ"),
+    ('ㄽ', "ㄹㅅ"),
+    ('ㄾ', "ㄹㅌ"),
+    ('ㄿ', "ㄹㅍ"),
+    ('ㅀ', "ㄹㅎ"),
+    ('ㅄ', "ㅂㅅ"),
+    ('ㅘ', "ㅗㅏ"),
+    ('ㅙ', "ㅗㅐ"),
+    ('ㅚ', "ㅗㅣ"),
+    ('ㅝ', "ㅜㅓ"),
+    ('ㅞ', "ㅜㅔ"),
+    ('ㅟ', "ㅜㅣ"),
+    ('ㅢ', "ㅡㅣ"),
+];
+
+/// Syllable index of `c` within the precomposed block.
+fn syllable_index(c: char) -> Option<u32> {
+    let code = c as u32;
+    (SYLLABLE_FIRST..=SYLLABLE_LAST).contains(&code).then(|| code - SYLLABLE_FIRST)
+}
+
+/// Whether `c` is a compatibility consonant such as `ㅅ`.
+fn is_compat_consonant(c: char) -> bool {
+    matches!(c, 'ㄱ'..='ㅎ')
+}
+
+/// Spell Hangul syllables and compound jamo as basic jamo; other characters are kept.
+pub(crate) fn decompose_jamo(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 3);
+    for c in s.chars() {
+        if let Some(index) = syllable_index(c) {
+            out.push(CHOSUNG[(index / (JUNGSUNG_COUNT * JONGSUNG_COUNT)) as usize]);
+            out.push_str(JUNGSUNG[(index / JONGSUNG_COUNT % JUNGSUNG_COUNT) as usize]);
+            out.push_str(JONGSUNG[(index % JONGSUNG_COUNT) as usize]);
+        } else if let Some((_, parts)) = COMPOUND_JAMO.iter().find(|(jamo, _)| *jamo == c) {
+            out.push_str(parts);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Replace each Hangul syllable with its initial consonant (`사과` → `ㅅㄱ`); other characters are kept.
+pub(crate) fn chosung(s: &str) -> String {
+    s.chars()
+        .map(|c| syllable_index(c).map_or(c, |index| CHOSUNG[(index / (JUNGSUNG_COUNT * JONGSUNG_COUNT)) as usize]))
+        .collect()
+}
+
+/// Whether `s` consists of initial consonants only, ignoring whitespace (`ㅅㄱ`).
+pub(crate) fn is_chosung_query(s: &str) -> bool {
+    let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+    chars.peek().is_some() && chars.all(is_compat_consonant)
+}
+
+/// Whether `s` holds a standalone jamo, as left behind by an unfinished IME composition.
+pub(crate) fn has_loose_jamo(s: &str) -> bool {
+    s.chars().any(|c| is_hangul(c) && syllable_index(c).is_none())
+}
+
+/// Space-free jamo and chosung keys of `s`, or `None` when it holds no Hangul.
+pub(crate) fn hangul_keys(s: &str) -> Option<(String, String)> {
+    if !s.chars().any(is_hangul) {
+        return None;
+    }
+    let compact = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    Some((decompose_jamo(&compact), chosung(&compact)))
+}
+
+/// [`hangul_keys`] of every suffix of `s` starting at a character, longest first; empty when
+/// `s` holds no Hangul.
+///
+/// A substring match at a syllable start is a prefix match on one of these keys.
+pub(crate) fn hangul_suffix_keys(s: &str) -> Vec<(String, String)> {
+    if !s.chars().any(is_hangul) {
+        return Vec::new();
+    }
+    let compact = s.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    (0..compact.len())
+        .map(|start| {
+            let rest = compact[start..].iter().collect::<String>();
+            (decompose_jamo(&rest), chosung(&rest))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decomposes_syllables_for_chosung_and_partial_matching() {
+        assert_eq!(decompose_jamo("사과"), "ㅅㅏㄱㅗㅏ");
+        assert_eq!(decompose_jamo("닭값"), "ㄷㅏㄹㄱㄱㅏㅂㅅ");
+        assert_eq!(decompose_jamo("ㅘ Haus"), "ㅗㅏ Haus");
+        assert!(decompose_jamo("사과").starts_with(&decompose_jamo("삭")));
+        assert_eq!(chosung("사과나무"), "ㅅㄱㄴㅁ");
+        assert_eq!(chosung("꽃 A"), "ㄲ A");
+        assert!(is_chosung_query("ㅅㄱ ㄴㅁ"));
+        assert!(!is_chosung_query("ㅅ과") && !is_chosung_query(" "));
+        assert!(has_loose_jamo("사ㄱ") && !has_loose_jamo("사과"));
+        assert_eq!(hangul_keys("둥근 것"), Some(("ㄷㅜㅇㄱㅡㄴㄱㅓㅅ".to_string(), "ㄷㄱㄱ".to_string())));
+        assert_eq!(hangul_keys("Haus"), None);
+        let suffixes = hangul_suffix_keys("사과 나무");
+        assert_eq!(suffixes.first().cloned(), hangul_keys("사과 나무"));
+        assert_eq!(suffixes[2], ("ㄴㅏㅁㅜ".to_string(), "ㄴㅁ".to_string()));
+        assert!(hangul_suffix_keys("Haus").is_empty());
+    }
+}
@@ -1,9 +1,11 @@
-//! CHM index/content parsing helpers (`.hhk`, `.hhc`, and filename fallbacks).
+//! CHM index/content parsing helpers (`.hhk`, `.hhc`, `#TOPICS`, and filename fallbacks).
 use std::collections::BTreeSet;
 
 use crate::chm;
 use crate::app::model::{ContentItem, EntryDetail};
-use crate::parsing::text::{compact_ws, decode_euc_kr, extract_attr_value, path_stem};
+use encoding_rs::{Encoding, EUC_KR};
+
+use crate::parsing::text::{compact_ws, decode_chm_text, extract_attr_value, path_stem};
 use crate::runtime::link_media::{normalize_path, parse_internal_ref};
 
 #[cfg(test)]
@@ -165,9 +167,9 @@ fn extract_all_headwords_from_paths(html_paths: Vec<String>) -> Vec<String> {
     words.into_iter().collect::<Vec<_>>()
 }
 
-fn extract_all_headwords_from_open_chm(chm: &chm::ChmArchive) -> Vec<String> {
+fn extract_all_headwords_from_open_chm(chm: &chm::ChmArchive) -> Result<Vec<String>, chm::ChmError> {
     let html_paths = chm
-        .entries()
+        .entries()?
         .iter()
         .filter(|entry| {
             let p = entry.path.to_ascii_lowercase();
@@ -175,11 +177,16 @@ fn extract_all_headwords_from_open_chm(chm: &chm::ChmArchive) -> Vec<String> {
         })
         .map(|entry| entry.path.clone())
         .collect::<Vec<_>>();
-    extract_all_headwords_from_paths(html_paths)
+    Ok(extract_all_headwords_from_paths(html_paths))
+}
+
+/// Encoding declared by the CHM `#SYSTEM` LCID, if any.
+pub(crate) fn chm_declared_encoding(chm: &chm::ChmArchive) -> Option<&'static Encoding> {
+    chm::system::SystemInfo::read(chm).ok()?.declared_encoding()
 }
 
 /// Parse HHK sitemap block into entry rows.
-fn parse_hhk_entries_from_text(text: &str, default_source_path: &str) -> Vec<EntryDetail> {
+fn parse_hhk_entries_from_text(text: &str, default_source_path: &str, encoding: &'static Encoding) -> Vec<EntryDetail> {
     let mut out = Vec::new();
     for_each_sitemap_object(text, |block| {
         let mut name = String::new();
@@ -221,21 +228,63 @@ fn parse_hhk_entries_from_text(text: &str, default_source_path: &str) -> Vec<Ent
                 target_local,
                 definition_text: String::new(),
                 definition_html: String::new(),
+                encoding: encoding.name().to_string(),
             });
         }
     });
     out
 }
 
-/// Extract index entries from CHM (HHK first, filename fallback).
+/// Build entry rows from compiled `#TOPICS` titles for CHMs without a usable HHK.
+fn extract_entries_from_topics(chm_file_name: &str, chm: &chm::ChmArchive) -> Vec<EntryDetail> {
+    let Ok(system) = chm::ChmSystem::read_with_fallback(chm, EUC_KR) else {
+        return Vec::new();
+    };
+    let encoding = system.info.declared_encoding().unwrap_or(EUC_KR).name();
+    let mut out = Vec::new();
+    for topic in system.topics {
+        let (Some(title), Some(local)) = (topic.title, topic.local) else {
+            continue;
+        };
+        let lower = local.to_ascii_lowercase();
+        if !(lower.ends_with(".htm") || lower.ends_with(".html")) {
+            continue;
+        }
+        let headword = compact_ws(&title);
+        let target_local = normalize_path(&local);
+        let mut aliases = vec![headword.clone()];
+        let target_stem = compact_ws(&path_stem(&target_local));
+        if !target_stem.is_empty() && !aliases.contains(&target_stem) {
+            aliases.push(target_stem);
+        }
+        out.push(EntryDetail {
+            id: 0,
+            headword,
+            aliases,
+            source_path: chm_file_name.to_ascii_lowercase(),
+            target_local,
+            definition_text: String::new(),
+            definition_html: String::new(),
+            encoding: encoding.to_string(),
+        });
+    }
+    out
+}
+
+/// Extract index entries from CHM (HHK first, then `#TOPICS`, then filenames).
+///
+/// # Errors
+///
+/// Returns an error when the CHM directory listing is corrupt.
 pub(crate) fn extract_index_entries_from_open_chm(
     chm_file_name: &str,
-    chm: &mut chm::ChmArchive,
-) -> Vec<EntryDetail> {
+    chm: &chm::ChmArchive,
+) -> Result<Vec<EntryDetail>, chm::ChmError> {
     let mut out = Vec::new();
+    let declared = chm_declared_encoding(chm);
 
     let hhk_paths = chm
-        .entries()
+        .entries()?
         .iter()
         .filter(|entry| entry.path.to_ascii_lowercase().ends_with(".hhk"))
         .map(|entry| entry.path.clone())
@@ -243,13 +292,17 @@ pub(crate) fn extract_index_entries_from_open_chm(
 
     for hhk_path in hhk_paths {
         if let Ok(bytes) = chm.read_object(&hhk_path) {
-            let text = decode_euc_kr(&bytes);
-            out.append(&mut parse_hhk_entries_from_text(&text, chm_file_name));
+            let (text, encoding) = decode_chm_text(&bytes, declared);
+            out.append(&mut parse_hhk_entries_from_text(&text, chm_file_name, encoding));
         }
     }
 
     if out.is_empty() {
-        for word in extract_all_headwords_from_open_chm(chm) {
+        out = extract_entries_from_topics(chm_file_name, chm);
+    }
+
+    if out.is_empty() {
+        for word in extract_all_headwords_from_open_chm(chm)? {
             out.push(EntryDetail {
                 id: 0,
                 headword: word.clone(),
@@ -258,11 +311,12 @@ pub(crate) fn extract_index_entries_from_open_chm(
                 target_local: String::new(),
                 definition_text: String::new(),
                 definition_html: String::new(),
+                encoding: declared.unwrap_or(EUC_KR).name().to_string(),
             });
         }
     }
 
-    out
+    Ok(out)
 }
 
 /// Test helper: parse HHK-style `<param name=\"Name\">` headwords.
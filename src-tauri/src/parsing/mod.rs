@@ -1,4 +1,6 @@
 //! Parsing layer: ZIP dataset stats, CHM index extraction, and text utilities.
 pub(crate) mod dataset;
+pub(crate) mod gloss;
+pub(crate) mod hangul;
 pub(crate) mod index;
 pub(crate) mod text;
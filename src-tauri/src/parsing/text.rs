@@ -1,5 +1,8 @@
 //! Text/HTML utility helpers used by CHM parsing and runtime decoding.
-use encoding_rs::EUC_KR;
+use encoding_rs::{Encoding, EUC_KR};
+
+/// How far into a document `<meta charset>` is looked for (HTML prescan length).
+const META_PRESCAN_LEN: usize = 1024;
 
 /// Key HTML fragments extracted in one pass-friendly flow.
 pub(crate) struct HtmlFragments {
@@ -8,10 +11,46 @@ pub(crate) struct HtmlFragments {
     pub(crate) first_paragraph_html: Option<String>,
 }
 
-/// Decode EUC-KR bytes used by this dictionary dataset.
-pub(crate) fn decode_euc_kr(bytes: &[u8]) -> String {
-    let (s, _, _) = EUC_KR.decode(bytes);
-    s.into_owned()
+/// Read the label following `charset` in a lowercased `<meta>` tag.
+fn meta_charset_label(tag: &str) -> Option<&str> {
+    let rest = &tag[tag.find("charset")? + "charset".len()..];
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let rest = rest.trim_start_matches(['"', '\'']);
+    let end = rest
+        .find(|c: char| c == '"' || c == '\'' || c == ';' || c == '>' || c == '/' || c.is_whitespace())
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|label| !label.is_empty())
+}
+
+/// Find the encoding declared by `<meta charset>` or `<meta content="...; charset=...">`.
+pub(crate) fn sniff_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(META_PRESCAN_LEN)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let mut offset = 0usize;
+    while let Some(rel) = head[offset..].find("<meta") {
+        let start = offset + rel;
+        let end = head[start..].find('>').map_or(head.len(), |e| start + e);
+        if let Some(found) = meta_charset_label(&head[start..end]).and_then(|l| Encoding::for_label(l.as_bytes())) {
+            // A declared UTF-16 charset cannot apply to ASCII-readable bytes.
+            return Some(found.output_encoding());
+        }
+        offset = end;
+    }
+    None
+}
+
+/// Decode CHM text bytes and return the text with the encoding actually used.
+///
+/// The encoding comes from the CHM `#SYSTEM` codepage, then `<meta charset>`, then a BOM,
+/// and falls back to EUC-KR, the codepage of the bundled dictionary dataset.
+/// A BOM that contradicts the chosen encoding still wins, as in browsers.
+pub(crate) fn decode_chm_text(bytes: &[u8], chm_encoding: Option<&'static Encoding>) -> (String, &'static Encoding) {
+    let chosen = chm_encoding
+        .or_else(|| sniff_meta_charset(bytes))
+        .or_else(|| Encoding::for_bom(bytes).map(|(found, _)| found))
+        .unwrap_or(EUC_KR);
+    let (text, used, _) = chosen.decode(bytes);
+    (text.into_owned(), used)
 }
 
 /// Decode a minimal set of HTML entities used in legacy pages.
@@ -45,6 +84,11 @@ pub(crate) fn strip_html_tags(input: &str) -> String {
         .replace("&middot;", "·")
 }
 
+/// Whether `c` is a Hangul syllable or jamo.
+pub(crate) fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7A3}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}')
+}
+
 /// Collapse consecutive whitespace to single spaces.
 pub(crate) fn compact_ws(input: &str) -> String {
     input.split_whitespace().collect::<Vec<_>>().join(" ")
@@ -172,3 +216,32 @@ pub(crate) fn extract_attr_value(tag: &str, attr_name: &str) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_meta_charset_variants() {
+        let http_equiv = br#"<html><head><META http-equiv="Content-Type" content="text/html; charset=Shift_JIS"></head>"#;
+        assert_eq!(sniff_meta_charset(http_equiv), Some(encoding_rs::SHIFT_JIS));
+        let html5 = b"<meta charset='windows-1252'/><p>x</p>";
+        assert_eq!(sniff_meta_charset(html5), Some(encoding_rs::WINDOWS_1252));
+        assert_eq!(sniff_meta_charset(b"<meta name=\"author\"><p>charset=utf-8</p>"), None);
+    }
+
+    #[test]
+    fn decode_prefers_chm_codepage_then_meta_then_bom() {
+        let latin = b"<meta charset=\"windows-1252\">Stra\xdfe";
+        assert_eq!(
+            decode_chm_text(latin, None),
+            ("<meta charset=\"windows-1252\">Straße".to_string(), encoding_rs::WINDOWS_1252)
+        );
+        let (_, used) = decode_chm_text(latin, Some(encoding_rs::EUC_KR));
+        assert_eq!(used, encoding_rs::EUC_KR);
+
+        let bom = b"\xef\xbb\xbfHaus";
+        assert_eq!(decode_chm_text(bom, None), ("Haus".to_string(), encoding_rs::UTF_8));
+        assert_eq!(decode_chm_text(b"\xc7\xd1", None), ("\u{d55c}".to_string(), EUC_KR));
+    }
+}
@@ -0,0 +1,40 @@
+//! Cooperative cancellation for long-running builds.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::DokhanError;
+
+/// Shared flag checked between units of build work.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Whether both handles share one flag.
+    pub(crate) fn same_as(&self, other: &CancelToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Return [`DokhanError::Cancelled`] once `token` has been cancelled.
+///
+/// # Errors
+///
+/// Returns an error when the token is present and cancelled.
+pub(crate) fn check_cancelled(token: Option<&CancelToken>) -> Result<(), DokhanError> {
+    match token {
+        Some(token) if token.is_cancelled() => Err(DokhanError::Cancelled),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,115 @@
+//! German compound decomposition against a headword lexicon.
+//!
+//! A split covers the whole word with known parts, optionally joined by a linking element
+//! (Fugenelement). Among all covers, the one with the fewest parts wins, then the one with
+//! the fewest linking elements, then the one with the longest leading part.
+
+/// Linking elements that may follow a non-final part.
+const LINKING_ELEMENTS: &[&str] = &["s", "es", "n", "en"];
+/// Parts shorter than this are ignored, so particles and endings never count as words.
+const MIN_PART_CHARS: usize = 3;
+/// Longer input is not split; real compounds stay well below this.
+const MAX_WORD_CHARS: usize = 64;
+
+/// One part of a split compound.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CompoundSegment<T> {
+    /// Part as spelled in the input.
+    pub(crate) text: String,
+    /// Linking element between this part and the next, as spelled in the input.
+    pub(crate) linking: Option<String>,
+    /// Lexicon value the part resolved to.
+    pub(crate) value: T,
+}
+
+/// Best cover of a suffix: (parts, linking elements), then the segments in order.
+type Cover<T> = ((usize, usize), Vec<CompoundSegment<T>>);
+
+/// Split `word` into at least two parts that `lookup` resolves, or `None` when no cover exists.
+///
+/// `lookup` receives lowercased candidate parts.
+pub(crate) fn split_compound<T: Clone>(word: &str, lookup: impl Fn(&str) -> Option<T>) -> Option<Vec<CompoundSegment<T>>> {
+    let surface = word.trim().chars().collect::<Vec<_>>();
+    let lower = surface.iter().flat_map(|c| c.to_lowercase()).collect::<Vec<_>>();
+    // `to_lowercase` may change the length (`İ`); such input is not a German compound.
+    if lower.len() != surface.len() || surface.len() < 2 * MIN_PART_CHARS || surface.len() > MAX_WORD_CHARS {
+        return None;
+    }
+    let n = surface.len();
+    let text = |from: usize, to: usize| surface[from..to].iter().collect::<String>();
+
+    // best[i] is the best cover of the suffix starting at char i.
+    let mut best: Vec<Option<Cover<T>>> = vec![None; n + 1];
+    best[n] = Some(((0, 0), Vec::new()));
+    for i in (0..n).rev() {
+        // Longest leading part first, so ties keep it.
+        for j in (i + MIN_PART_CHARS..=n).rev() {
+            let part = lower[i..j].iter().collect::<String>();
+            let Some(value) = lookup(&part) else {
+                continue;
+            };
+            let links = std::iter::once("").chain(LINKING_ELEMENTS.iter().copied()).filter(|link| {
+                let link = link.chars().collect::<Vec<_>>();
+                (j == n && link.is_empty()) || (j < n && lower[j..].starts_with(&link))
+            });
+            for link in links {
+                let next = j + link.chars().count();
+                if next == n && !link.is_empty() {
+                    continue;
+                }
+                let Some(((parts, linkings), rest)) = &best[next] else {
+                    continue;
+                };
+                let cost = (parts + 1, linkings + usize::from(!link.is_empty()));
+                if best[i].as_ref().is_some_and(|(current, _)| *current <= cost) {
+                    continue;
+                }
+                let mut segments = Vec::with_capacity(rest.len() + 1);
+                segments.push(CompoundSegment {
+                    text: text(i, j),
+                    linking: (!link.is_empty()).then(|| text(j, next)),
+                    value: value.clone(),
+                });
+                segments.extend(rest.iter().cloned());
+                best[i] = Some((cost, segments));
+            }
+        }
+    }
+    best[0].take().map(|(_, segments)| segments).filter(|segments| segments.len() >= 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(word: &str, lexicon: &[&str]) -> Option<Vec<(String, Option<String>)>> {
+        split_compound(word, |part| lexicon.contains(&part).then_some(())).map(|segments| {
+            segments.into_iter().map(|s| (s.text, s.linking)).collect()
+        })
+    }
+
+    fn parts(items: &[(&str, Option<&str>)]) -> Option<Vec<(String, Option<String>)>> {
+        Some(items.iter().map(|(t, l)| (t.to_string(), l.map(str::to_string))).collect())
+    }
+
+    #[test]
+    fn splits_compounds_with_linking_elements() {
+        let lexicon = ["haus", "tür", "schlüssel", "donau", "dampf", "schiff", "fahrt", "arbeit", "amt", "hau",
+            "blume", "topf", "tag", "licht", "frau", "arzt", "liebe", "brief"];
+        assert_eq!(
+            split("Haustürschlüssel", &lexicon),
+            parts(&[("Haus", None), ("tür", None), ("schlüssel", None)])
+        );
+        assert_eq!(
+            split("Donaudampfschifffahrt", &lexicon),
+            parts(&[("Donau", None), ("dampf", None), ("schiff", None), ("fahrt", None)])
+        );
+        assert_eq!(split("Arbeitsamt", &lexicon), parts(&[("Arbeit", Some("s")), ("amt", None)]));
+        assert_eq!(split("Blumentopf", &lexicon), parts(&[("Blume", Some("n")), ("topf", None)]));
+        assert_eq!(split("Liebesbrief", &lexicon), parts(&[("Liebe", Some("s")), ("brief", None)]));
+        assert_eq!(split("Tageslicht", &lexicon), parts(&[("Tag", Some("es")), ("licht", None)]));
+        assert_eq!(split("Frauenarzt", &lexicon), parts(&[("Frau", Some("en")), ("arzt", None)]));
+        assert_eq!(split("Haus", &lexicon), None);
+        assert_eq!(split("Hausxtür", &lexicon), None);
+    }
+}
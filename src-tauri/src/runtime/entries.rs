@@ -0,0 +1,553 @@
+//! Entry table backed either by owned vectors or by a memory-mapped index file.
+//!
+//! The mapped layout (little-endian) is a fixed header, one fixed-size record per entry,
+//! one fixed-size slot per alias, then a UTF-8 string region. Records and slots hold
+//! `(offset, len)` pairs into the string region, so entries and their precomputed search
+//! keys are read in place without decoding the whole table.
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap2::Mmap;
+
+use crate::app::model::{EntryDetail, EntrySearchKey};
+use crate::error::DokhanError;
+use crate::runtime::search::build_entry_search_keys;
+
+const ENTRY_INDEX_MAGIC: &[u8; 8] = b"DKENTIDX";
+const ENTRY_INDEX_FORMAT: u32 = 1;
+const HEADER_LEN: usize = 48;
+
+// Entry record: id (u64), thirteen string refs, alias start and count (u32 each).
+const STR_HEADWORD: usize = 0;
+const STR_SOURCE_PATH: usize = 1;
+const STR_TARGET_LOCAL: usize = 2;
+const STR_DEFINITION_TEXT: usize = 3;
+const STR_DEFINITION_HTML: usize = 4;
+const STR_ENCODING: usize = 5;
+const STR_KEY_HEADWORD: usize = 6;
+const STR_KEY_HEADWORD_LOOSE: usize = 7;
+const STR_KEY_BODY: usize = 8;
+const STR_KEY_BODY_LOOSE: usize = 9;
+const STR_KEY_GLOSSES: usize = 10;
+const STR_KEY_GLOSSES_JAMO: usize = 11;
+const STR_KEY_GLOSSES_CHOSUNG: usize = 12;
+const RECORD_STRS: usize = 13;
+const RECORD_LEN: usize = 8 + RECORD_STRS * 8 + 8;
+
+// Alias slot: alias text, strict key, loose key, jamo key, chosung key.
+const ALIAS_TEXT: usize = 0;
+const ALIAS_KEY: usize = 1;
+const ALIAS_LOOSE: usize = 2;
+const ALIAS_JAMO: usize = 3;
+const ALIAS_CHOSUNG: usize = 4;
+const ALIAS_STRS: usize = 5;
+const ALIAS_LEN: usize = ALIAS_STRS * 8;
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    bytes
+        .get(at..at + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .unwrap_or(0)
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    bytes
+        .get(at..at + 8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+        .unwrap_or(0)
+}
+
+/// Read-only view over a memory-mapped entry index.
+#[derive(Debug)]
+pub(crate) struct MappedEntries {
+    map: Mmap,
+    count: usize,
+    alias_count: usize,
+    records_at: usize,
+    aliases_at: usize,
+    strings_at: usize,
+}
+
+impl MappedEntries {
+    /// Map `path` and validate its header, section bounds, and every string reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be mapped, its header is inconsistent, or a
+    /// record points outside its section or at invalid UTF-8.
+    pub(crate) fn open(path: &Path) -> Result<Self, DokhanError> {
+        let file = File::open(path).map_err(|e| DokhanError::io("failed to open entry index", e))?;
+        // SAFETY: cache files are only ever replaced by rename, never rewritten in place,
+        // so the mapped bytes stay stable for the lifetime of this map.
+        let map = unsafe { Mmap::map(&file) }.map_err(|e| DokhanError::io("failed to map entry index", e))?;
+        let invalid = |what: &str| DokhanError::Internal(format!("entry index {what}"));
+
+        if map.len() < HEADER_LEN || &map[..8] != ENTRY_INDEX_MAGIC {
+            return Err(invalid("magic mismatch"));
+        }
+        let format = read_u32(&map, 8);
+        if format != ENTRY_INDEX_FORMAT {
+            return Err(invalid(&format!("format v{format} is not supported")));
+        }
+        let count = usize::try_from(read_u32(&map, 12)).map_err(|_| invalid("entry count overflows"))?;
+        let alias_count = usize::try_from(read_u32(&map, 16)).map_err(|_| invalid("alias count overflows"))?;
+        let strings_len = usize::try_from(read_u64(&map, 24)).map_err(|_| invalid("string region overflows"))?;
+        let records_at = HEADER_LEN;
+        let aliases_at = count
+            .checked_mul(RECORD_LEN)
+            .and_then(|len| records_at.checked_add(len))
+            .ok_or_else(|| invalid("records overflow"))?;
+        let strings_at = alias_count
+            .checked_mul(ALIAS_LEN)
+            .and_then(|len| aliases_at.checked_add(len))
+            .ok_or_else(|| invalid("aliases overflow"))?;
+        if strings_at.checked_add(strings_len) != Some(map.len()) {
+            return Err(invalid("sections do not match file length"));
+        }
+        let mapped = Self {
+            map,
+            count,
+            alias_count,
+            records_at,
+            aliases_at,
+            strings_at,
+        };
+        mapped.validate().map_err(invalid)?;
+        Ok(mapped)
+    }
+
+    /// Check every string reference and alias range once, so reads never need a fallback.
+    fn validate(&self) -> Result<(), &'static str> {
+        for index in 0..self.count {
+            let record = self.record(index);
+            for field in 0..RECORD_STRS {
+                self.checked_string(record, 8 + field * 8)
+                    .ok_or("record string is out of bounds or not UTF-8")?;
+            }
+            let at = 8 + RECORD_STRS * 8;
+            let start = usize::try_from(read_u32(record, at)).ok();
+            let len = usize::try_from(read_u32(record, at + 4)).ok();
+            let end = start.zip(len).and_then(|(start, len)| start.checked_add(len));
+            if !matches!(end, Some(end) if end <= self.alias_count) {
+                return Err("alias range is out of bounds");
+            }
+        }
+        for slot in 0..self.alias_count {
+            let at = self.aliases_at + slot * ALIAS_LEN;
+            let slot = &self.map[at..at + ALIAS_LEN];
+            for field in 0..ALIAS_STRS {
+                self.checked_string(slot, field * 8)
+                    .ok_or("alias string is out of bounds or not UTF-8")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&self, index: usize) -> &[u8] {
+        let at = self.records_at + index * RECORD_LEN;
+        &self.map[at..at + RECORD_LEN]
+    }
+
+    /// String at `(offset, len)` stored at `at` in `slot`; `None` when out of bounds or not UTF-8.
+    fn checked_string(&self, slot: &[u8], at: usize) -> Option<&str> {
+        let range = || {
+            let start = self.strings_at.checked_add(usize::try_from(read_u32(slot, at)).ok()?)?;
+            let end = start.checked_add(usize::try_from(read_u32(slot, at + 4)).ok()?)?;
+            Some(start..end)
+        };
+        range()
+            .and_then(|range| self.map.get(range))
+            .and_then(|b| std::str::from_utf8(b).ok())
+    }
+
+    /// String at `at` in `slot`, which [`Self::validate`] has already checked.
+    fn string(&self, slot: &[u8], at: usize) -> &str {
+        self.checked_string(slot, at).unwrap_or("")
+    }
+
+    fn record_str(&self, index: usize, field: usize) -> &str {
+        self.string(self.record(index), 8 + field * 8)
+    }
+
+    fn alias_range(&self, index: usize) -> (usize, usize) {
+        let record = self.record(index);
+        let at = 8 + RECORD_STRS * 8;
+        let start = read_u32(record, at) as usize;
+        let len = read_u32(record, at + 4) as usize;
+        let start = start.min(self.alias_count);
+        (start, (start + len).min(self.alias_count))
+    }
+
+    fn alias_str(&self, slot: usize, field: usize) -> &str {
+        let at = self.aliases_at + slot * ALIAS_LEN;
+        self.string(&self.map[at..at + ALIAS_LEN], field * 8)
+    }
+}
+
+/// Index entries with their precomputed search keys.
+#[derive(Debug, Clone)]
+pub(crate) enum EntryTable {
+    /// Freshly parsed entries held on the heap.
+    Owned {
+        entries: Vec<EntryDetail>,
+        keys: Vec<EntrySearchKey>,
+    },
+    /// Entries read in place from the runtime cache.
+    Mapped(Arc<MappedEntries>),
+}
+
+impl EntryTable {
+    /// Wrap parsed entries, computing their search keys.
+    pub(crate) fn owned(entries: Vec<EntryDetail>) -> Self {
+        let keys = build_entry_search_keys(&entries);
+        Self::Owned { entries, keys }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Owned { entries, .. } => entries.len(),
+            Self::Mapped(mapped) => mapped.count,
+        }
+    }
+
+    /// Entry at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index` is out of bounds.
+    pub(crate) fn get(&self, index: usize) -> EntryRef<'_> {
+        assert!(index < self.len(), "entry index {index} out of bounds");
+        EntryRef { table: self, index }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = EntryRef<'_>> {
+        (0..self.len()).map(move |index| EntryRef { table: self, index })
+    }
+
+    /// Entry with the given id; ids are normally position + 1, so this is a direct lookup.
+    pub(crate) fn by_id(&self, id: usize) -> Option<EntryRef<'_>> {
+        if let Some(entry) = id.checked_sub(1).filter(|i| *i < self.len()).map(|i| self.get(i)) {
+            if entry.id() == id {
+                return Some(entry);
+            }
+        }
+        self.iter().find(|entry| entry.id() == id)
+    }
+}
+
+/// Borrowed view of one entry and its search keys.
+#[derive(Clone, Copy)]
+pub(crate) struct EntryRef<'a> {
+    table: &'a EntryTable,
+    index: usize,
+}
+
+impl<'a> EntryRef<'a> {
+    /// String field read from the owned entry/keys or from mapped record slot `field`.
+    fn field(&self, owned: impl FnOnce(&'a EntryDetail, &'a EntrySearchKey) -> &'a str, field: usize) -> &'a str {
+        match self.table {
+            EntryTable::Owned { entries, keys } => owned(&entries[self.index], &keys[self.index]),
+            EntryTable::Mapped(mapped) => mapped.record_str(self.index, field),
+        }
+    }
+
+    pub(crate) fn id(&self) -> usize {
+        match self.table {
+            EntryTable::Owned { entries, .. } => entries[self.index].id,
+            EntryTable::Mapped(mapped) => read_u64(mapped.record(self.index), 0) as usize,
+        }
+    }
+
+    pub(crate) fn headword(&self) -> &'a str {
+        self.field(|e, _| &e.headword, STR_HEADWORD)
+    }
+
+    pub(crate) fn source_path(&self) -> &'a str {
+        self.field(|e, _| &e.source_path, STR_SOURCE_PATH)
+    }
+
+    pub(crate) fn target_local(&self) -> &'a str {
+        self.field(|e, _| &e.target_local, STR_TARGET_LOCAL)
+    }
+
+    pub(crate) fn definition_text(&self) -> &'a str {
+        self.field(|e, _| &e.definition_text, STR_DEFINITION_TEXT)
+    }
+
+    pub(crate) fn definition_html(&self) -> &'a str {
+        self.field(|e, _| &e.definition_html, STR_DEFINITION_HTML)
+    }
+
+    pub(crate) fn encoding(&self) -> &'a str {
+        self.field(|e, _| &e.encoding, STR_ENCODING)
+    }
+
+    /// Normalized headword search key.
+    pub(crate) fn headword_key(&self) -> &'a str {
+        self.field(|_, k| &k.headword, STR_KEY_HEADWORD)
+    }
+
+    pub(crate) fn headword_loose(&self) -> &'a str {
+        self.field(|_, k| &k.headword_loose, STR_KEY_HEADWORD_LOOSE)
+    }
+
+    /// Normalized definition-text search key.
+    pub(crate) fn body_key(&self) -> &'a str {
+        self.field(|_, k| &k.body, STR_KEY_BODY)
+    }
+
+    pub(crate) fn body_loose(&self) -> &'a str {
+        self.field(|_, k| &k.body_loose, STR_KEY_BODY_LOOSE)
+    }
+
+    pub(crate) fn aliases(&self) -> AliasIter<'a> {
+        self.alias_iter(ALIAS_TEXT)
+    }
+
+    /// Strict and loose search keys of each alias, in alias order.
+    pub(crate) fn alias_keys(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.alias_iter(ALIAS_KEY).zip(self.alias_iter(ALIAS_LOOSE))
+    }
+
+    /// Jamo and chosung keys of each alias, in alias order; empty for aliases without Hangul.
+    pub(crate) fn alias_hangul_keys(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.alias_iter(ALIAS_JAMO).zip(self.alias_iter(ALIAS_CHOSUNG))
+    }
+
+    /// Korean glosses with their jamo and chosung keys, in sense order.
+    pub(crate) fn gloss_keys(&self) -> impl Iterator<Item = (&'a str, &'a str, &'a str)> {
+        let glosses = self.field(|_, k| &k.glosses, STR_KEY_GLOSSES);
+        let jamo = self.field(|_, k| &k.glosses_jamo, STR_KEY_GLOSSES_JAMO);
+        let chosung = self.field(|_, k| &k.glosses_chosung, STR_KEY_GLOSSES_CHOSUNG);
+        glosses
+            .split('\n')
+            .zip(jamo.split('\n'))
+            .zip(chosung.split('\n'))
+            .filter(|((gloss, _), _)| !gloss.is_empty())
+            .map(|((gloss, jamo), chosung)| (gloss, jamo, chosung))
+    }
+
+    fn alias_iter(&self, field: usize) -> AliasIter<'a> {
+        let (pos, end) = match self.table {
+            EntryTable::Owned { entries, .. } => (0, entries[self.index].aliases.len()),
+            EntryTable::Mapped(mapped) => mapped.alias_range(self.index),
+        };
+        AliasIter {
+            entry: *self,
+            field,
+            pos,
+            end,
+        }
+    }
+
+    /// Owned copy for API responses.
+    pub(crate) fn to_detail(self) -> EntryDetail {
+        match self.table {
+            EntryTable::Owned { entries, .. } => entries[self.index].clone(),
+            EntryTable::Mapped(_) => EntryDetail {
+                id: self.id(),
+                headword: self.headword().to_string(),
+                aliases: self.aliases().map(str::to_string).collect(),
+                source_path: self.source_path().to_string(),
+                target_local: self.target_local().to_string(),
+                definition_text: self.definition_text().to_string(),
+                definition_html: self.definition_html().to_string(),
+                encoding: self.encoding().to_string(),
+            },
+        }
+    }
+}
+
+/// Iterator over one field of an entry's aliases.
+pub(crate) struct AliasIter<'a> {
+    entry: EntryRef<'a>,
+    field: usize,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for AliasIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let i = self.pos;
+        self.pos += 1;
+        Some(match self.entry.table {
+            EntryTable::Owned { entries, keys } => {
+                let keys = &keys[self.entry.index];
+                let field = match self.field {
+                    ALIAS_TEXT => return Some(entries[self.entry.index].aliases[i].as_str()),
+                    ALIAS_KEY => &keys.aliases,
+                    ALIAS_LOOSE => &keys.aliases_loose,
+                    ALIAS_JAMO => &keys.aliases_jamo,
+                    _ => &keys.aliases_chosung,
+                };
+                field.get(i).map_or("", String::as_str)
+            }
+            EntryTable::Mapped(mapped) => mapped.alias_str(i, self.field),
+        })
+    }
+}
+
+struct StringRegion {
+    bytes: Vec<u8>,
+}
+
+impl StringRegion {
+    fn push(&mut self, out: &mut Vec<u8>, s: &str) -> Result<(), DokhanError> {
+        let offset = u32::try_from(self.bytes.len())
+            .map_err(|_| DokhanError::Internal("entry index string region exceeds 4 GiB".to_string()))?;
+        let len = u32::try_from(s.len())
+            .map_err(|_| DokhanError::Internal("entry index string exceeds 4 GiB".to_string()))?;
+        self.bytes.extend_from_slice(s.as_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+}
+
+/// Write `table` as a mappable entry index at `path` via a temp file and rename.
+///
+/// # Errors
+///
+/// Returns an error when the table is too large for 32-bit offsets or the write fails.
+pub(crate) fn write_entry_index(path: &Path, table: &EntryTable) -> Result<(), DokhanError> {
+    let mut strings = StringRegion { bytes: Vec::new() };
+    let mut records = Vec::with_capacity(table.len() * RECORD_LEN);
+    let mut aliases = Vec::new();
+    let mut alias_count = 0u32;
+
+    for entry in table.iter() {
+        records.extend_from_slice(&(entry.id() as u64).to_le_bytes());
+        for s in [
+            entry.headword(),
+            entry.source_path(),
+            entry.target_local(),
+            entry.definition_text(),
+            entry.definition_html(),
+            entry.encoding(),
+            entry.headword_key(),
+            entry.headword_loose(),
+            entry.body_key(),
+            entry.body_loose(),
+            entry.field(|_, k| &k.glosses, STR_KEY_GLOSSES),
+            entry.field(|_, k| &k.glosses_jamo, STR_KEY_GLOSSES_JAMO),
+            entry.field(|_, k| &k.glosses_chosung, STR_KEY_GLOSSES_CHOSUNG),
+        ] {
+            strings.push(&mut records, s)?;
+        }
+        let start = alias_count;
+        let keys = entry.alias_keys().zip(entry.alias_hangul_keys());
+        for (alias, ((key, loose), (jamo, chosung))) in entry.aliases().zip(keys) {
+            for s in [alias, key, loose, jamo, chosung] {
+                strings.push(&mut aliases, s)?;
+            }
+            alias_count += 1;
+        }
+        records.extend_from_slice(&start.to_le_bytes());
+        records.extend_from_slice(&(alias_count - start).to_le_bytes());
+    }
+
+    let count = u32::try_from(table.len())
+        .map_err(|_| DokhanError::Internal("entry index has too many entries".to_string()))?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(ENTRY_INDEX_MAGIC);
+    header.extend_from_slice(&ENTRY_INDEX_FORMAT.to_le_bytes());
+    header.extend_from_slice(&count.to_le_bytes());
+    header.extend_from_slice(&alias_count.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.extend_from_slice(&(strings.bytes.len() as u64).to_le_bytes());
+    header.resize(HEADER_LEN, 0);
+
+    let tmp = path.with_extension("tmp");
+    let file = File::create(&tmp).map_err(|e| DokhanError::io("failed to create entry index", e))?;
+    let mut out = BufWriter::new(file);
+    out.write_all(&header)
+        .and_then(|_| out.write_all(&records))
+        .and_then(|_| out.write_all(&aliases))
+        .and_then(|_| out.write_all(&strings.bytes))
+        .and_then(|_| out.flush())
+        .map_err(|e| DokhanError::io("failed to write entry index", e))?;
+    drop(out);
+    fs::rename(&tmp, path).map_err(|e| DokhanError::io("failed to publish entry index", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, headword: &str, aliases: &[&str], text: &str) -> EntryDetail {
+        EntryDetail {
+            id,
+            headword: headword.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            source_path: "merge01.chm".to_string(),
+            target_local: format!("{headword}.htm"),
+            definition_text: text.to_string(),
+            definition_html: format!("<p>{text}</p>"),
+            encoding: "EUC-KR".to_string(),
+        }
+    }
+
+    #[test]
+    fn mapped_index_matches_owned_table() {
+        let owned = EntryTable::owned(vec![
+            entry(1, "Straße", &["Strasse", "die Straße", "거리"], "길, 거리"),
+            entry(2, "Haus", &[], "집"),
+            entry(3, "Übung", &["üben"], ""),
+        ]);
+        let path = std::env::temp_dir().join(format!("dokhan-entries-{}.idx", std::process::id()));
+        write_entry_index(&path, &owned).expect("write");
+        let mapped = EntryTable::Mapped(Arc::new(MappedEntries::open(&path).expect("open")));
+
+        assert_eq!(mapped.len(), owned.len());
+        for (a, b) in owned.iter().zip(mapped.iter()) {
+            assert_eq!(a.to_detail().headword, b.to_detail().headword);
+            assert_eq!(a.aliases().collect::<Vec<_>>(), b.aliases().collect::<Vec<_>>());
+            assert_eq!(a.alias_keys().collect::<Vec<_>>(), b.alias_keys().collect::<Vec<_>>());
+            assert_eq!(
+                a.alias_hangul_keys().collect::<Vec<_>>(),
+                b.alias_hangul_keys().collect::<Vec<_>>()
+            );
+            assert_eq!(a.gloss_keys().collect::<Vec<_>>(), b.gloss_keys().collect::<Vec<_>>());
+            assert_eq!(
+                (a.id(), a.headword_key(), a.headword_loose(), a.body_key(), a.body_loose()),
+                (b.id(), b.headword_key(), b.headword_loose(), b.body_key(), b.body_loose())
+            );
+            assert_eq!(a.definition_html(), b.definition_html());
+        }
+        assert_eq!(mapped.by_id(3).expect("entry 3").headword(), "Übung");
+        assert!(mapped.by_id(9).is_none());
+        assert_eq!(
+            mapped.get(0).gloss_keys().collect::<Vec<_>>(),
+            [("길", "ㄱㅣㄹ", "ㄱ"), ("거리", "ㄱㅓㄹㅣ", "ㄱㄹ")]
+        );
+        assert_eq!(mapped.get(0).alias_hangul_keys().nth(2), Some(("ㄱㅓㄹㅣ", "ㄱㄹ")));
+
+        // The map must be released before the file is damaged; it is never rewritten in place.
+        drop(mapped);
+        let bytes = fs::read(&path).expect("read");
+        fs::write(&path, &bytes[..bytes.len() - 1]).expect("truncate");
+        assert!(MappedEntries::open(&path).is_err());
+
+        let mut bad_string = bytes.clone();
+        bad_string[HEADER_LEN + 8..HEADER_LEN + 12].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, &bad_string).expect("corrupt string reference");
+        assert!(MappedEntries::open(&path).is_err());
+
+        let mut header = bytes[..HEADER_LEN].to_vec();
+        header[12..20].copy_from_slice(&[0xff; 8]);
+        header[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, &header).expect("overflowing header");
+        assert!(MappedEntries::open(&path).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+}
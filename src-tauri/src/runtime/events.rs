@@ -0,0 +1,87 @@
+//! Build lifecycle events pushed to every window.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tauri::{AppHandle, Emitter};
+
+use crate::app::model::{BuildDoneEvent, BuildErrorEvent, BuildProgress, BuildProgressEvent, BuildStatus};
+
+pub(crate) const BUILD_PROGRESS_EVENT: &str = "build://progress";
+pub(crate) const BUILD_DONE_EVENT: &str = "build://done";
+pub(crate) const BUILD_ERROR_EVENT: &str = "build://error";
+
+static BUILD_EVENT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Next value of the process-wide build event sequence, starting at 1.
+pub(crate) fn next_build_seq() -> u64 {
+    BUILD_EVENT_SEQ.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// Emit the event matching a freshly stored status snapshot.
+///
+/// Delivery is best-effort; `get_master_build_status` stays the snapshot fallback.
+pub(crate) fn emit_build_status(app: &AppHandle, key: &str, status: &BuildStatus) {
+    let key = key.to_string();
+    if !status.done {
+        let _ = app.emit(
+            BUILD_PROGRESS_EVENT,
+            BuildProgressEvent {
+                key,
+                seq: status.seq,
+                progress: BuildProgress {
+                    phase: status.phase.clone(),
+                    current: status.current,
+                    total: status.total,
+                    message: status.message.clone(),
+                },
+            },
+        );
+        return;
+    }
+    match (&status.summary, status.success) {
+        (Some(summary), true) => {
+            let _ = app.emit(
+                BUILD_DONE_EVENT,
+                BuildDoneEvent {
+                    key,
+                    seq: status.seq,
+                    message: status.message.clone(),
+                    summary: summary.clone(),
+                },
+            );
+        }
+        _ => {
+            let _ = app.emit(
+                BUILD_ERROR_EVENT,
+                BuildErrorEvent {
+                    key,
+                    seq: status.seq,
+                    message: status.message.clone(),
+                    error: status.error.clone(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_is_strictly_increasing_across_threads() {
+        let handles = (0..4)
+            .map(|_| std::thread::spawn(|| (0..256).map(|_| next_build_seq()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let mut all = Vec::new();
+        for h in handles {
+            let seqs = h.join().expect("thread");
+            assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+            all.extend(seqs);
+        }
+        let len = all.len();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), len);
+        assert!(all[0] >= 1);
+    }
+}
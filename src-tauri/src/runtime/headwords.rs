@@ -0,0 +1,515 @@
+//! Finite-state-transducer index over normalized headwords and aliases, and over the Hangul
+//! keys of aliases and glosses.
+//!
+//! Layout (little-endian): a fixed header, the strict-key FST, the loose-key FST, the alias
+//! Hangul FST, the gloss Hangul FST, then the postings region of `u32` entry ids. Each FST maps
+//! a key to `(start << 32) | count`, a slice of sorted ids in the postings region. The Hangul
+//! FSTs hold the jamo and chosung keys of every suffix starting at a character, so substring
+//! matches at syllable starts are prefix lookups.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use fst::automaton::{Levenshtein, Str, Subsequence};
+use fst::{Automaton, IntoStreamer, Map, MapBuilder, Streamer};
+use memmap2::Mmap;
+
+use crate::error::DokhanError;
+use crate::parsing::hangul::hangul_suffix_keys;
+use crate::runtime::entries::EntryTable;
+use crate::runtime::search::{normalize_search_key, normalize_search_key_loose};
+use crate::runtime::spelling::{german_edit_distance, SPELLING_VARIANT_COST};
+
+const HEADWORD_INDEX_MAGIC: &[u8; 8] = b"DKHWFST1";
+const HEADWORD_INDEX_FORMAT: u32 = 1;
+/// FST sections after the header: strict, loose, alias Hangul, gloss Hangul.
+const FST_SECTIONS: usize = 4;
+const HEADER_LEN: usize = 16 + (FST_SECTIONS + 1) * 8;
+/// Largest edit distance accepted by [`HeadwordIndex::fuzzy`]; larger automata get too big.
+pub(crate) const MAX_FUZZY_DISTANCE: u32 = 2;
+/// Longest headword regex accepted, in bytes.
+const MAX_REGEX_PATTERN_LEN: usize = 256;
+/// Largest bound accepted in a counted repetition such as `{3,5}`.
+const MAX_REGEX_REPEAT: u32 = 64;
+
+enum IndexBytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl IndexBytes {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Owned(bytes) => bytes,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+/// One FST section of the shared index bytes.
+#[derive(Clone)]
+struct Section {
+    bytes: Arc<IndexBytes>,
+    range: Range<usize>,
+}
+
+impl AsRef<[u8]> for Section {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes.as_slice()[self.range.clone()]
+    }
+}
+
+/// Headword/alias key index, built in memory after a parse or mapped from the runtime cache.
+pub(crate) struct HeadwordIndex {
+    bytes: Arc<IndexBytes>,
+    strict: Map<Section>,
+    loose: Map<Section>,
+    alias_hangul: Map<Section>,
+    gloss_hangul: Map<Section>,
+    postings: Range<usize>,
+}
+
+impl fmt::Debug for HeadwordIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeadwordIndex")
+            .field("strict_keys", &self.strict.len())
+            .field("loose_keys", &self.loose.len())
+            .field("alias_hangul_keys", &self.alias_hangul.len())
+            .field("gloss_hangul_keys", &self.gloss_hangul.len())
+            .field("bytes", &self.bytes.as_slice().len())
+            .finish()
+    }
+}
+
+fn fst_error(context: &str, e: fst::Error) -> DokhanError {
+    DokhanError::Internal(format!("{context}: {e}"))
+}
+
+/// Little-endian `u64` at `at`; `None` when it does not fit `usize`.
+fn read_u64(bytes: &[u8], at: usize) -> Option<usize> {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[at..at + 8]);
+    usize::try_from(u64::from_le_bytes(word)).ok()
+}
+
+/// Append each key's sorted ids to `postings` and map the key to its slice.
+fn build_map<K: AsRef<[u8]>>(
+    keys: &BTreeMap<K, BTreeSet<u32>>,
+    postings: &mut Vec<u8>,
+) -> Result<Vec<u8>, DokhanError> {
+    let mut builder = MapBuilder::memory();
+    for (key, ids) in keys {
+        let start = (postings.len() / 4) as u64;
+        for id in ids {
+            postings.extend_from_slice(&id.to_le_bytes());
+        }
+        builder
+            .insert(key, (start << 32) | ids.len() as u64)
+            .map_err(|e| fst_error("headword index insert failed", e))?;
+    }
+    builder
+        .into_inner()
+        .map_err(|e| fst_error("headword index build failed", e))
+}
+
+/// Map the jamo and chosung suffix keys of each of `texts` to `id`.
+fn insert_hangul_keys<'a>(keys: &mut BTreeMap<String, BTreeSet<u32>>, texts: impl Iterator<Item = &'a str>, id: u32) {
+    for (jamo, chosung) in texts.flat_map(hangul_suffix_keys) {
+        keys.entry(jamo).or_default().insert(id);
+        keys.entry(chosung).or_default().insert(id);
+    }
+}
+
+impl HeadwordIndex {
+    /// Index the strict and loose keys of every headword and alias in `entries`, and the
+    /// Hangul suffix keys of every alias and gloss.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an entry id does not fit the postings format or FST construction fails.
+    pub(crate) fn build(entries: &EntryTable) -> Result<Self, DokhanError> {
+        let mut strict = BTreeMap::<&str, BTreeSet<u32>>::new();
+        let mut loose = BTreeMap::<&str, BTreeSet<u32>>::new();
+        let mut alias_hangul = BTreeMap::<String, BTreeSet<u32>>::new();
+        let mut gloss_hangul = BTreeMap::<String, BTreeSet<u32>>::new();
+        for entry in entries.iter() {
+            let id = u32::try_from(entry.id())
+                .map_err(|_| DokhanError::Internal(format!("entry id {} exceeds headword index range", entry.id())))?;
+            let keys = std::iter::once((entry.headword_key(), entry.headword_loose())).chain(entry.alias_keys());
+            for (key, loose_key) in keys {
+                if !key.is_empty() {
+                    strict.entry(key).or_default().insert(id);
+                }
+                if !loose_key.is_empty() {
+                    loose.entry(loose_key).or_default().insert(id);
+                }
+            }
+            insert_hangul_keys(&mut alias_hangul, entry.aliases(), id);
+            insert_hangul_keys(&mut gloss_hangul, entry.gloss_keys().map(|(gloss, _, _)| gloss), id);
+        }
+
+        let mut postings = Vec::new();
+        let sections = [
+            build_map(&strict, &mut postings)?,
+            build_map(&loose, &mut postings)?,
+            build_map(&alias_hangul, &mut postings)?,
+            build_map(&gloss_hangul, &mut postings)?,
+        ];
+        let total = sections.iter().map(Vec::len).sum::<usize>() + postings.len();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + total);
+        bytes.extend_from_slice(HEADWORD_INDEX_MAGIC);
+        bytes.extend_from_slice(&HEADWORD_INDEX_FORMAT.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        for len in sections.iter().map(Vec::len).chain([postings.len()]) {
+            bytes.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+        for section in &sections {
+            bytes.extend_from_slice(section);
+        }
+        bytes.extend_from_slice(&postings);
+        Self::from_bytes(IndexBytes::Owned(bytes))
+    }
+
+    /// Map a persisted index and validate its header and FST sections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be mapped or is not a valid headword index.
+    pub(crate) fn open(path: &Path) -> Result<Self, DokhanError> {
+        let file = File::open(path).map_err(|e| DokhanError::io("failed to open headword index", e))?;
+        // SAFETY: cache files are only ever replaced by rename, never rewritten in place,
+        // so the mapped bytes stay stable for the lifetime of this map.
+        let map = unsafe { Mmap::map(&file) }.map_err(|e| DokhanError::io("failed to map headword index", e))?;
+        Self::from_bytes(IndexBytes::Mapped(map))
+    }
+
+    fn from_bytes(bytes: IndexBytes) -> Result<Self, DokhanError> {
+        let raw = bytes.as_slice();
+        let invalid = |what: &str| DokhanError::Internal(format!("headword index {what}"));
+        if raw.len() < HEADER_LEN || &raw[..8] != HEADWORD_INDEX_MAGIC {
+            return Err(invalid("magic mismatch"));
+        }
+        let mut word = [0u8; 4];
+        word.copy_from_slice(&raw[8..12]);
+        let format = u32::from_le_bytes(word);
+        if format != HEADWORD_INDEX_FORMAT {
+            return Err(invalid(&format!("format v{format} is not supported")));
+        }
+        let mut ranges = Vec::with_capacity(FST_SECTIONS + 1);
+        let mut end = HEADER_LEN;
+        for at in (0..=FST_SECTIONS).map(|i| 16 + i * 8) {
+            let start = end;
+            end = read_u64(raw, at)
+                .and_then(|len| start.checked_add(len))
+                .ok_or_else(|| invalid("sections overflow"))?;
+            ranges.push(start..end);
+        }
+        let postings = ranges.pop().unwrap_or_default();
+        if end != raw.len() || !postings.len().is_multiple_of(4) {
+            return Err(invalid("sections do not match file length"));
+        }
+
+        let bytes = Arc::new(bytes);
+        let fst = |index: usize, name: &str| {
+            Map::new(Section {
+                bytes: bytes.clone(),
+                range: ranges[index].clone(),
+            })
+            .map_err(|e| fst_error(&format!("{name} headword FST invalid"), e))
+        };
+        Ok(Self {
+            strict: fst(0, "strict")?,
+            loose: fst(1, "loose")?,
+            alias_hangul: fst(2, "alias Hangul")?,
+            gloss_hangul: fst(3, "gloss Hangul")?,
+            bytes,
+            postings,
+        })
+    }
+
+    /// Serialized index, as written to the runtime cache.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Add the ids of the postings slice `value` points at; a slice outside the section adds nothing.
+    fn add_postings(&self, value: u64, out: &mut BTreeSet<usize>) {
+        let offset = |words: u64| usize::try_from(words).ok()?.checked_mul(4);
+        let start = offset(value >> 32).and_then(|first| self.postings.start.checked_add(first));
+        let Some((start, end)) = start.and_then(|start| Some((start, start.checked_add(offset(value & 0xffff_ffff)?)?)))
+        else {
+            return;
+        };
+        if start > end || end > self.postings.end {
+            return;
+        }
+        for chunk in self.bytes.as_slice()[start..end].chunks_exact(4) {
+            let mut word = [0u8; 4];
+            word.copy_from_slice(chunk);
+            out.insert(u32::from_le_bytes(word) as usize);
+        }
+    }
+
+    /// Stream the strict and loose FSTs through their automata and merge the matching ids.
+    fn collect(&self, strict: impl Automaton, loose: impl Automaton) -> Vec<usize> {
+        let mut ids = BTreeSet::new();
+        let mut stream = self.strict.search(strict).into_stream();
+        while let Some((_, value)) = stream.next() {
+            self.add_postings(value, &mut ids);
+        }
+        let mut stream = self.loose.search(loose).into_stream();
+        while let Some((_, value)) = stream.next() {
+            self.add_postings(value, &mut ids);
+        }
+        ids.into_iter().collect()
+    }
+
+    /// Sorted ids of entries with a headword or alias equal to `word` under strict normalization.
+    ///
+    /// The loose keys are skipped so that `schon` does not match `schön`.
+    pub(crate) fn exact(&self, word: &str) -> Vec<usize> {
+        let mut ids = BTreeSet::new();
+        if let Some(value) = self.strict.get(normalize_search_key(word)) {
+            self.add_postings(value, &mut ids);
+        }
+        ids.into_iter().collect()
+    }
+
+    /// Sorted ids of entries with a headword or alias starting with `prefix`.
+    pub(crate) fn prefix(&self, prefix: &str) -> Vec<usize> {
+        let (strict, loose) = (normalize_search_key(prefix), normalize_search_key_loose(prefix));
+        self.collect(Str::new(&strict).starts_with(), Str::new(&loose).starts_with())
+    }
+
+    /// Sorted ids of entries with a headword or alias containing `query` as a subsequence.
+    ///
+    /// This is a superset of prefix and substring matches, so callers can rank the result exactly.
+    pub(crate) fn subsequence(&self, query: &str) -> Vec<usize> {
+        let (strict, loose) = (normalize_search_key(query), normalize_search_key_loose(query));
+        self.collect(Subsequence::new(&strict), Subsequence::new(&loose))
+    }
+
+    /// Sorted ids of entries with an alias whose jamo or chosung key contains `key` at a
+    /// character start.
+    ///
+    /// Jamo and chosung keys share one FST, so callers check the matching kind themselves.
+    pub(crate) fn alias_hangul(&self, key: &str) -> Vec<usize> {
+        self.hangul_prefix(&self.alias_hangul, key)
+    }
+
+    /// Sorted ids of entries with a Korean gloss whose jamo or chosung key contains `key` at a
+    /// character start; see [`Self::alias_hangul`].
+    pub(crate) fn gloss_hangul(&self, key: &str) -> Vec<usize> {
+        self.hangul_prefix(&self.gloss_hangul, key)
+    }
+
+    fn hangul_prefix(&self, map: &Map<Section>, key: &str) -> Vec<usize> {
+        let mut ids = BTreeSet::new();
+        let mut stream = map.search(Str::new(key).starts_with()).into_stream();
+        while let Some((_, value)) = stream.next() {
+            self.add_postings(value, &mut ids);
+        }
+        ids.into_iter().collect()
+    }
+
+    /// Sorted ids of entries with a headword or alias within `distance` edits of `term`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DokhanError::InvalidInput`] when `distance` exceeds [`MAX_FUZZY_DISTANCE`] or the
+    /// automaton for `term` grows too large.
+    pub(crate) fn fuzzy(&self, term: &str, distance: u32) -> Result<Vec<usize>, DokhanError> {
+        if distance > MAX_FUZZY_DISTANCE {
+            return Err(DokhanError::InvalidInput(format!(
+                "edit distance {distance} exceeds {MAX_FUZZY_DISTANCE}"
+            )));
+        }
+        let automaton = |key: &str| {
+            Levenshtein::new(key, distance)
+                .map_err(|e| DokhanError::InvalidInput(format!("fuzzy query too complex: {e}")))
+        };
+        Ok(self.collect(
+            automaton(&normalize_search_key(term))?,
+            automaton(&normalize_search_key_loose(term))?,
+        ))
+    }
+
+    /// Sorted ids of entries with a headword or alias key within `max_cost` of `term` by
+    /// [`german_edit_distance`].
+    ///
+    /// Candidates are the keys within [`MAX_FUZZY_DISTANCE`] plain edits plus every strict key
+    /// sharing the first character of `term`, so transpositions and stacked umlaut or `ss`
+    /// variants that exceed the plain edit bound are still scored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DokhanError::InvalidInput`] when even a one-edit automaton for `term` is too large.
+    pub(crate) fn weighted_fuzzy(&self, term: &str, max_cost: u32) -> Result<Vec<usize>, DokhanError> {
+        let mut ids = self
+            .fuzzy(term, MAX_FUZZY_DISTANCE)
+            .or_else(|_| self.fuzzy(term, 1))?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        let key = normalize_search_key(term);
+        let Some(first) = key.chars().next() else {
+            return Ok(ids.into_iter().collect());
+        };
+        let len = key.chars().count();
+        let mut first_buf = [0u8; 4];
+        let mut stream = self
+            .strict
+            .search(Str::new(first.encode_utf8(&mut first_buf)).starts_with())
+            .into_stream();
+        while let Some((candidate, value)) = stream.next() {
+            let Ok(candidate) = std::str::from_utf8(candidate) else {
+                continue;
+            };
+            // Every length difference costs at least one spelling-variant edit.
+            let min_cost = u32::try_from(candidate.chars().count().abs_diff(len))
+                .map_or(u32::MAX, |diff| diff.saturating_mul(SPELLING_VARIANT_COST));
+            if min_cost <= max_cost && german_edit_distance(&key, candidate) <= max_cost {
+                self.add_postings(value, &mut ids);
+            }
+        }
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Sorted ids of entries with a normalized headword or alias fully matching `pattern`.
+    ///
+    /// Matching is case-insensitive against the umlaut-folded keys (`strasse`, not `straße`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DokhanError::InvalidInput`] when the pattern is invalid, uses unsupported
+    /// syntax such as anchors or word boundaries, or is too long or complex to compile.
+    pub(crate) fn regex(&self, pattern: &str) -> Result<Vec<usize>, DokhanError> {
+        if pattern.len() > MAX_REGEX_PATTERN_LEN {
+            return Err(DokhanError::InvalidInput(format!(
+                "headword pattern exceeds {MAX_REGEX_PATTERN_LEN} bytes"
+            )));
+        }
+        if counted_repeats(pattern).any(|n| n > MAX_REGEX_REPEAT) {
+            return Err(DokhanError::InvalidInput(format!(
+                "headword pattern repeats more than {MAX_REGEX_REPEAT} times"
+            )));
+        }
+        // 16-bit state ids bound determinization, so an exploding DFA fails instead of hanging.
+        let dfa = regex_automata::dense::Builder::new()
+            .anchored(true)
+            .case_insensitive(true)
+            .build_with_size::<u16>(pattern)
+            .map_err(|e| {
+                DokhanError::InvalidInput(match e.kind() {
+                    regex_automata::ErrorKind::Syntax(_) | regex_automata::ErrorKind::Unsupported(_) => {
+                        format!("invalid headword pattern: {e}")
+                    }
+                    _ => format!("headword pattern too complex: {e}"),
+                })
+            })?;
+        Ok(self.collect(&dfa, &dfa))
+    }
+}
+
+/// Bounds of the counted repetitions (`{n}`, `{n,}`, `{n,m}`) in a regex pattern.
+fn counted_repeats(pattern: &str) -> impl Iterator<Item = u32> + '_ {
+    pattern
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .flat_map(|(bounds, _)| bounds.split(',').filter_map(|n| n.trim().parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::model::EntryDetail;
+
+    fn table(headwords: &[(&str, &[&str])]) -> EntryTable {
+        EntryTable::owned(
+            headwords
+                .iter()
+                .enumerate()
+                .map(|(i, (headword, aliases))| EntryDetail {
+                    id: i + 1,
+                    headword: headword.to_string(),
+                    aliases: aliases.iter().map(|a| a.to_string()).collect(),
+                    source_path: "merge01.chm".to_string(),
+                    target_local: String::new(),
+                    definition_text: String::new(),
+                    definition_html: String::new(),
+                    encoding: "EUC-KR".to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn queries_return_sorted_ids_from_built_and_mapped_index() {
+        let entries = table(&[
+            ("Straße", &["Strasse"]),
+            ("Haus", &[]),
+            ("Hausboot", &[]),
+            ("zuhause", &["Haus"]),
+            ("Übung", &[]),
+            ("Apfelbaum", &["사과 나무"]),
+        ]);
+        let built = HeadwordIndex::build(&entries).expect("build");
+        let path = std::env::temp_dir().join(format!("dokhan-headwords-{}.fst", std::process::id()));
+        std::fs::write(&path, built.as_bytes()).expect("write");
+        let mapped = HeadwordIndex::open(&path).expect("open");
+
+        for index in [&built, &mapped] {
+            assert_eq!(index.prefix("haus"), [2, 3, 4]);
+            assert_eq!(index.prefix("Üb"), [5]);
+            assert_eq!(index.exact("HAUS"), [2, 4]);
+            assert!(index.exact("Hausb").is_empty());
+            assert_eq!(index.subsequence("hs"), [2, 3, 4]);
+            assert_eq!(index.fuzzy("Stasse", 1).expect("fuzzy"), [1]);
+            assert_eq!(index.fuzzy("Hau", 1).expect("fuzzy"), [2, 4]);
+            assert_eq!(index.regex("H(a|o)US(boot)?").expect("regex"), [2, 3, 4]);
+            assert_eq!(index.alias_hangul("ㅅㅏㄱ"), [6]);
+            assert_eq!(index.alias_hangul("ㄴㅁ"), [6]);
+            assert!(index.alias_hangul("ㅏㄱ").is_empty());
+        }
+        let glosses = EntryTable::owned(vec![EntryDetail {
+            id: 1,
+            headword: "Apfel".to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: "m. ① 사과 ② 둥근 것".to_string(),
+            definition_html: String::new(),
+            encoding: "EUC-KR".to_string(),
+        }]);
+        let glosses = HeadwordIndex::build(&glosses).expect("build");
+        assert_eq!(glosses.gloss_hangul("ㄱㄱ"), [1]);
+        assert_eq!(glosses.gloss_hangul("ㄱㅓㅅ"), [1]);
+        assert!(glosses.alias_hangul("ㄱㄱ").is_empty());
+        assert!(built.fuzzy("haus", 3).is_err());
+        assert!(built.regex("haus$").is_err());
+        assert!(built.regex("ha{2,64}us").is_ok() && built.regex(".*stra.*e.*").is_ok());
+        let limit_error = |pattern: &str| match built.regex(pattern) {
+            Err(DokhanError::InvalidInput(message)) => message,
+            other => panic!("{pattern}: expected a limit error, got {other:?}"),
+        };
+        assert!(limit_error("ha{65}us").contains("repeats more than"));
+        assert!(limit_error(&"a".repeat(MAX_REGEX_PATTERN_LEN + 1)).contains("exceeds"));
+        assert!(limit_error("(a|b)*a(a|b){20}").contains("too complex"));
+
+        let mut out = BTreeSet::new();
+        mapped.add_postings(u64::MAX, &mut out);
+        mapped.add_postings(u64::from(u32::MAX), &mut out);
+        assert!(out.is_empty());
+
+        // The map must be released before the file is truncated.
+        drop(mapped);
+        let mut bytes = built.as_bytes().to_vec();
+        bytes.pop();
+        std::fs::write(&path, &bytes).expect("truncate");
+        assert!(HeadwordIndex::open(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
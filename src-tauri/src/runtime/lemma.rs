@@ -0,0 +1,386 @@
+//! Rule-based German lemmatiser mapping inflected word forms to candidate headwords.
+//!
+//! Candidates over-generate on purpose: callers keep only those that exist as headwords,
+//! so a rule only has to produce the right lemma somewhere in its output. Candidates are
+//! ordered by rule reliability: irregular tables first, then adjectives, nouns, and weak verbs.
+
+/// Forms that no rule below derives.
+const IRREGULAR_FORMS: &[(&str, &str)] = &[
+    ("bin", "sein"),
+    ("bist", "sein"),
+    ("ist", "sein"),
+    ("sind", "sein"),
+    ("seid", "sein"),
+    ("war", "sein"),
+    ("warst", "sein"),
+    ("waren", "sein"),
+    ("wart", "sein"),
+    ("gewesen", "sein"),
+    ("sei", "sein"),
+    ("wäre", "sein"),
+    ("wären", "sein"),
+    ("habe", "haben"),
+    ("hätte", "haben"),
+    ("hätten", "haben"),
+    ("würde", "werden"),
+    ("würden", "werden"),
+    ("besser", "gut"),
+    ("best", "gut"),
+    ("mehr", "viel"),
+    ("meist", "viel"),
+    ("lieber", "gern"),
+    ("liebst", "gern"),
+    ("höher", "hoch"),
+    ("höchst", "hoch"),
+    ("näher", "nah"),
+    ("nächst", "nah"),
+];
+
+/// Strong, mixed, and modal verbs: infinitive, changed present singular forms, preterite stem,
+/// past participle.
+const STRONG_VERBS: &[(&str, &str, &str, &str)] = &[
+    ("haben", "hast hat", "hatte", "gehabt"),
+    ("werden", "wirst wird", "wurde", "geworden"),
+    ("gehen", "", "ging", "gegangen"),
+    ("kommen", "", "kam", "gekommen"),
+    ("sehen", "siehst sieht", "sah", "gesehen"),
+    ("geben", "gibst gibt", "gab", "gegeben"),
+    ("nehmen", "nimmst nimmt", "nahm", "genommen"),
+    ("essen", "isst", "aß", "gegessen"),
+    ("messen", "misst", "maß", "gemessen"),
+    ("vergessen", "vergisst", "vergaß", "vergessen"),
+    ("sprechen", "sprichst spricht", "sprach", "gesprochen"),
+    ("brechen", "brichst bricht", "brach", "gebrochen"),
+    ("treffen", "triffst trifft", "traf", "getroffen"),
+    ("helfen", "hilfst hilft", "half", "geholfen"),
+    ("werfen", "wirfst wirft", "warf", "geworfen"),
+    ("sterben", "stirbst stirbt", "starb", "gestorben"),
+    ("gelten", "giltst gilt", "galt", "gegolten"),
+    ("treten", "trittst tritt", "trat", "getreten"),
+    ("stehlen", "stiehlst stiehlt", "stahl", "gestohlen"),
+    ("befehlen", "befiehlst befiehlt", "befahl", "befohlen"),
+    ("empfehlen", "empfiehlst empfiehlt", "empfahl", "empfohlen"),
+    ("geschehen", "geschieht", "geschah", "geschehen"),
+    ("lesen", "liest", "las", "gelesen"),
+    ("fahren", "fährst fährt", "fuhr", "gefahren"),
+    ("tragen", "trägst trägt", "trug", "getragen"),
+    ("schlagen", "schlägst schlägt", "schlug", "geschlagen"),
+    ("laden", "lädst lädt", "lud", "geladen"),
+    ("graben", "gräbst gräbt", "grub", "gegraben"),
+    ("waschen", "wäschst wäscht", "wusch", "gewaschen"),
+    ("wachsen", "wächst", "wuchs", "gewachsen"),
+    ("schlafen", "schläfst schläft", "schlief", "geschlafen"),
+    ("laufen", "läufst läuft", "lief", "gelaufen"),
+    ("fallen", "fällst fällt", "fiel", "gefallen"),
+    ("halten", "hältst hält", "hielt", "gehalten"),
+    ("lassen", "lässt", "ließ", "gelassen"),
+    ("fangen", "fängst fängt", "fing", "gefangen"),
+    ("raten", "rätst rät", "riet", "geraten"),
+    ("braten", "brätst brät", "briet", "gebraten"),
+    ("stoßen", "stößt", "stieß", "gestoßen"),
+    ("rufen", "", "rief", "gerufen"),
+    ("heißen", "", "hieß", "geheißen"),
+    ("finden", "", "fand", "gefunden"),
+    ("binden", "", "band", "gebunden"),
+    ("trinken", "", "trank", "getrunken"),
+    ("sinken", "", "sank", "gesunken"),
+    ("singen", "", "sang", "gesungen"),
+    ("springen", "", "sprang", "gesprungen"),
+    ("zwingen", "", "zwang", "gezwungen"),
+    ("beginnen", "", "begann", "begonnen"),
+    ("gewinnen", "", "gewann", "gewonnen"),
+    ("schwimmen", "", "schwamm", "geschwommen"),
+    ("bleiben", "", "blieb", "geblieben"),
+    ("schreiben", "", "schrieb", "geschrieben"),
+    ("steigen", "", "stieg", "gestiegen"),
+    ("schweigen", "", "schwieg", "geschwiegen"),
+    ("scheinen", "", "schien", "geschienen"),
+    ("schreien", "", "schrie", "geschrien"),
+    ("leihen", "", "lieh", "geliehen"),
+    ("greifen", "", "griff", "gegriffen"),
+    ("leiden", "", "litt", "gelitten"),
+    ("reiten", "", "ritt", "geritten"),
+    ("schneiden", "", "schnitt", "geschnitten"),
+    ("streiten", "", "stritt", "gestritten"),
+    ("bieten", "", "bot", "geboten"),
+    ("fliegen", "", "flog", "geflogen"),
+    ("fließen", "", "floss", "geflossen"),
+    ("frieren", "", "fror", "gefroren"),
+    ("riechen", "", "roch", "gerochen"),
+    ("schieben", "", "schob", "geschoben"),
+    ("schließen", "", "schloss", "geschlossen"),
+    ("lügen", "", "log", "gelogen"),
+    ("verlieren", "", "verlor", "verloren"),
+    ("ziehen", "", "zog", "gezogen"),
+    ("heben", "", "hob", "gehoben"),
+    ("stehen", "", "stand", "gestanden"),
+    ("sitzen", "", "saß", "gesessen"),
+    ("liegen", "", "lag", "gelegen"),
+    ("bitten", "", "bat", "gebeten"),
+    ("tun", "tust tut", "tat", "getan"),
+    ("wissen", "weiß weißt", "wusste", "gewusst"),
+    ("denken", "", "dachte", "gedacht"),
+    ("bringen", "", "brachte", "gebracht"),
+    ("kennen", "", "kannte", "gekannt"),
+    ("nennen", "", "nannte", "genannt"),
+    ("rennen", "", "rannte", "gerannt"),
+    ("können", "kann kannst", "konnte", "gekonnt"),
+    ("müssen", "muss musst", "musste", "gemusst"),
+    ("dürfen", "darf darfst", "durfte", "gedurft"),
+    ("wollen", "will willst", "wollte", "gewollt"),
+    ("sollen", "soll sollst", "sollte", "gesollt"),
+    ("mögen", "mag magst", "mochte", "gemocht"),
+];
+
+/// Verb prefixes that never take `ge-` in the participle.
+const INSEPARABLE_PREFIXES: &[&str] = &["be", "emp", "ent", "er", "miss", "ver", "zer"];
+
+/// Separable verb prefixes; `ge-` and `zu` go between prefix and stem.
+const SEPARABLE_PREFIXES: &[&str] = &[
+    "ab", "an", "auf", "aus", "bei", "durch", "ein", "fest", "fort", "heraus", "herein", "her", "hinaus",
+    "hinein", "hin", "los", "mit", "nach", "um", "vorbei", "vor", "weg", "wieder", "zurück", "zusammen", "zu",
+];
+
+/// Adjective declension endings, longest first.
+const DECLENSION_ENDINGS: &[&str] = &["en", "em", "er", "es", "e"];
+
+/// Noun plural and case endings with the singular ending they replace.
+const NOUN_ENDINGS: &[(&str, &str)] = &[
+    ("innen", "in"),
+    ("ern", ""),
+    ("en", ""),
+    ("en", "um"),
+    ("en", "a"),
+    ("en", "us"),
+    ("er", ""),
+    ("es", ""),
+    ("e", ""),
+    ("n", ""),
+    ("s", ""),
+];
+
+/// Weak verb endings (preterite, participle tail, present), longest first.
+const WEAK_VERB_ENDINGS: &[&str] = &["etest", "eten", "etet", "ete", "test", "ten", "tet", "te", "est", "et", "st", "t", "e"];
+
+/// Ordered, de-duplicated candidate list that skips the query itself.
+struct Candidates<'a> {
+    word: &'a str,
+    out: Vec<String>,
+}
+
+impl Candidates<'_> {
+    fn push(&mut self, lemma: String) {
+        if lemma.chars().count() >= 2 && lemma != self.word && !self.out.contains(&lemma) {
+            self.out.push(lemma);
+        }
+    }
+
+    /// Push `stem` and its form with the last umlaut removed (`häus` → `haus`).
+    fn push_with_umlaut_variant(&mut self, stem: &str) {
+        self.push(stem.to_string());
+        if let Some(plain) = remove_last_umlaut(stem) {
+            self.push(plain);
+        }
+    }
+
+    /// Push the infinitive(s) a weak verb stem can have.
+    fn push_infinitive(&mut self, prefix: &str, stem: &str) {
+        self.push(format!("{prefix}{stem}en"));
+        if stem.ends_with("el") || stem.ends_with("er") {
+            self.push(format!("{prefix}{stem}n"));
+        }
+    }
+}
+
+fn remove_last_umlaut(word: &str) -> Option<String> {
+    let (at, ch) = word.char_indices().rev().find(|(_, c)| matches!(c, 'ä' | 'ö' | 'ü'))?;
+    let plain = match ch {
+        'ä' => 'a',
+        'ö' => 'o',
+        _ => 'u',
+    };
+    Some(format!("{}{plain}{}", &word[..at], &word[at + ch.len_utf8()..]))
+}
+
+/// `(prefix, rest)` splits of `word`, starting with the unprefixed form.
+fn prefix_splits<'w>(word: &'w str, prefixes: &'static [&'static str]) -> impl Iterator<Item = (&'static str, &'w str)> {
+    std::iter::once(("", word)).chain(prefixes.iter().filter_map(move |prefix| {
+        word.strip_prefix(prefix)
+            .filter(|rest| rest.chars().count() >= 2)
+            .map(|rest| (*prefix, rest))
+    }))
+}
+
+/// Whether `form` is an inflected form of the strong verb `verb`.
+///
+/// With `bare_participle`, the participle also matches without `ge-`, as after an inseparable prefix.
+fn is_strong_form(verb: &(&str, &str, &str, &str), form: &str, bare_participle: bool) -> bool {
+    let (infinitive, present, preterite, participle) = *verb;
+    if form == infinitive || present.split_whitespace().any(|p| p == form) {
+        return true;
+    }
+    let preterite_endings: &[&str] = if preterite.ends_with('e') {
+        &["", "st", "n", "t"]
+    } else {
+        &["", "st", "est", "en", "t", "et"]
+    };
+    if preterite_endings.iter().any(|e| form.strip_suffix(e) == Some(preterite)) {
+        return true;
+    }
+    let participle_stem = DECLENSION_ENDINGS
+        .iter()
+        .find_map(|e| form.strip_suffix(e).filter(|stem| stem.ends_with(&participle[participle.len() - 1..])))
+        .unwrap_or(form);
+    [form, participle_stem].iter().any(|f| {
+        *f == participle || (bare_participle && participle.strip_prefix("ge") == Some(*f))
+    })
+}
+
+fn strong_verb_lemmas(words: &str, out: &mut Candidates<'_>) {
+    let splits = prefix_splits(words, INSEPARABLE_PREFIXES).chain(prefix_splits(words, SEPARABLE_PREFIXES).skip(1));
+    for (prefix, rest) in splits {
+        let bare_participle = INSEPARABLE_PREFIXES.contains(&prefix);
+        for (form, lemma) in IRREGULAR_FORMS {
+            if *form == rest {
+                out.push(format!("{prefix}{lemma}"));
+            }
+        }
+        for verb in STRONG_VERBS {
+            if is_strong_form(verb, rest, bare_participle) {
+                out.push(format!("{prefix}{}", verb.0));
+            }
+        }
+        // Separable zu-infinitive: `anzukommen` → `ankommen`.
+        if !prefix.is_empty() && !bare_participle {
+            if let Some(infinitive) = rest.strip_prefix("zu").filter(|r| r.ends_with('n') && r.len() > 3) {
+                out.push(format!("{prefix}{infinitive}"));
+            }
+        }
+    }
+}
+
+fn adjective_lemmas(word: &str, out: &mut Candidates<'_>) {
+    let bases = std::iter::once(word).chain(DECLENSION_ENDINGS.iter().filter_map(|e| word.strip_suffix(e)));
+    for base in bases.filter(|b| b.chars().count() >= 2) {
+        if base != word {
+            out.push(base.to_string());
+        }
+        if let Some((_, lemma)) = IRREGULAR_FORMS.iter().find(|(form, _)| *form == base) {
+            out.push((*lemma).to_string());
+        }
+        // Comparative `-er`, superlative `-est`/`-st`; `größt` drops only the `t`.
+        for stem in [
+            base.strip_suffix("er"),
+            base.strip_suffix("est"),
+            base.strip_suffix("st"),
+            base.strip_suffix("ßt").map(|_| &base[..base.len() - 1]),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if stem.chars().count() >= 2 {
+                out.push_with_umlaut_variant(stem);
+            }
+        }
+    }
+}
+
+fn noun_lemmas(word: &str, out: &mut Candidates<'_>) {
+    // Umlaut-only plurals: `Mütter` → `Mutter`.
+    if let Some(plain) = remove_last_umlaut(word) {
+        out.push(plain);
+    }
+    for (ending, singular) in NOUN_ENDINGS {
+        if let Some(stem) = word.strip_suffix(ending) {
+            out.push_with_umlaut_variant(&format!("{stem}{singular}"));
+        }
+    }
+}
+
+fn weak_verb_lemmas(word: &str, out: &mut Candidates<'_>) {
+    // Participles: `gemacht`, `aufgemacht`, `gesammelt`.
+    for (prefix, rest) in prefix_splits(word, SEPARABLE_PREFIXES) {
+        if let Some(core) = rest.strip_prefix("ge").and_then(|r| r.strip_suffix('t')) {
+            let stem = core.strip_suffix('e').filter(|s| s.ends_with(['t', 'd'])).unwrap_or(core);
+            if stem.chars().count() >= 2 {
+                out.push_infinitive(prefix, stem);
+            }
+        }
+    }
+    for ending in WEAK_VERB_ENDINGS {
+        if let Some(stem) = word.strip_suffix(ending).filter(|s| s.chars().count() >= 2) {
+            out.push_infinitive("", stem);
+        }
+    }
+}
+
+/// Candidate lemmas for an inflected word, most reliable first.
+///
+/// Returns an empty list for multi-word input; a leading `am` (`am schönsten`) is ignored.
+pub(crate) fn lemma_candidates(word: &str) -> Vec<String> {
+    let lowered = word.trim().to_lowercase();
+    let word = lowered.strip_prefix("am ").map_or(lowered.as_str(), str::trim);
+    if word.chars().count() < 2 || word.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+    let mut out = Candidates { word, out: Vec::new() };
+    strong_verb_lemmas(word, &mut out);
+    adjective_lemmas(word, &mut out);
+    noun_lemmas(word, &mut out);
+    weak_verb_lemmas(word, &mut out);
+    out.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has(word: &str, lemma: &str) -> bool {
+        lemma_candidates(word).iter().any(|c| c == lemma)
+    }
+
+    #[test]
+    fn maps_inflected_forms_to_lemmas() {
+        for (word, lemma) in [
+            ("ging", "gehen"),
+            ("gingen", "gehen"),
+            ("gegangen", "gehen"),
+            ("angekommen", "ankommen"),
+            ("verstand", "verstehen"),
+            ("zerbrochen", "zerbrechen"),
+            ("anzukommen", "ankommen"),
+            ("gibt", "geben"),
+            ("hält", "halten"),
+            ("dachten", "denken"),
+            ("bin", "sein"),
+            ("kannst", "können"),
+            ("Häuser", "haus"),
+            ("Mütter", "mutter"),
+            ("Lehrerinnen", "lehrerin"),
+            ("Museen", "museum"),
+            ("Kindern", "kind"),
+            ("Autos", "auto"),
+            ("schönsten", "schön"),
+            ("am schönsten", "schön"),
+            ("ältere", "alt"),
+            ("größten", "groß"),
+            ("besten", "gut"),
+            ("machte", "machen"),
+            ("gemacht", "machen"),
+            ("aufgemacht", "aufmachen"),
+            ("arbeitete", "arbeiten"),
+            ("gesammelt", "sammeln"),
+            ("spielst", "spielen"),
+        ] {
+            assert!(has(word, lemma), "{word} → {lemma}: {:?}", lemma_candidates(word));
+        }
+    }
+
+    #[test]
+    fn irregular_lemmas_rank_first_and_input_is_excluded() {
+        assert_eq!(lemma_candidates("ging").first().map(String::as_str), Some("gehen"));
+        assert_eq!(lemma_candidates("besser").first().map(String::as_str), Some("gut"));
+        assert!(!has("haus", "haus"));
+        assert!(lemma_candidates("zwei Wörter").is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use tauri::AppHandle;
 
 use crate::chm;
 use crate::app::model::{LinkTarget, RuntimeSource};
+use crate::error::DokhanError;
 use crate::parsing::text::path_stem;
 use crate::runtime::search::{eq_search_key, normalize_search_key};
 use crate::runtime::state::get_runtime;
@@ -126,7 +127,7 @@ fn mime_from_path(path: &str) -> &'static str {
 }
 
 /// Read binary object from CHM with path and basename fallbacks.
-pub(crate) fn read_chm_binary_object(chm: &mut chm::ChmArchive, local: &str) -> Option<Vec<u8>> {
+pub(crate) fn read_chm_binary_object(chm: &chm::ChmArchive, local: &str) -> Option<Vec<u8>> {
     let path = local.trim().trim_start_matches('/');
     if path.is_empty() {
         return None;
@@ -142,6 +143,7 @@ pub(crate) fn read_chm_binary_object(chm: &mut chm::ChmArchive, local: &str) ->
     let base = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
     let mut matches = chm
         .entries()
+        .ok()?
         .iter()
         .filter_map(|e| {
             let entry_lower = e.path.trim_start_matches('/').to_ascii_lowercase();
@@ -174,9 +176,9 @@ fn resolve_media_data_url_inner(
     current_source_path: Option<&str>,
     current_local: Option<&str>,
     zip_path: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, DokhanError> {
     let Some((source_override, local_raw, is_absolute)) = parse_internal_ref(href) else {
-        return Err(format!("unsupported media href: {href}"));
+        return Err(DokhanError::Unsupported(format!("unsupported media href: {href}")));
     };
     let resolved_local = resolve_relative_local(&local_raw, current_local, is_absolute);
     let source_path = source_override
@@ -184,9 +186,9 @@ fn resolve_media_data_url_inner(
         .unwrap_or_else(|| "master.chm".to_string());
     let bytes = match resolve_runtime_source(app, zip_path)? {
         RuntimeSource::ZipPath(zip_path) => {
-            let mut chm = open_named_chm_from_zip(&zip_path, &source_path)?;
-            read_chm_binary_object(&mut chm, &resolved_local)
-                .ok_or_else(|| format!("asset not found in {source_path}: {resolved_local}"))?
+            let chm = open_named_chm_from_zip(&zip_path, &source_path)?;
+            read_chm_binary_object(&chm, &resolved_local)
+                .ok_or_else(|| DokhanError::not_found("asset", format!("{source_path}::{resolved_local}")))?
         }
     };
     let mime = mime_from_path(&resolved_local);
@@ -205,7 +207,7 @@ pub(crate) fn resolve_media_data_url_impl(
     current_source_path: Option<&str>,
     current_local: Option<&str>,
     zip_path: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, DokhanError> {
     resolve_media_data_url_inner(app, href, current_source_path, current_local, zip_path)
 }
 
@@ -220,9 +222,9 @@ pub(crate) fn resolve_link_target_impl(
     current_source_path: Option<&str>,
     current_local: Option<&str>,
     zip_path: Option<String>,
-) -> Result<LinkTarget, String> {
+) -> Result<LinkTarget, DokhanError> {
     let Some((source_override, local_raw, is_absolute)) = parse_internal_ref(href) else {
-        return Err(format!("unsupported or empty href: {href}"));
+        return Err(DokhanError::Unsupported(format!("unsupported or empty href: {href}")));
     };
     let source_context = source_override
         .or(current_source_path.map(|x| x.to_ascii_lowercase()))
@@ -252,26 +254,24 @@ pub(crate) fn resolve_link_target_impl(
     }
 
     if let Some(entry) = runtime.entries.iter().find(|entry| {
-        entry.source_path.to_ascii_lowercase() == source_context
-            && (eq_search_key(&entry.headword, &local_stem_key)
+        entry.source_path().to_ascii_lowercase() == source_context
+            && (eq_search_key(entry.headword(), &local_stem_key)
                 || entry
-                    .aliases
-                    .iter()
+                    .aliases()
                     .any(|alias| eq_search_key(alias, &local_stem_key)))
     }) {
-        return Ok(LinkTarget::Entry { id: entry.id });
+        return Ok(LinkTarget::Entry { id: entry.id() });
     }
 
     if let Some(entry) = runtime.entries.iter().find(|entry| {
-        if eq_search_key(&entry.headword, &local_stem_key) {
+        if eq_search_key(entry.headword(), &local_stem_key) {
             return true;
         }
         entry
-            .aliases
-            .iter()
+            .aliases()
             .any(|alias| eq_search_key(alias, &local_stem_key))
     }) {
-        return Ok(LinkTarget::Entry { id: entry.id });
+        return Ok(LinkTarget::Entry { id: entry.id() });
     }
 
     Ok(LinkTarget::Content {
@@ -1,6 +1,16 @@
 //! Runtime layer: cache/state, search, CHM object access, and link/media resolution.
+pub(crate) mod cancel;
+pub(crate) mod compound;
+pub(crate) mod entries;
+pub(crate) mod events;
+pub(crate) mod headwords;
+pub(crate) mod lemma;
 pub(crate) mod link_media;
+pub(crate) mod pages;
+pub(crate) mod registry;
 pub(crate) mod search;
+pub(crate) mod spelling;
 pub(crate) mod state;
 pub(crate) mod storage;
+pub(crate) mod tokenizer;
 pub(crate) mod zip;
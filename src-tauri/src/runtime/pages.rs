@@ -0,0 +1,301 @@
+//! On-disk zstd page store with an offset table for random access to decoded CHM pages.
+//!
+//! Layout: one zstd frame per [`ContentPage`], then the bincode slot table sorted by
+//! `(source_path, local)`, then a fixed footer holding the table offset, length, and magic.
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::model::ContentPage;
+use crate::error::DokhanError;
+use crate::runtime::storage::{compress_zstd, decode_bin, decompress_zstd, encode_bin};
+
+const PAGE_STORE_MAGIC: &[u8; 8] = b"DKPGSTR1";
+const FOOTER_LEN: u64 = 24;
+/// Per-process counter that keeps concurrent writers' temp files apart.
+static WRITER_SEQ: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PageSlot {
+    source_path: String,
+    local: String,
+    offset: u64,
+    len: u64,
+    checksum: u64,
+}
+
+/// Normalized lookup key: lowercase CHM name and local path without a leading slash.
+fn page_key(source_path: &str, local: &str) -> (String, String) {
+    (
+        source_path.trim().to_ascii_lowercase(),
+        local.trim().trim_start_matches('/').to_ascii_lowercase(),
+    )
+}
+
+fn frame_checksum(frame: &[u8]) -> u64 {
+    let hash = blake3::hash(frame);
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_le_bytes(head)
+}
+
+struct WriterState {
+    out: BufWriter<File>,
+    offset: u64,
+    slots: Vec<PageSlot>,
+    keys: BTreeSet<(String, String)>,
+    failed: Option<std::io::Error>,
+}
+
+/// Append-only page store writer shared by parse workers.
+///
+/// Pages go to a temp file unique to this writer, which [`PageStoreWriter::finish`] renames
+/// into place; an unfinished writer removes its temp file on drop.
+pub(crate) struct PageStoreWriter {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    state: Mutex<WriterState>,
+}
+
+impl PageStoreWriter {
+    /// Start a store that will be published at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the temp file cannot be created.
+    pub(crate) fn create(path: &Path) -> Result<Self, DokhanError> {
+        let seq = WRITER_SEQ.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = path.with_extension(format!("{}-{seq}.tmp", std::process::id()));
+        let file = File::create(&tmp_path).map_err(|e| DokhanError::io("failed to create page store", e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            tmp_path,
+            state: Mutex::new(WriterState {
+                out: BufWriter::new(file),
+                offset: 0,
+                slots: Vec::new(),
+                keys: BTreeSet::new(),
+                failed: None,
+            }),
+        })
+    }
+
+    /// Whether a page for this key has already been written.
+    pub(crate) fn contains(&self, source_path: &str, local: &str) -> bool {
+        self.state
+            .lock()
+            .map(|st| st.keys.contains(&page_key(source_path, local)))
+            .unwrap_or(true)
+    }
+
+    /// Compress and append `page` unless its key is already stored.
+    ///
+    /// Write failures are remembered and reported by [`PageStoreWriter::finish`].
+    pub(crate) fn insert(&self, page: &ContentPage) {
+        let key = page_key(&page.source_path, &page.local);
+        if self.contains(&key.0, &key.1) {
+            return;
+        }
+        let Ok(frame) = encode_bin(page).and_then(|raw| compress_zstd(&raw)) else {
+            return;
+        };
+        let Ok(mut st) = self.state.lock() else {
+            return;
+        };
+        if st.failed.is_some() || !st.keys.insert(key.clone()) {
+            return;
+        }
+        if let Err(e) = st.out.write_all(&frame) {
+            st.failed = Some(e);
+            return;
+        }
+        let offset = st.offset;
+        st.offset += frame.len() as u64;
+        st.slots.push(PageSlot {
+            source_path: key.0,
+            local: key.1,
+            offset,
+            len: frame.len() as u64,
+            checksum: frame_checksum(&frame),
+        });
+    }
+
+    /// Write the slot table and footer, then move the store into place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when an earlier page write or the final write/rename failed.
+    pub(crate) fn finish(self) -> Result<PageStore, DokhanError> {
+        let mut st = self
+            .state
+            .lock()
+            .map_err(|_| DokhanError::LockPoisoned("page store writer"))?;
+        if let Some(e) = st.failed.take() {
+            return Err(DokhanError::io("failed to write page store", e));
+        }
+        let mut slots = std::mem::take(&mut st.slots);
+        slots.sort_by(|a, b| (&a.source_path, &a.local).cmp(&(&b.source_path, &b.local)));
+        let table = encode_bin(&slots)?;
+        let table_offset = st.offset;
+        let mut footer = Vec::with_capacity(FOOTER_LEN as usize);
+        footer.extend_from_slice(&table_offset.to_le_bytes());
+        footer.extend_from_slice(&(table.len() as u64).to_le_bytes());
+        footer.extend_from_slice(PAGE_STORE_MAGIC);
+        st.out
+            .write_all(&table)
+            .and_then(|_| st.out.write_all(&footer))
+            .and_then(|_| st.out.flush())
+            .map_err(|e| DokhanError::io("failed to write page store table", e))?;
+        drop(st);
+        fs::rename(&self.tmp_path, &self.path).map_err(|e| DokhanError::io("failed to publish page store", e))?;
+        let file = File::open(&self.path).map_err(|e| DokhanError::io("failed to open page store", e))?;
+        Ok(PageStore {
+            file: Mutex::new(file),
+            slots,
+        })
+    }
+}
+
+impl Drop for PageStoreWriter {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.tmp_path);
+    }
+}
+
+/// Read-only page store; pages are decompressed on demand.
+#[derive(Debug)]
+pub(crate) struct PageStore {
+    file: Mutex<File>,
+    slots: Vec<PageSlot>,
+}
+
+impl PageStore {
+    /// Open the store at `path`, reading only its footer and slot table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file is missing, truncated, or its table cannot be decoded.
+    pub(crate) fn open(path: &Path) -> Result<Self, DokhanError> {
+        let mut file = File::open(path).map_err(|e| DokhanError::io("failed to open page store", e))?;
+        let file_len = file
+            .metadata()
+            .map_err(|e| DokhanError::io("failed to stat page store", e))?
+            .len();
+        if file_len < FOOTER_LEN {
+            return Err(DokhanError::Internal("page store is truncated".to_string()));
+        }
+        let mut footer = [0u8; FOOTER_LEN as usize];
+        file.seek(SeekFrom::Start(file_len - FOOTER_LEN))
+            .and_then(|_| file.read_exact(&mut footer))
+            .map_err(|e| DokhanError::io("failed to read page store footer", e))?;
+        if &footer[16..] != PAGE_STORE_MAGIC {
+            return Err(DokhanError::Internal("page store magic mismatch".to_string()));
+        }
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&footer[..8]);
+        let table_offset = u64::from_le_bytes(word);
+        word.copy_from_slice(&footer[8..16]);
+        let table_len = u64::from_le_bytes(word);
+        if table_offset.checked_add(table_len) != Some(file_len - FOOTER_LEN) {
+            return Err(DokhanError::Internal("page store table out of bounds".to_string()));
+        }
+
+        let mut table = vec![0u8; table_len as usize];
+        file.seek(SeekFrom::Start(table_offset))
+            .and_then(|_| file.read_exact(&mut table))
+            .map_err(|e| DokhanError::io("failed to read page store table", e))?;
+        let slots: Vec<PageSlot> = decode_bin(&table)?;
+        if slots.iter().any(|s| s.offset.saturating_add(s.len) > table_offset) {
+            return Err(DokhanError::Internal("page store slot out of bounds".to_string()));
+        }
+        Ok(Self {
+            file: Mutex::new(file),
+            slots,
+        })
+    }
+
+    /// Look up and decode one page; `Ok(None)` when the store has no such page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the frame cannot be read, fails its checksum, or cannot be decoded.
+    pub(crate) fn get(&self, source_path: &str, local: &str) -> Result<Option<ContentPage>, DokhanError> {
+        let (source_path, local) = page_key(source_path, local);
+        let Ok(i) = self
+            .slots
+            .binary_search_by(|s| (s.source_path.as_str(), s.local.as_str()).cmp(&(source_path.as_str(), local.as_str())))
+        else {
+            return Ok(None);
+        };
+        let slot = &self.slots[i];
+        let mut frame = vec![0u8; slot.len as usize];
+        {
+            let mut file = self.file.lock().map_err(|_| DokhanError::LockPoisoned("page store"))?;
+            file.seek(SeekFrom::Start(slot.offset))
+                .and_then(|_| file.read_exact(&mut frame))
+                .map_err(|e| DokhanError::io("failed to read page store frame", e))?;
+        }
+        if frame_checksum(&frame) != slot.checksum {
+            return Err(DokhanError::Internal(format!(
+                "page store checksum mismatch: {}::{}",
+                slot.source_path, slot.local
+            )));
+        }
+        Ok(Some(decode_bin(&decompress_zstd(&frame)?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(source_path: &str, local: &str, body: &str) -> ContentPage {
+        ContentPage {
+            local: local.to_string(),
+            source_path: source_path.to_string(),
+            title: local.to_string(),
+            body_text: body.to_string(),
+            body_html: format!("<p>{body}</p>"),
+            encoding: "UTF-8".to_string(),
+        }
+    }
+
+    #[test]
+    fn pages_round_trip_by_normalized_key_and_detect_corruption() {
+        let dir = std::env::temp_dir().join(format!("dokhan-pages-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("tmp dir");
+        let path = dir.join("pages.bin");
+
+        let writer = PageStoreWriter::create(&path).expect("create");
+        let abandoned = PageStoreWriter::create(&path).expect("concurrent create");
+        assert_ne!(writer.tmp_path, abandoned.tmp_path);
+        abandoned.insert(&page("master.chm", "other.htm", "other"));
+        writer.insert(&page("merge02.chm", "b/Haus.htm", "house"));
+        writer.insert(&page("master.chm", "intro.htm", "intro"));
+        writer.insert(&page("MERGE02.chm", "/b/haus.htm", "duplicate"));
+        assert!(writer.contains("merge02.chm", "B/HAUS.htm"));
+        let written = writer.finish().expect("finish");
+        assert_eq!(written.slots.len(), 2);
+        drop(abandoned);
+        assert_eq!(fs::read_dir(&dir).expect("list dir").count(), 1, "temp files are removed");
+
+        let store = PageStore::open(&path).expect("open");
+        let found = store.get("Merge02.chm", "/b/haus.htm").expect("read").expect("page");
+        assert_eq!(found.body_text, "house");
+        assert_eq!(store.get("master.chm", "intro.htm").expect("read").expect("page").body_html, "<p>intro</p>");
+        assert!(store.get("master.chm", "missing.htm").expect("read").is_none());
+
+        let mut bytes = fs::read(&path).expect("read file");
+        bytes[0] ^= 0xff;
+        fs::write(&path, &bytes).expect("corrupt");
+        let store = PageStore::open(&path).expect("table still intact");
+        assert!(store.get("merge02.chm", "b/haus.htm").is_err());
+        assert!(store.get("master.chm", "intro.htm").expect("read").is_some());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
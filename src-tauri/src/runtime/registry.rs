@@ -0,0 +1,507 @@
+//! Registry of managed dictionaries with user-given names and enabled flags.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::app::model::{CacheGcReport, DictionaryBuildState, DictionaryInfo, RuntimeSource};
+use crate::error::DokhanError;
+use crate::runtime::search::evict_search_index;
+use crate::runtime::state::{dictionary_build_state, evict_runtime};
+use crate::runtime::storage::{
+    collect_orphaned_caches, list_managed_zips, load_registry_file, migrate_legacy_managed_zips,
+    remove_managed_dictionary, save_registry_file, split_managed_stem, ManagedZip,
+};
+use crate::runtime::zip::evict_chm_archives;
+
+/// Registry as last loaded or written, dropped whenever the managed ZIPs change on disk.
+static REGISTRY: OnceLock<Mutex<Option<Registry>>> = OnceLock::new();
+static LEGACY_MIGRATED: AtomicBool = AtomicBool::new(false);
+const REGISTRY_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct DictionaryRecord {
+    id: String,
+    name: String,
+    file_name: String,
+    enabled: bool,
+    added_at: u64,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    last_used_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RegistryFile {
+    version: u32,
+    dictionaries: Vec<DictionaryRecord>,
+}
+
+/// Registry records synced against the managed ZIPs, keyed by file name.
+struct Registry {
+    file: RegistryFile,
+    zips: BTreeMap<String, ManagedZip>,
+}
+
+impl Registry {
+    /// Records with their managed ZIP, pinned first, otherwise in registry order.
+    fn listed(&self) -> Vec<(&DictionaryRecord, &ManagedZip)> {
+        let mut out = self
+            .file
+            .dictionaries
+            .iter()
+            .filter_map(|r| Some((r, self.zips.get(&r.file_name)?)))
+            .collect::<Vec<_>>();
+        out.sort_by_key(|(r, _)| !r.pinned);
+        out
+    }
+}
+
+/// Registered dictionary resolved to its managed ZIP, without build state.
+pub(crate) struct RegisteredDictionary {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) zip_path: PathBuf,
+    pub(crate) enabled: bool,
+}
+
+/// Registry id of a managed ZIP: its file stem.
+pub(crate) fn dictionary_id_for_path(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("dictionary")
+        .to_string()
+}
+
+/// Registry id of a runtime source.
+pub(crate) fn dictionary_id_for_source(source: &RuntimeSource) -> String {
+    match source {
+        RuntimeSource::ZipPath(path) => dictionary_id_for_path(path),
+    }
+}
+
+/// Derive a display name from a managed file stem by dropping the `-<hash>` suffix.
+fn default_name(id: &str) -> String {
+    split_managed_stem(id).0.replace('_', " ")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Reconcile records with the managed ZIPs on disk; returns whether anything changed.
+///
+/// New ZIPs are appended enabled, in modification order; records whose ZIP is gone are dropped.
+fn sync_records(records: &mut Vec<DictionaryRecord>, zips: &[(String, SystemTime)], now: u64) -> bool {
+    let on_disk = zips.iter().map(|(name, _)| name.as_str()).collect::<BTreeSet<_>>();
+    let before = records.len();
+    records.retain(|r| on_disk.contains(r.file_name.as_str()));
+    let mut changed = records.len() != before;
+
+    let known = records.iter().map(|r| r.file_name.clone()).collect::<BTreeSet<_>>();
+    let mut fresh = zips
+        .iter()
+        .filter(|(name, _)| !known.contains(name))
+        .collect::<Vec<_>>();
+    fresh.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    for (file_name, _) in fresh {
+        let id = dictionary_id_for_path(Path::new(file_name));
+        records.push(DictionaryRecord {
+            name: default_name(&id),
+            id,
+            file_name: file_name.clone(),
+            enabled: true,
+            added_at: now,
+            pinned: false,
+            last_used_at: 0,
+        });
+        changed = true;
+    }
+    changed
+}
+
+fn lock_registry() -> Result<MutexGuard<'static, Option<Registry>>, DokhanError> {
+    REGISTRY
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("dictionary registry"))
+}
+
+/// Drop the in-memory registry so the next access rescans the managed ZIPs.
+///
+/// # Errors
+///
+/// Returns an error when the registry mutex is poisoned.
+pub(crate) fn invalidate_registry() -> Result<(), DokhanError> {
+    *lock_registry()? = None;
+    Ok(())
+}
+
+/// Point records at their renamed files; returns whether anything changed.
+fn apply_renames(records: &mut [DictionaryRecord], renames: &[(String, String)]) -> bool {
+    let mut changed = false;
+    for record in records.iter_mut() {
+        if let Some((_, new_name)) = renames.iter().find(|(old, _)| *old == record.file_name) {
+            record.id = dictionary_id_for_path(Path::new(new_name));
+            record.file_name = new_name.clone();
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Move legacy-fingerprint ZIPs and caches to content-hash keys once per process,
+/// carrying registry names and flags over. Caller holds the registry lock.
+fn migrate_legacy_locked(app: &AppHandle, registry: &mut Option<Registry>) -> Result<(), DokhanError> {
+    if LEGACY_MIGRATED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    let renames = migrate_legacy_managed_zips(app)?;
+    if !renames.is_empty() {
+        *registry = None;
+        if let Some(mut file) = load_registry_file::<RegistryFile>(app)?.filter(|f| f.version == REGISTRY_VERSION) {
+            if apply_renames(&mut file.dictionaries, &renames) {
+                save_registry_file(app, &file)?;
+            }
+        }
+    }
+    LEGACY_MIGRATED.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// Run the one-time legacy identity migration before any managed ZIP is resolved.
+///
+/// # Errors
+///
+/// Returns an error when managed files cannot be renamed or the registry cannot be saved.
+pub(crate) fn ensure_dictionaries_migrated(app: &AppHandle) -> Result<(), DokhanError> {
+    if LEGACY_MIGRATED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    let mut guard = lock_registry()?;
+    migrate_legacy_locked(app, &mut guard)
+}
+
+/// Scan the managed ZIPs and load the registry file, persisting it when syncing changed it.
+fn load_registry(app: &AppHandle) -> Result<Registry, DokhanError> {
+    let zips = list_managed_zips(app)?
+        .into_iter()
+        .filter_map(|zip| {
+            let name = zip.path.file_name().and_then(|n| n.to_str())?.to_string();
+            Some((name, zip))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let names = zips
+        .iter()
+        .map(|(name, zip)| (name.clone(), zip.modified))
+        .collect::<Vec<_>>();
+
+    let mut file = load_registry_file::<RegistryFile>(app)?
+        .filter(|f| f.version == REGISTRY_VERSION)
+        .unwrap_or_default();
+    if sync_records(&mut file.dictionaries, &names, now_secs()) {
+        file.version = REGISTRY_VERSION;
+        save_registry_file(app, &file)?;
+    }
+    Ok(Registry { file, zips })
+}
+
+/// Run `access` on the in-memory registry under the registry lock, loading it first when
+/// it was invalidated, and persist the records when `access` reports a change.
+///
+/// The registry is taken out while in use, so any error leaves it to be reloaded from disk.
+fn with_registry<T, F>(app: &AppHandle, access: F) -> Result<T, DokhanError>
+where
+    F: FnOnce(&mut Registry) -> Result<(T, bool), DokhanError>,
+{
+    with_registry_locked(app, &mut *lock_registry()?, access)
+}
+
+/// [`with_registry`] for a caller already holding the registry lock.
+fn with_registry_locked<T, F>(app: &AppHandle, guard: &mut Option<Registry>, access: F) -> Result<T, DokhanError>
+where
+    F: FnOnce(&mut Registry) -> Result<(T, bool), DokhanError>,
+{
+    migrate_legacy_locked(app, guard)?;
+    let mut registry = match guard.take() {
+        Some(registry) => registry,
+        None => load_registry(app)?,
+    };
+    let (out, changed) = access(&mut registry)?;
+    if changed {
+        registry.file.version = REGISTRY_VERSION;
+        save_registry_file(app, &registry.file)?;
+    }
+    *guard = Some(registry);
+    Ok(out)
+}
+
+/// Library listing of `registry` with each dictionary's current build state.
+fn dictionary_infos(app: &AppHandle, registry: &Registry) -> Result<Vec<DictionaryInfo>, DokhanError> {
+    registry
+        .listed()
+        .into_iter()
+        .map(|(r, zip)| {
+            Ok(DictionaryInfo {
+                id: r.id.clone(),
+                name: r.name.clone(),
+                zip_path: zip.path.to_string_lossy().to_string(),
+                enabled: r.enabled,
+                pinned: r.pinned,
+                added_at: r.added_at,
+                last_used_at: r.last_used_at,
+                size_bytes: zip.size,
+                fingerprint: split_managed_stem(&r.id).1.unwrap_or_default().to_string(),
+                build_state: dictionary_build_state(app, &RuntimeSource::ZipPath(zip.path.clone()))?,
+            })
+        })
+        .collect()
+}
+
+/// List managed dictionaries, pinned first, otherwise in registry order.
+///
+/// # Errors
+///
+/// Returns an error when the managed ZIP directory or registry file cannot be accessed.
+pub(crate) fn list_dictionaries_impl(app: &AppHandle) -> Result<Vec<DictionaryInfo>, DokhanError> {
+    with_registry(app, |registry| Ok((dictionary_infos(app, registry)?, false)))
+}
+
+/// Registered dictionaries in listing order, served from memory for per-query use.
+///
+/// # Errors
+///
+/// Returns an error when the registry has to be reloaded and the managed ZIP directory or
+/// registry file cannot be accessed.
+pub(crate) fn registered_dictionaries(app: &AppHandle) -> Result<Vec<RegisteredDictionary>, DokhanError> {
+    with_registry(app, |registry| {
+        let listed = registry
+            .listed()
+            .into_iter()
+            .map(|(r, zip)| RegisteredDictionary {
+                id: r.id.clone(),
+                name: r.name.clone(),
+                zip_path: zip.path.clone(),
+                enabled: r.enabled,
+            })
+            .collect();
+        Ok((listed, false))
+    })
+}
+
+fn update_record<F>(app: &AppHandle, id: &str, apply: F) -> Result<DictionaryInfo, DokhanError>
+where
+    F: FnOnce(&mut DictionaryRecord),
+{
+    with_registry(app, |registry| {
+        let record = registry
+            .file
+            .dictionaries
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| DokhanError::not_found("dictionary", id))?;
+        let before = record.clone();
+        apply(record);
+        let changed = *record != before;
+        let info = dictionary_infos(app, registry)?
+            .into_iter()
+            .find(|d| d.id == id)
+            .ok_or_else(|| DokhanError::not_found("dictionary", id))?;
+        Ok((info, changed))
+    })
+}
+
+/// Include or exclude a dictionary from federated search.
+///
+/// # Errors
+///
+/// Returns an error when the id is unknown or the registry cannot be saved.
+pub(crate) fn set_dictionary_enabled_impl(
+    app: &AppHandle,
+    id: &str,
+    enabled: bool,
+) -> Result<DictionaryInfo, DokhanError> {
+    update_record(app, id, |r| r.enabled = enabled)
+}
+
+/// Give a dictionary a display name.
+///
+/// # Errors
+///
+/// Returns an error when the name is blank, the id is unknown, or the registry cannot be saved.
+pub(crate) fn rename_dictionary_impl(app: &AppHandle, id: &str, name: &str) -> Result<DictionaryInfo, DokhanError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DokhanError::InvalidInput("dictionary name is empty".to_string()));
+    }
+    update_record(app, id, |r| r.name = name.to_string())
+}
+
+/// Keep or release a dictionary from deletion; pinned dictionaries are listed first.
+///
+/// # Errors
+///
+/// Returns an error when the id is unknown or the registry cannot be saved.
+pub(crate) fn set_dictionary_pinned_impl(
+    app: &AppHandle,
+    id: &str,
+    pinned: bool,
+) -> Result<DictionaryInfo, DokhanError> {
+    update_record(app, id, |r| r.pinned = pinned)
+}
+
+/// Record that `source` was opened, then run `then` under the registry lock so the
+/// dictionary cannot be deleted in between.
+///
+/// Failing to record the time is ignored since that is bookkeeping only.
+///
+/// # Errors
+///
+/// Returns an error when the registry mutex is poisoned or `then` fails.
+pub(crate) fn touch_dictionary<T, F>(app: &AppHandle, source: &RuntimeSource, then: F) -> Result<T, DokhanError>
+where
+    F: FnOnce() -> Result<T, DokhanError>,
+{
+    let id = dictionary_id_for_source(source);
+    let mut guard = lock_registry()?;
+    let _ = with_registry_locked(app, &mut guard, |registry| {
+        Ok(match registry.file.dictionaries.iter_mut().find(|r| r.id == id) {
+            Some(record) => {
+                record.last_used_at = now_secs();
+                ((), true)
+            }
+            None => ((), false),
+        })
+    });
+    then()
+}
+
+/// Delete a dictionary's managed ZIP, runtime cache, and search index, then collect orphans.
+///
+/// # Errors
+///
+/// Returns an error when the id is unknown, the dictionary is pinned or still building,
+/// or its files cannot be removed.
+pub(crate) fn delete_dictionary_impl(app: &AppHandle, id: &str) -> Result<CacheGcReport, DokhanError> {
+    // Checks and removal share the registry lock, which builds are also started under.
+    with_registry(app, |registry| {
+        let (record, zip) = registry
+            .listed()
+            .into_iter()
+            .find(|(r, _)| r.id == id)
+            .map(|(r, zip)| (r.clone(), zip.clone()))
+            .ok_or_else(|| DokhanError::not_found("dictionary", id))?;
+        if record.pinned {
+            return Err(DokhanError::InvalidInput(format!("dictionary is pinned: {}", record.name)));
+        }
+        let source = RuntimeSource::ZipPath(zip.path);
+        if dictionary_build_state(app, &source)? == DictionaryBuildState::Building {
+            return Err(DokhanError::InvalidInput(format!(
+                "dictionary is still building: {}",
+                record.name
+            )));
+        }
+
+        evict_runtime(&source)?;
+        evict_search_index(&source)?;
+        // Cached archives keep the ZIP open, which blocks its removal on Windows.
+        match &source {
+            RuntimeSource::ZipPath(zip_path) => evict_chm_archives(zip_path)?,
+        }
+        remove_managed_dictionary(app, &source)?;
+        registry.file.dictionaries.retain(|r| r.id != record.id);
+        registry.zips.remove(&record.file_name);
+
+        let mut report = collect_orphaned_caches(app)?;
+        report.removed.insert(0, record.id);
+        report.freed_bytes += zip.size;
+        Ok((report, true))
+    })
+}
+
+/// Remove runtime-cache and Tantivy directories left behind by deleted ZIPs.
+///
+/// # Errors
+///
+/// Returns an error when the cache directories cannot be read or cleaned.
+pub(crate) fn gc_runtime_cache_impl(app: &AppHandle) -> Result<CacheGcReport, DokhanError> {
+    let _guard = lock_registry()?;
+    collect_orphaned_caches(app)
+}
+
+/// Display name registered for `source`, falling back to its derived default.
+pub(crate) fn dictionary_name_for_source(app: &AppHandle, source: &RuntimeSource) -> String {
+    let id = dictionary_id_for_source(source);
+    registered_dictionaries(app)
+        .ok()
+        .and_then(|all| all.into_iter().find(|d| d.id == id))
+        .map(|d| d.name)
+        .unwrap_or_else(|| default_name(&id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_name_strips_fingerprint_suffix() {
+        assert_eq!(default_name("dictionary_v77-0123456789abcdef"), "dictionary v77");
+        assert_eq!(default_name("duden-2024"), "duden-2024");
+        assert_eq!(default_name("-0123456789abcdef"), "-0123456789abcdef");
+    }
+
+    #[test]
+    fn renames_carry_user_fields_to_content_hash_names() {
+        let hash = "00112233445566778899aabbccddeeff";
+        let mut records = vec![DictionaryRecord {
+            id: "duden-0123456789abcdef".to_string(),
+            name: "Duden".to_string(),
+            file_name: "duden-0123456789abcdef.zip".to_string(),
+            enabled: false,
+            added_at: 1,
+            pinned: true,
+            last_used_at: 2,
+        }];
+        let renames = vec![("duden-0123456789abcdef.zip".to_string(), format!("duden-{hash}.zip"))];
+        assert!(apply_renames(&mut records, &renames));
+        assert_eq!(records[0].id, format!("duden-{hash}"));
+        assert_eq!(records[0].name, "Duden");
+        assert!(records[0].pinned && !records[0].enabled);
+        assert_eq!(default_name(&records[0].id), "duden");
+        assert!(!apply_renames(&mut records, &renames));
+    }
+
+    #[test]
+    fn sync_keeps_user_fields_and_drops_missing_zips() {
+        let t = |s| UNIX_EPOCH + std::time::Duration::from_secs(s);
+        let mut records = Vec::new();
+        let zips = vec![
+            ("b-0123456789abcdef.zip".to_string(), t(20)),
+            ("a-0123456789abcdef.zip".to_string(), t(10)),
+        ];
+        assert!(sync_records(&mut records, &zips, 5));
+        assert_eq!(records.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+
+        records[0].name = "Koreanisch".to_string();
+        records[1].enabled = false;
+        assert!(!sync_records(&mut records, &zips, 6));
+
+        let zips = vec![zips[1].clone(), ("c.zip".to_string(), t(30))];
+        assert!(sync_records(&mut records, &zips, 7));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name, "Koreanisch");
+        assert_eq!(records[1].id, "c");
+        assert!(records[1].enabled);
+        assert_eq!(records[1].added_at, 7);
+    }
+}
@@ -1,5 +1,5 @@
 //! In-memory index and full-text search utilities.
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex, OnceLock};
@@ -7,15 +7,33 @@ use std::sync::{Arc, Mutex, OnceLock};
 use tauri::AppHandle;
 use tantivy::collector::TopDocs;
 use tantivy::doc;
-use tantivy::query::QueryParser;
-use tantivy::schema::{Field, Schema, TantivyDocument, Value, INDEXED, STORED, TEXT};
-use tantivy::{Index, IndexReader, ReloadPolicy};
+use tantivy::query::{BooleanQuery, Occur, Query, QueryParser, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TantivyDocument, TextFieldIndexing, TextOptions, Value, INDEXED, STORED, TEXT,
+};
+use tantivy::tokenizer::{TokenStream, Tokenizer, TokenizerManager};
+use tantivy::{Index, IndexReader, ReloadPolicy, Term};
 
-use crate::app::model::{DictionaryIndexEntry, EntryDetail, EntrySearchKey, SearchHit, TextSpan};
+use crate::app::model::{
+    CompoundPart, CompoundSplit, DictionaryIndexEntry, EntryDetail, EntrySearchKey, HeadwordLookupMode,
+    HeadwordSuggestion, LinkTarget, RuntimeIndex, SearchHit, TextSpan,
+};
 use crate::app::model::RuntimeSource;
+use crate::error::DokhanError;
+use crate::parsing::gloss::extract_glosses;
+use crate::parsing::hangul::{chosung, decompose_jamo, has_loose_jamo, hangul_keys, is_chosung_query};
 use crate::parsing::text::compact_ws;
-use crate::runtime::state::get_runtime;
+use crate::runtime::cancel::{check_cancelled, CancelToken};
+use crate::runtime::compound::split_compound;
+use crate::runtime::entries::{EntryRef, EntryTable};
+use crate::runtime::headwords::{HeadwordIndex, MAX_FUZZY_DISTANCE};
+use crate::runtime::lemma::lemma_candidates;
+use crate::runtime::registry::{dictionary_id_for_source, dictionary_name_for_source, registered_dictionaries};
+use crate::runtime::spelling::{german_edit_distance, EDIT_COST};
+use crate::runtime::state::{get_runtime, get_search_pending_runtime, runtime_ready};
+use crate::runtime::zip::ZipFullText;
 use crate::runtime::storage::search_index_dir;
+use crate::runtime::tokenizer::{strip_particle, DokhanTokenizer, TokenizerMode, KOREAN_TOKENIZER};
 use crate::resolve_runtime_source;
 
 static SEARCH_CACHE: OnceLock<Mutex<BTreeMap<String, Arc<TantivySearchIndex>>>> = OnceLock::new();
@@ -23,11 +41,27 @@ static NORMALIZE_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new
 static NORMALIZE_LOOSE_CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
 const NORMALIZE_CACHE_MAX: usize = 65_536;
 const SEARCH_INDEX_WRITER_HEAP_BYTES: usize = 50_000_000;
+const SEARCH_INDEX_CANCEL_CHECK_EVERY: usize = 1_024;
+/// Index score of a lemma row; ranks above prefix matches (300) of the inflected form.
+const LEMMA_INDEX_SCORE: usize = 320;
+/// Version of the Tantivy schema and analysis; bump when fields or tokenization change
+/// so persisted indexes are rebuilt while the parsed runtime cache is kept.
+pub(crate) const SEARCH_SCHEMA_VERSION: u32 = 3;
 
 const FIELD_ID: &str = "id";
 const FIELD_HEADWORD: &str = "headword";
 const FIELD_ALIASES: &str = "aliases";
 const FIELD_BODY: &str = "body";
+/// Korean glosses extracted from the definition, one field value per gloss in sense order.
+const FIELD_GLOSSES: &str = "glosses";
+/// Tantivy candidates fetched per dictionary before gloss reranking.
+const REVERSE_CANDIDATES: usize = 500;
+/// Reverse-lookup scores for an exact, prefix, or substring gloss match.
+const GLOSS_EXACT_SCORE: usize = 1_000;
+const GLOSS_PREFIX_SCORE: usize = 500;
+const GLOSS_CONTAINS_SCORE: usize = 250;
+/// Score lost per earlier gloss, capped after ten glosses.
+const GLOSS_POSITION_PENALTY: usize = 20;
 
 #[derive(Clone)]
 struct TantivySearchIndex {
@@ -37,6 +71,7 @@ struct TantivySearchIndex {
     headword_field: Field,
     aliases_field: Field,
     body_field: Field,
+    glosses_field: Field,
 }
 
 fn source_key(source: &RuntimeSource) -> String {
@@ -54,39 +89,72 @@ fn tantivy_schema() -> Schema {
     let mut schema_builder = Schema::builder();
     let _ = schema_builder.add_u64_field(FIELD_ID, INDEXED | STORED);
     let _ = schema_builder.add_text_field(FIELD_HEADWORD, TEXT);
-    let _ = schema_builder.add_text_field(FIELD_ALIASES, TEXT);
-    let _ = schema_builder.add_text_field(FIELD_BODY, TEXT);
+    let korean_text = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(KOREAN_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let _ = schema_builder.add_text_field(FIELD_ALIASES, korean_text.clone());
+    let _ = schema_builder.add_text_field(FIELD_BODY, korean_text.clone());
+    let _ = schema_builder.add_text_field(FIELD_GLOSSES, korean_text);
     schema_builder.build()
 }
 
-fn extract_fields(index: &Index) -> Result<(Field, Field, Field, Field), String> {
+/// Register the index-time analyzer; Tantivy does not persist custom tokenizers.
+fn register_tokenizers(index: &Index) {
+    index
+        .tokenizers()
+        .register(KOREAN_TOKENIZER, DokhanTokenizer::new(TokenizerMode::Index));
+}
+
+/// Tokenizers for query parsing, where particles are stripped rather than indexed.
+fn query_tokenizers() -> TokenizerManager {
+    let manager = TokenizerManager::default();
+    manager.register(KOREAN_TOKENIZER, DokhanTokenizer::new(TokenizerMode::Query));
+    manager
+}
+
+fn extract_fields(index: &Index) -> Result<(Field, Field, Field, Field, Field), DokhanError> {
     let schema = index.schema();
     let id_field = schema
         .get_field(FIELD_ID)
-        .map_err(|_| "tantivy index schema missing id field".to_string())?;
+        .map_err(|e| DokhanError::search("tantivy index schema missing id field", e))?;
     let headword_field = schema
         .get_field(FIELD_HEADWORD)
-        .map_err(|_| "tantivy index schema missing headword field".to_string())?;
+        .map_err(|e| DokhanError::search("tantivy index schema missing headword field", e))?;
     let aliases_field = schema
         .get_field(FIELD_ALIASES)
-        .map_err(|_| "tantivy index schema missing aliases field".to_string())?;
+        .map_err(|e| DokhanError::search("tantivy index schema missing aliases field", e))?;
     let body_field = schema
         .get_field(FIELD_BODY)
-        .map_err(|_| "tantivy index schema missing body field".to_string())?;
-    Ok((id_field, headword_field, aliases_field, body_field))
+        .map_err(|e| DokhanError::search("tantivy index schema missing body field", e))?;
+    let glosses_field = schema
+        .get_field(FIELD_GLOSSES)
+        .map_err(|e| DokhanError::search("tantivy index schema missing glosses field", e))?;
+    Ok((id_field, headword_field, aliases_field, body_field, glosses_field))
 }
 
-fn load_search_index(dir: &Path) -> Result<TantivySearchIndex, String> {
-    let index = Index::open_in_dir(dir).map_err(|e| format!("tantivy open failed: {e}"))?;
-    let (id_field, headword_field, aliases_field, body_field) = extract_fields(&index)?;
+/// Open the persisted index in `dir` to confirm it is readable with the expected fields.
+///
+/// # Errors
+///
+/// Returns an error when Tantivy cannot open the index or a field is missing.
+pub(crate) fn check_search_index(dir: &Path) -> Result<(), DokhanError> {
+    load_search_index(dir).map(|_| ())
+}
+
+fn load_search_index(dir: &Path) -> Result<TantivySearchIndex, DokhanError> {
+    let index = Index::open_in_dir(dir).map_err(|e| DokhanError::search("tantivy open failed", e))?;
+    register_tokenizers(&index);
+    let (id_field, headword_field, aliases_field, body_field, glosses_field) = extract_fields(&index)?;
     let reader = index
         .reader_builder()
         .reload_policy(ReloadPolicy::Manual)
         .try_into()
-        .map_err(|e| format!("tantivy reader init failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy reader init failed", e))?;
     reader
         .reload()
-        .map_err(|e| format!("tantivy reader reload failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy reader reload failed", e))?;
 
     Ok(TantivySearchIndex {
         index,
@@ -95,43 +163,67 @@ fn load_search_index(dir: &Path) -> Result<TantivySearchIndex, String> {
         headword_field,
         aliases_field,
         body_field,
+        glosses_field,
     })
 }
 
-fn rebuild_search_index(dir: &Path, entries: &[EntryDetail]) -> Result<TantivySearchIndex, String> {
+/// Recreate the Tantivy index in `dir`, polling `cancel` between document batches.
+///
+/// # Errors
+///
+/// Returns an error when Tantivy fails, or [`DokhanError::Cancelled`] after removing the partial index.
+fn rebuild_search_index(
+    dir: &Path,
+    entries: &EntryTable,
+    cancel: Option<&CancelToken>,
+) -> Result<TantivySearchIndex, DokhanError> {
     if dir.exists() {
-        fs::remove_dir_all(dir).map_err(|e| format!("failed to clear search index dir: {e}"))?;
+        fs::remove_dir_all(dir).map_err(|e| DokhanError::io("failed to clear search index dir", e))?;
     }
-    fs::create_dir_all(dir).map_err(|e| format!("failed to create search index dir: {e}"))?;
+    fs::create_dir_all(dir).map_err(|e| DokhanError::io("failed to create search index dir", e))?;
 
     let schema = tantivy_schema();
-    let index = Index::create_in_dir(dir, schema).map_err(|e| format!("tantivy create failed: {e}"))?;
-    let (id_field, headword_field, aliases_field, body_field) = extract_fields(&index)?;
+    let index = Index::create_in_dir(dir, schema).map_err(|e| DokhanError::search("tantivy create failed", e))?;
+    register_tokenizers(&index);
+    let (id_field, headword_field, aliases_field, body_field, glosses_field) = extract_fields(&index)?;
 
     let mut writer = index
         .writer(SEARCH_INDEX_WRITER_HEAP_BYTES)
-        .map_err(|e| format!("tantivy writer init failed: {e}"))?;
-    for entry in entries {
-        let aliases = entry.aliases.join(" ");
-        writer.add_document(doc!(
-            id_field => entry.id as u64,
-            headword_field => entry.headword.clone(),
+        .map_err(|e| DokhanError::search("tantivy writer init failed", e))?;
+    for (i, entry) in entries.iter().enumerate() {
+        if i % SEARCH_INDEX_CANCEL_CHECK_EVERY == 0 {
+            if let Err(err) = check_cancelled(cancel) {
+                drop(writer);
+                let _ = fs::remove_dir_all(dir);
+                return Err(err);
+            }
+        }
+        let aliases = entry.aliases().collect::<Vec<_>>().join(" ");
+        let mut document = doc!(
+            id_field => entry.id() as u64,
+            headword_field => entry.headword(),
             aliases_field => aliases,
-            body_field => entry.definition_text.clone()
-        )).map_err(|e| format!("tantivy add doc failed: {e}"))?;
+            body_field => entry.definition_text()
+        );
+        for (gloss, _, _) in entry.gloss_keys() {
+            document.add_text(glosses_field, gloss);
+        }
+        writer
+            .add_document(document)
+            .map_err(|e| DokhanError::search("tantivy add doc failed", e))?;
     }
     writer
         .commit()
-        .map_err(|e| format!("tantivy commit failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy commit failed", e))?;
 
     let reader = index
         .reader_builder()
         .reload_policy(ReloadPolicy::Manual)
         .try_into()
-        .map_err(|e| format!("tantivy reader init failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy reader init failed", e))?;
     reader
         .reload()
-        .map_err(|e| format!("tantivy reader reload failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy reader reload failed", e))?;
 
     Ok(TantivySearchIndex {
         index,
@@ -140,20 +232,22 @@ fn rebuild_search_index(dir: &Path, entries: &[EntryDetail]) -> Result<TantivySe
         headword_field,
         aliases_field,
         body_field,
+        glosses_field,
     })
 }
 
 fn get_or_build_tantivy_index(
     app: &AppHandle,
     source: &RuntimeSource,
-    entries: &[EntryDetail],
-) -> Result<Arc<TantivySearchIndex>, String> {
+    entries: &EntryTable,
+    cancel: Option<&CancelToken>,
+) -> Result<Arc<TantivySearchIndex>, DokhanError> {
     let key = source_key(source);
     let cache = SEARCH_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
     {
         let guard = cache
             .lock()
-            .map_err(|_| "search cache lock poisoned".to_string())?;
+            .map_err(|_| DokhanError::LockPoisoned("search cache"))?;
         if let Some(found) = guard.get(&key) {
             return Ok(found.clone());
         }
@@ -162,29 +256,53 @@ fn get_or_build_tantivy_index(
     let dir = search_index_dir(app, source)?;
     let built = match load_search_index(&dir) {
         Ok(index) => Arc::new(index),
-        Err(_) => Arc::new(rebuild_search_index(&dir, entries)?),
+        Err(_) => Arc::new(rebuild_search_index(&dir, entries, cancel)?),
     };
     let mut guard = cache
         .lock()
-        .map_err(|_| "search cache lock poisoned".to_string())?;
+        .map_err(|_| DokhanError::LockPoisoned("search cache"))?;
     guard.insert(key, built.clone());
     Ok(built)
 }
 
+/// Close the cached Tantivy reader for `source` so its directory can be removed.
+///
+/// # Errors
+///
+/// Returns an error when the search cache mutex is poisoned.
+pub(crate) fn evict_search_index(source: &RuntimeSource) -> Result<(), DokhanError> {
+    let cache = SEARCH_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
+    cache
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("search cache"))?
+        .remove(&source_key(source));
+    Ok(())
+}
+
 /// Build and cache Tantivy index for a runtime source eagerly.
 ///
 /// # Errors
 ///
-/// Returns an error when Tantivy index creation fails.
+/// Returns an error when Tantivy index creation fails or `cancel` fires mid-rebuild.
 pub(crate) fn warm_search_index(
     app: &AppHandle,
     source: &RuntimeSource,
-    entries: &[EntryDetail],
-) -> Result<(), String> {
-    let _ = get_or_build_tantivy_index(app, source, entries)?;
+    entries: &EntryTable,
+    cancel: Option<&CancelToken>,
+) -> Result<(), DokhanError> {
+    let _ = get_or_build_tantivy_index(app, source, entries, cancel)?;
     Ok(())
 }
 
+/// Lowercase and spell out umlauts and `ß` (`Straße` → `strasse`).
+pub(crate) fn fold_german(s: &str) -> String {
+    s.to_lowercase()
+        .replace("ä", "ae")
+        .replace("ö", "oe")
+        .replace("ü", "ue")
+        .replace("ß", "ss")
+}
+
 /// Normalize headword/search text with German orthography folding.
 pub(crate) fn normalize_search_key(s: &str) -> String {
     if let Ok(mut cache) = NORMALIZE_CACHE
@@ -194,23 +312,14 @@ pub(crate) fn normalize_search_key(s: &str) -> String {
         if let Some(found) = cache.get(s) {
             return found.clone();
         }
-        let normalized = s
-            .to_lowercase()
-            .replace("ä", "ae")
-            .replace("ö", "oe")
-            .replace("ü", "ue")
-            .replace("ß", "ss");
+        let normalized = fold_german(s);
         if cache.len() >= NORMALIZE_CACHE_MAX {
             cache.clear();
         }
         cache.insert(s.to_string(), normalized.clone());
         return normalized;
     }
-    s.to_lowercase()
-        .replace("ä", "ae")
-        .replace("ö", "oe")
-        .replace("ü", "ue")
-        .replace("ß", "ss")
+    fold_german(s)
 }
 
 /// Looser normalization for prefix/contains tolerance (ae->a etc.).
@@ -269,6 +378,21 @@ fn is_subsequence(haystack: &str, needle: &str) -> bool {
     false
 }
 
+/// Entry ids whose headword is a lemma of the single-word inflected `query`, best lemma first.
+///
+/// Empty when `query` is itself a headword, so base forms never pick up unrelated lemmas.
+fn lemma_entry_ids(runtime: &RuntimeIndex, query: &str) -> Vec<usize> {
+    if !runtime.headwords.exact(query).is_empty() {
+        return Vec::new();
+    }
+    let mut seen = BTreeSet::new();
+    lemma_candidates(query)
+        .iter()
+        .flat_map(|lemma| runtime.headwords.exact(lemma))
+        .filter(|id| seen.insert(*id))
+        .collect()
+}
+
 fn fuzzy_match_score(
     value_key: &str,
     value_loose: &str,
@@ -290,6 +414,17 @@ fn fuzzy_match_score(
     None
 }
 
+/// Prefix or substring score of a jamo or chosung key, on the scale of [`fuzzy_match_score`].
+fn hangul_match_score(value: &str, form: &str) -> Option<usize> {
+    if value.starts_with(form) {
+        Some(300)
+    } else if value.contains(form) {
+        Some(180)
+    } else {
+        None
+    }
+}
+
 fn normalize_with_char_map(value: &str) -> Vec<(char, usize)> {
     let mut out = Vec::<(char, usize)>::new();
     for (raw_idx, ch) in value.chars().enumerate() {
@@ -472,6 +607,16 @@ pub(crate) fn build_entry_search_keys(entries: &[EntryDetail]) -> Vec<EntrySearc
                 .iter()
                 .map(|a| normalize_search_key_loose(a))
                 .collect::<Vec<_>>();
+            let (aliases_jamo, aliases_chosung) = e
+                .aliases
+                .iter()
+                .map(|a| hangul_keys(a).unwrap_or_default())
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+            let glosses = extract_glosses(&e.definition_text);
+            let (glosses_jamo, glosses_chosung) = glosses
+                .iter()
+                .filter_map(|g| hangul_keys(g))
+                .unzip::<_, _, Vec<_>, Vec<_>>();
             EntrySearchKey {
                 headword: normalize_search_key(&e.headword),
                 headword_loose: normalize_search_key_loose(&e.headword),
@@ -479,23 +624,26 @@ pub(crate) fn build_entry_search_keys(entries: &[EntryDetail]) -> Vec<EntrySearc
                 body_loose: normalize_search_key_loose(&e.definition_text),
                 aliases,
                 aliases_loose,
+                aliases_jamo,
+                aliases_chosung,
+                glosses: glosses.join("\n"),
+                glosses_jamo: glosses_jamo.join("\n"),
+                glosses_chosung: glosses_chosung.join("\n"),
             }
         })
         .collect::<Vec<_>>()
 }
 
-/// Return index rows, optionally filtered by fuzzy matching.
-pub(crate) fn get_index_entries_impl(
+/// Return one dictionary's index rows with their match scores, best first.
+fn index_entries_for_source(
     app: &AppHandle,
-    prefix: Option<String>,
+    source: &RuntimeSource,
+    p: &str,
     limit: Option<usize>,
-    zip_path: Option<String>,
-) -> Result<Vec<DictionaryIndexEntry>, String> {
-    let source = resolve_runtime_source(app, zip_path)?;
-    let runtime = get_runtime(app, &source)?;
-    let p = prefix.unwrap_or_default();
-    let p_key = normalize_search_key(&p);
-    let p_loose = normalize_search_key_loose(&p);
+) -> Result<Vec<(usize, DictionaryIndexEntry)>, DokhanError> {
+    let runtime = get_runtime(app, source)?;
+    let p_key = normalize_search_key(p);
+    let p_loose = normalize_search_key_loose(p);
     let limit = if p.is_empty() {
         limit.unwrap_or(runtime.entries.len()).clamp(1, runtime.entries.len().max(1))
     } else {
@@ -505,68 +653,320 @@ pub(crate) fn get_index_entries_impl(
     if p.is_empty() {
         let mut out = Vec::new();
         for e in runtime.entries.iter().take(limit) {
-            out.push(DictionaryIndexEntry {
-                id: e.id,
-                headword: e.headword.clone(),
+            out.push((0, DictionaryIndexEntry {
+                id: e.id(),
+                headword: e.headword().to_string(),
                 headword_highlights: Vec::new(),
-                aliases: e.aliases.clone(),
-                source_path: e.source_path.clone(),
-            });
+                aliases: e.aliases().map(str::to_string).collect(),
+                source_path: e.source_path().to_string(),
+                dictionary_id: String::new(),
+                dictionary_name: String::new(),
+            }));
         }
         return Ok(out);
     }
 
+    // Every prefix, substring, or subsequence match is also an FST subsequence match,
+    // so only those candidates need scoring.
     let mut scored = Vec::<(usize, usize, usize)>::new();
-    for (idx, (e, k)) in runtime.entries.iter().zip(runtime.entry_keys.iter()).enumerate() {
-        let mut best = fuzzy_match_score(&k.headword, &k.headword_loose, &p_key, &p_loose);
-        for (alias, alias_loose) in k.aliases.iter().zip(k.aliases_loose.iter()) {
+    for id in runtime.headwords.subsequence(p) {
+        let Some(e) = runtime.entries.by_id(id) else {
+            continue;
+        };
+        let mut best = fuzzy_match_score(e.headword_key(), e.headword_loose(), &p_key, &p_loose);
+        for (alias, alias_loose) in e.alias_keys() {
             if let Some(alias_score) = fuzzy_match_score(alias, alias_loose, &p_key, &p_loose) {
                 best = Some(best.map_or(alias_score, |v| v.max(alias_score)));
             }
         }
         if let Some(score) = best {
-            scored.push((score, e.headword.len(), idx));
+            scored.push((score, e.headword().len(), id));
+        }
+    }
+    for id in lemma_entry_ids(&runtime, p) {
+        if let Some(e) = runtime.entries.by_id(id) {
+            scored.push((LEMMA_INDEX_SCORE, e.headword().len(), id));
+        }
+    }
+    // Initials (`ㅅㄱ`) and syllables still being composed (`삭`) only match at jamo level;
+    // the FST holds those keys from every syllable start, so candidates are a prefix lookup.
+    if let Some((p_jamo, p_chosung)) = hangul_keys(p) {
+        let chosung_only = is_chosung_query(p);
+        let form = if chosung_only { &p_chosung } else { &p_jamo };
+        for id in runtime.headwords.alias_hangul(form) {
+            let Some(e) = runtime.entries.by_id(id) else {
+                continue;
+            };
+            let best = e
+                .alias_hangul_keys()
+                .filter(|(jamo, _)| !jamo.is_empty())
+                .filter_map(|(jamo, chosung)| {
+                    if chosung_only {
+                        hangul_match_score(chosung, &p_chosung)
+                    } else {
+                        hangul_match_score(jamo, &p_jamo)
+                    }
+                })
+                .max();
+            if let Some(score) = best {
+                scored.push((score, e.headword().len(), id));
+            }
         }
     }
 
-    scored.sort_by(|(a_score, a_len, a_idx), (b_score, b_len, b_idx)| {
+    scored.sort_by(|(a_score, a_len, a_id), (b_score, b_len, b_id)| {
         b_score
             .cmp(a_score)
             .then(a_len.cmp(b_len))
-            .then(a_idx.cmp(b_idx))
+            .then(a_id.cmp(b_id))
     });
+    let mut seen = BTreeSet::new();
+    scored.retain(|(_, _, id)| seen.insert(*id));
     let mut out = Vec::with_capacity(limit.min(scored.len()));
-    for (_, _, idx) in scored.into_iter().take(limit) {
-        let e = &runtime.entries[idx];
-        out.push(DictionaryIndexEntry {
-            id: e.id,
-            headword: e.headword.clone(),
-            headword_highlights: build_headword_highlights(&e.headword, &p),
-            aliases: e.aliases.clone(),
-            source_path: e.source_path.clone(),
-        });
+    for (score, _, id) in scored.into_iter().take(limit) {
+        let Some(e) = runtime.entries.by_id(id) else {
+            continue;
+        };
+        out.push((score, DictionaryIndexEntry {
+            id: e.id(),
+            headword: e.headword().to_string(),
+            headword_highlights: build_headword_highlights(e.headword(), p),
+            aliases: e.aliases().map(str::to_string).collect(),
+            source_path: e.source_path().to_string(),
+            dictionary_id: String::new(),
+            dictionary_name: String::new(),
+        }));
     }
     Ok(out)
 }
 
-fn search_entries_linear(query: &str, limit: usize, entries: &[EntryDetail], keys: &[EntrySearchKey]) -> Vec<SearchHit> {
+/// Return index rows, optionally filtered by fuzzy matching.
+///
+/// Without `zip_path`, every enabled dictionary with a ready runtime is queried; `limit`
+/// applies per dictionary and rows stay grouped by dictionary, best group first.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails, or when every queried dictionary fails.
+pub(crate) fn get_index_entries_impl(
+    app: &AppHandle,
+    prefix: Option<String>,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+) -> Result<Vec<DictionaryIndexEntry>, DokhanError> {
+    let p = prefix.unwrap_or_default();
+    federate(app, zip_path, |source, dictionary| {
+        let rows = index_entries_for_source(app, source, &p, limit)?;
+        let best = rows.first().map_or(0, |(score, _)| *score);
+        let rows = rows
+            .into_iter()
+            .map(|(_, mut row)| {
+                row.dictionary_id = dictionary.id.clone();
+                row.dictionary_name = dictionary.name.clone();
+                row
+            })
+            .collect();
+        Ok((best, rows))
+    })
+}
+
+/// Return index rows whose normalized headword or alias matches `query` under `mode`.
+///
+/// Rows are in entry id order. `max_distance` applies to [`HeadwordLookupMode::Fuzzy`] and
+/// defaults to 1. Without `zip_path`, every enabled dictionary with a ready runtime is queried
+/// in registry order; `limit` applies per dictionary.
+///
+/// # Errors
+///
+/// Returns [`DokhanError::InvalidInput`] for an invalid pattern or distance, or an error when
+/// source resolution fails or every queried dictionary fails.
+pub(crate) fn lookup_headwords_impl(
+    app: &AppHandle,
+    query: &str,
+    mode: HeadwordLookupMode,
+    max_distance: Option<u32>,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+) -> Result<Vec<DictionaryIndexEntry>, DokhanError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(200).clamp(1, 5_000);
+    federate(app, zip_path, |source, dictionary| {
+        let runtime = get_runtime(app, source)?;
+        Ok((0, lookup_headwords_in(&runtime, dictionary, query, mode, max_distance, limit)?))
+    })
+}
+
+/// One dictionary's rows for [`lookup_headwords_impl`].
+fn lookup_headwords_in(
+    runtime: &RuntimeIndex,
+    dictionary: &QueryTarget,
+    query: &str,
+    mode: HeadwordLookupMode,
+    max_distance: Option<u32>,
+    limit: usize,
+) -> Result<Vec<DictionaryIndexEntry>, DokhanError> {
+    let ids = match mode {
+        HeadwordLookupMode::Prefix => runtime.headwords.prefix(query),
+        HeadwordLookupMode::Fuzzy => runtime.headwords.fuzzy(query, max_distance.unwrap_or(1))?,
+        HeadwordLookupMode::Regex => runtime.headwords.regex(query)?,
+    };
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| runtime.entries.by_id(id))
+        .take(limit)
+        .map(|e| DictionaryIndexEntry {
+            id: e.id(),
+            headword: e.headword().to_string(),
+            headword_highlights: if mode == HeadwordLookupMode::Prefix {
+                build_headword_highlights(e.headword(), query)
+            } else {
+                Vec::new()
+            },
+            aliases: e.aliases().map(str::to_string).collect(),
+            source_path: e.source_path().to_string(),
+            dictionary_id: dictionary.id.clone(),
+            dictionary_name: dictionary.name.clone(),
+        })
+        .collect())
+}
+
+/// Return the headwords nearest to `query` by German-weighted edit distance, closest first.
+///
+/// Candidates come from [`HeadwordIndex::weighted_fuzzy`] and are ranked on the
+/// [`normalize_search_key`] form; short queries allow one edit, longer ones two. Without
+/// `zip_path`, every enabled dictionary with a ready runtime contributes, and headwords
+/// suggested by several dictionaries are listed once.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails, or when every queried dictionary fails.
+pub(crate) fn suggest_headwords_impl(
+    app: &AppHandle,
+    query: &str,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+) -> Result<Vec<HeadwordSuggestion>, DokhanError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(5).clamp(1, 50);
+    let mut ranked = federate(app, zip_path, |source, dictionary| {
+        let runtime = get_runtime(app, source)?;
+        Ok((0, suggest_headwords_in(&runtime, dictionary, query)?))
+    })?;
+
+    ranked.sort_by(|(a_cost, a), (b_cost, b)| {
+        a_cost
+            .cmp(b_cost)
+            .then_with(|| a.headword.len().abs_diff(query.len()).cmp(&b.headword.len().abs_diff(query.len())))
+            .then_with(|| a.headword.cmp(&b.headword))
+    });
+    let mut seen = BTreeSet::new();
+    Ok(ranked
+        .into_iter()
+        .map(|(_, suggestion)| suggestion)
+        .filter(|s| seen.insert(s.headword.to_lowercase()))
+        .take(limit)
+        .collect())
+}
+
+/// One dictionary's `(cost, suggestion)` rows for [`suggest_headwords_impl`], unsorted.
+fn suggest_headwords_in(
+    runtime: &RuntimeIndex,
+    dictionary: &QueryTarget,
+    query: &str,
+) -> Result<Vec<(u32, HeadwordSuggestion)>, DokhanError> {
+    let q_key = normalize_search_key(query);
+    let distance = if q_key.chars().count() <= 4 { 1 } else { MAX_FUZZY_DISTANCE };
+    let max_cost = distance * EDIT_COST;
+    let ids = runtime.headwords.weighted_fuzzy(query, max_cost)?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| runtime.entries.by_id(id))
+        .filter_map(|e| {
+            let cost = std::iter::once(e.headword_key())
+                .chain(e.alias_keys().map(|(key, _)| key))
+                .map(|key| german_edit_distance(&q_key, key))
+                .min()
+                .filter(|cost| *cost <= max_cost)?;
+            Some((cost, HeadwordSuggestion {
+                id: e.id(),
+                headword: e.headword().to_string(),
+                distance: cost as f32 / EDIT_COST as f32,
+                dictionary_id: dictionary.id.clone(),
+                dictionary_name: dictionary.name.clone(),
+            }))
+        })
+        .collect())
+}
+
+/// Split a compound query into headword parts, each linked to its entry.
+///
+/// Each dictionary splits against its own headwords and contributes at most one split;
+/// dictionaries where `query` is itself a headword contribute none. Multi-word queries
+/// are never split.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails, or when every queried dictionary fails.
+pub(crate) fn split_compound_impl(
+    app: &AppHandle,
+    query: &str,
+    zip_path: Option<String>,
+) -> Result<Vec<CompoundSplit>, DokhanError> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return Ok(Vec::new());
+    }
+    federate(app, zip_path, |source, dictionary| {
+        let runtime = get_runtime(app, source)?;
+        if !runtime.headwords.exact(query).is_empty() {
+            return Ok((0, Vec::new()));
+        }
+        let Some(segments) = split_compound(query, |part| runtime.headwords.exact(part).first().copied()) else {
+            return Ok((0, Vec::new()));
+        };
+        let parts = segments
+            .into_iter()
+            .map(|segment| {
+                let entry = runtime.entries.by_id(segment.value)?;
+                Some(CompoundPart {
+                    text: segment.text,
+                    linking: segment.linking,
+                    headword: entry.headword().to_string(),
+                    link: LinkTarget::Entry { id: entry.id() },
+                })
+            })
+            .collect::<Option<Vec<_>>>();
+        let split = parts.map(|parts| CompoundSplit {
+            dictionary_id: dictionary.id.clone(),
+            dictionary_name: dictionary.name.clone(),
+            parts,
+        });
+        Ok((0, split.into_iter().collect()))
+    })
+}
+
+fn search_entries_linear(query: &str, limit: usize, entries: &EntryTable) -> Vec<SearchHit> {
     let terms = query
         .split_whitespace()
         .map(|x| (normalize_search_key(x), normalize_search_key_loose(x)))
         .collect::<Vec<_>>();
 
     let mut hits = Vec::<SearchHit>::new();
-    for (e, k) in entries.iter().zip(keys.iter()) {
+    for e in entries.iter() {
         let mut score = 0usize;
         let mut ok = true;
         for (t_key, t_loose) in &terms {
-            let in_head = contains_search_key_precomputed(&k.headword, &k.headword_loose, t_key, t_loose)
-                || k.aliases
-                    .iter()
-                    .zip(k.aliases_loose.iter())
+            let in_head = contains_search_key_precomputed(e.headword_key(), e.headword_loose(), t_key, t_loose)
+                || e.alias_keys()
                     .any(|(a, al)| contains_search_key_precomputed(a, al, t_key, t_loose));
             let in_body =
-                contains_search_key_precomputed(&k.body, &k.body_loose, t_key, t_loose);
+                contains_search_key_precomputed(e.body_key(), e.body_loose(), t_key, t_loose);
             if !(in_head || in_body) {
                 ok = false;
                 break;
@@ -579,13 +979,16 @@ fn search_entries_linear(query: &str, limit: usize, entries: &[EntryDetail], key
             }
         }
         if ok {
-            let snippet = e.definition_text.chars().take(180).collect::<String>();
+            let snippet = e.definition_text().chars().take(180).collect::<String>();
             hits.push(SearchHit {
-                id: e.id,
-                headword: e.headword.clone(),
-                source_path: e.source_path.clone(),
+                id: e.id(),
+                headword: e.headword().to_string(),
+                source_path: e.source_path().to_string(),
                 score,
                 snippet,
+                dictionary_id: String::new(),
+                dictionary_name: String::new(),
+                matched_via: None,
             });
         }
     }
@@ -599,32 +1002,37 @@ fn search_entries_tantivy(
     source: &RuntimeSource,
     query: &str,
     limit: usize,
-    entries: &[EntryDetail],
-) -> Result<Vec<SearchHit>, String> {
-    let idx = get_or_build_tantivy_index(app, source, entries)?;
-    let mut parser = QueryParser::for_index(
-        &idx.index,
+    entries: &EntryTable,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    let idx = get_or_build_tantivy_index(app, source, entries, None)?;
+    search_tantivy_index(&idx, query, limit, entries)
+}
+
+fn search_tantivy_index(
+    idx: &TantivySearchIndex,
+    query: &str,
+    limit: usize,
+    entries: &EntryTable,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    let mut parser = QueryParser::new(
+        idx.index.schema(),
         vec![idx.headword_field, idx.aliases_field, idx.body_field],
+        query_tokenizers(),
     );
     parser.set_conjunction_by_default();
     let parsed = parser
         .parse_query(query)
-        .map_err(|e| format!("tantivy query parse failed: {e}"))?;
+        .map_err(|e| DokhanError::search("tantivy query parse failed", e.into()))?;
 
     let searcher = idx.reader.searcher();
     let top_docs = searcher
         .search(&parsed, &TopDocs::with_limit(limit))
-        .map_err(|e| format!("tantivy search failed: {e}"))?;
-    let by_id = entries
-        .iter()
-        .map(|e| (e.id, e))
-        .collect::<HashMap<usize, &EntryDetail>>();
-
+        .map_err(|e| DokhanError::search("tantivy search failed", e))?;
     let mut out = Vec::<SearchHit>::new();
     for (score, addr) in top_docs {
         let doc: TantivyDocument = searcher
             .doc(addr)
-            .map_err(|e| format!("tantivy doc read failed: {e}"))?;
+            .map_err(|e| DokhanError::search("tantivy doc read failed", e))?;
         let Some(id) = doc
             .get_first(idx.id_field)
             .and_then(|v| v.as_u64())
@@ -632,48 +1040,502 @@ fn search_entries_tantivy(
         else {
             continue;
         };
-        let Some(entry) = by_id.get(&id) else {
+        let Some(entry) = entries.by_id(id) else {
             continue;
         };
         out.push(SearchHit {
-            id: entry.id,
-            headword: entry.headword.clone(),
-            source_path: entry.source_path.clone(),
+            id: entry.id(),
+            headword: entry.headword().to_string(),
+            source_path: entry.source_path().to_string(),
             score: score.max(0.0).round() as usize,
-            snippet: entry.definition_text.chars().take(180).collect::<String>(),
+            snippet: entry.definition_text().chars().take(180).collect::<String>(),
+            dictionary_id: String::new(),
+            dictionary_name: String::new(),
+            matched_via: None,
         });
     }
     Ok(out)
 }
 
-/// Execute weighted in-memory search over headword/aliases/body.
+/// Best score of `glosses` against the space-free query `forms`, favouring exact and early glosses.
+fn gloss_match_score<'g>(glosses: impl IntoIterator<Item = &'g str>, forms: &[String]) -> Option<usize> {
+    glosses
+        .into_iter()
+        .enumerate()
+        .filter_map(|(position, gloss)| {
+            let gloss = gloss.replace(' ', "");
+            let base = if forms.contains(&gloss) {
+                GLOSS_EXACT_SCORE
+            } else if forms.iter().any(|f| gloss.starts_with(f.as_str())) {
+                GLOSS_PREFIX_SCORE
+            } else if forms.iter().any(|f| gloss.contains(f.as_str())) {
+                GLOSS_CONTAINS_SCORE
+            } else {
+                return None;
+            };
+            Some(base - position.min(9) * GLOSS_POSITION_PENALTY)
+        })
+        .max()
+}
+
+/// Conjunction of the query-mode tokens of `text` on `field`, or `None` when no token remains.
+///
+/// Built without the query parser, so `(`, `"`, `:` or `-` in user input are plain text.
+fn token_conjunction(field: Field, text: &str) -> Option<BooleanQuery> {
+    let mut tokenizer = DokhanTokenizer::new(TokenizerMode::Query);
+    let mut stream = tokenizer.token_stream(text);
+    let mut terms = BTreeSet::new();
+    while stream.advance() {
+        terms.insert(stream.token().text.clone());
+    }
+    if terms.is_empty() {
+        return None;
+    }
+    let clauses = terms
+        .into_iter()
+        .map(|text| {
+            let term = TermQuery::new(Term::from_field_text(field, &text), IndexRecordOption::WithFreqs);
+            (Occur::Must, Box::new(term) as Box<dyn Query>)
+        })
+        .collect();
+    Some(BooleanQuery::new(clauses))
+}
+
+fn search_reverse_index(
+    idx: &TantivySearchIndex,
+    query: &str,
+    limit: usize,
+    entries: &EntryTable,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    let Some(parsed) = token_conjunction(idx.glosses_field, query) else {
+        return Ok(Vec::new());
+    };
+    let searcher = idx.reader.searcher();
+    let top_docs = searcher
+        .search(&parsed, &TopDocs::with_limit(REVERSE_CANDIDATES))
+        .map_err(|e| DokhanError::search("tantivy search failed", e))?;
+
+    let mut forms = vec![query.replace(' ', "")];
+    forms.push(strip_particle(query).replace(' ', ""));
+    forms.dedup();
+    let mut ranked = Vec::new();
+    for (relevance, addr) in top_docs {
+        let doc: TantivyDocument = searcher
+            .doc(addr)
+            .map_err(|e| DokhanError::search("tantivy doc read failed", e))?;
+        let Some(entry) = doc
+            .get_first(idx.id_field)
+            .and_then(|v| v.as_u64())
+            .and_then(|id| entries.by_id(id as usize))
+        else {
+            continue;
+        };
+        // Token matches that span gloss boundaries are not gloss matches.
+        let Some(score) = gloss_match_score(entry.gloss_keys().map(|(gloss, _, _)| gloss), &forms) else {
+            continue;
+        };
+        ranked.push((relevance, gloss_hit(entry, score)));
+    }
+    ranked.sort_by(|(a_rel, a), (b_rel, b)| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b_rel.total_cmp(a_rel))
+            .then_with(|| a.headword.len().cmp(&b.headword.len()))
+            .then_with(|| a.headword.cmp(&b.headword))
+    });
+    Ok(ranked.into_iter().map(|(_, hit)| hit).take(limit).collect())
+}
+
+fn gloss_hit(entry: EntryRef<'_>, score: usize) -> SearchHit {
+    let glosses = entry.gloss_keys().map(|(gloss, _, _)| gloss).collect::<Vec<_>>();
+    SearchHit {
+        id: entry.id(),
+        headword: entry.headword().to_string(),
+        source_path: entry.source_path().to_string(),
+        score,
+        snippet: glosses.join(", ").chars().take(180).collect::<String>(),
+        dictionary_id: String::new(),
+        dictionary_name: String::new(),
+        matched_via: Some("gloss"),
+    }
+}
+
+/// Reverse lookup on initials (`ㅅㄱ`) or half-composed syllables (`사ㄱ`, `삭`), ranking the
+/// entries whose gloss keys the headword FST matches at a syllable start.
+fn search_reverse_jamo(query: &str, limit: usize, entries: &EntryTable, headwords: &HeadwordIndex) -> Vec<SearchHit> {
+    let chosung_only = is_chosung_query(query);
+    let compact = query.replace(' ', "");
+    let forms = [if chosung_only { chosung(&compact) } else { decompose_jamo(&compact) }];
+    let mut hits = headwords
+        .gloss_hangul(&forms[0])
+        .into_iter()
+        .filter_map(|id| {
+            let entry = entries.by_id(id)?;
+            let keys = entry
+                .gloss_keys()
+                .map(|(_, jamo, initials)| if chosung_only { initials } else { jamo });
+            gloss_match_score(keys, &forms).map(|score| gloss_hit(entry, score))
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.headword.len().cmp(&b.headword.len()))
+            .then_with(|| a.headword.cmp(&b.headword))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+/// Korean → German lookup: headwords whose Korean glosses match `query`, best first.
+///
+/// Candidates come from the Tantivy `glosses` field and are ranked by how exactly and how
+/// early a gloss matches; a trailing particle on the query is ignored. Initials-only and
+/// half-composed queries, or queries without a token match, fall back to the glosses' jamo
+/// and chosung keys. Without `zip_path`, every enabled dictionary is searched and hits stay
+/// grouped per dictionary.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails, or when every queried dictionary fails.
+pub(crate) fn search_reverse_impl(
+    app: &AppHandle,
+    query: &str,
+    limit: Option<usize>,
+    zip_path: Option<String>,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    let q = compact_ws(query);
+    if q.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    federate(app, zip_path, |source, dictionary| {
+        let runtime = get_runtime(app, source)?;
+        // A build still writing the Tantivy index leaves the gloss keys of the headword FST.
+        let indexed = !is_chosung_query(&q) && !has_loose_jamo(&q) && get_search_pending_runtime(source)?.is_none();
+        let mut hits = if indexed {
+            let idx = get_or_build_tantivy_index(app, source, &runtime.entries, None)?;
+            search_reverse_index(&idx, &q, limit, &runtime.entries)?
+        } else {
+            Vec::new()
+        };
+        if hits.is_empty() {
+            hits = search_reverse_jamo(&q, limit, &runtime.entries, &runtime.headwords);
+        }
+        for hit in &mut hits {
+            hit.dictionary_id = dictionary.id.clone();
+            hit.dictionary_name = dictionary.name.clone();
+        }
+        let best = best_gloss_match_class(&q, &hits, &runtime.entries);
+        Ok((best, hits))
+    })
+}
+
+/// CHM full-text indexes of a pending runtime, with topics mapped back to its entries.
+pub(crate) struct PendingFullText {
+    indexes: ZipFullText,
+    /// Entry id per `(source path, lowercased target local)`.
+    by_local: HashMap<(String, String), usize>,
+}
+
+impl PendingFullText {
+    /// Open the full-text index of every CHM that `runtime` has entries from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a CHM cannot be loaded from the dataset ZIP.
+    pub(crate) fn open(source: &RuntimeSource, runtime: &RuntimeIndex) -> Result<Self, DokhanError> {
+        let chm_names = runtime
+            .entries
+            .iter()
+            .map(|e| e.source_path().to_string())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let indexes = match source {
+            RuntimeSource::ZipPath(zip_path) => ZipFullText::open(zip_path, &chm_names)?,
+        };
+        let by_local = runtime
+            .entries
+            .iter()
+            .filter(|e| !e.target_local().is_empty())
+            .map(|e| ((e.source_path().to_string(), e.target_local().to_ascii_lowercase()), e.id()))
+            .collect();
+        Ok(Self { indexes, by_local })
+    }
+}
+
+/// Answer a query from the CHMs' own `$FIftiMain` indexes, mapping topics back to entries.
+fn search_entries_fulltext(
+    fulltext: &PendingFullText,
+    query: &str,
+    limit: usize,
+    runtime: &RuntimeIndex,
+) -> Vec<SearchHit> {
+    let terms = query.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+    let mut best = BTreeMap::<usize, SearchHit>::new();
+    for topic in fulltext.indexes.search(&terms, limit) {
+        let key = (topic.source_path, topic.local.to_ascii_lowercase());
+        let Some(entry) = fulltext.by_local.get(&key).and_then(|id| runtime.entries.by_id(*id)) else {
+            continue;
+        };
+        let hit = best.entry(entry.id()).or_insert_with(|| SearchHit {
+            id: entry.id(),
+            headword: entry.headword().to_string(),
+            source_path: entry.source_path().to_string(),
+            score: 0,
+            snippet: entry.definition_text().chars().take(180).collect::<String>(),
+            dictionary_id: String::new(),
+            dictionary_name: String::new(),
+            matched_via: None,
+        });
+        hit.score = hit.score.max(topic.score);
+    }
+    let mut hits = best.into_values().collect::<Vec<_>>();
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.headword.cmp(&b.headword)));
+    hits.truncate(limit);
+    hits
+}
+
+/// Search one dictionary, preferring Tantivy.
+///
+/// While a background build is still writing the Tantivy index, the CHM full-text
+/// indexes answer instead, with the linear scan as last resort.
+fn search_source(
+    app: &AppHandle,
+    source: &RuntimeSource,
+    q: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>, DokhanError> {
+    let (runtime, hits) = if let Some(pending) = get_search_pending_runtime(source)? {
+        let runtime = pending.runtime.clone();
+        let fulltext = pending
+            .fulltext
+            .get_or_init(|| PendingFullText::open(source, &runtime).ok());
+        let hits = match fulltext.as_ref().map(|f| search_entries_fulltext(f, q, limit, &runtime)) {
+            Some(v) if !v.is_empty() => v,
+            _ => search_entries_linear(q, limit, &runtime.entries),
+        };
+        (runtime, hits)
+    } else {
+        let runtime = get_runtime(app, source)?;
+        let hits = search_entries_tantivy(app, source, q, limit, &runtime.entries)
+            .unwrap_or_else(|_| search_entries_linear(q, limit, &runtime.entries));
+        (runtime, hits)
+    };
+    Ok(with_lemma_hits(&runtime, q, hits, limit))
+}
+
+/// Put the lemma entries of an inflected query ahead of its direct hits.
+///
+/// Lemma hits take the best direct score so they sort alongside the direct hits; dictionary
+/// ranking in [`federate`] counts them as exact matches.
+fn with_lemma_hits(runtime: &RuntimeIndex, query: &str, hits: Vec<SearchHit>, limit: usize) -> Vec<SearchHit> {
+    let lemma_ids = lemma_entry_ids(runtime, query);
+    if lemma_ids.is_empty() {
+        return hits;
+    }
+    let score = hits.iter().map(|h| h.score).max().unwrap_or(1);
+    let mut out = lemma_ids
+        .iter()
+        .filter_map(|id| runtime.entries.by_id(*id))
+        .map(|e| SearchHit {
+            id: e.id(),
+            headword: e.headword().to_string(),
+            source_path: e.source_path().to_string(),
+            score,
+            snippet: e.definition_text().chars().take(180).collect::<String>(),
+            dictionary_id: String::new(),
+            dictionary_name: String::new(),
+            matched_via: Some("lemma"),
+        })
+        .collect::<Vec<_>>();
+    out.extend(hits.into_iter().filter(|h| !lemma_ids.contains(&h.id)));
+    out.truncate(limit);
+    out
+}
+
+/// Execute weighted search over headword/aliases/body.
+///
+/// Without `zip_path`, every enabled dictionary with a ready runtime is searched; `limit`
+/// applies per dictionary and hits stay grouped by dictionary, best group first.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails, or when every queried dictionary fails.
 pub(crate) fn search_entries_impl(
     app: &AppHandle,
     query: &str,
     limit: Option<usize>,
     zip_path: Option<String>,
-) -> Result<Vec<SearchHit>, String> {
+) -> Result<Vec<SearchHit>, DokhanError> {
     let q = compact_ws(query);
     if q.is_empty() {
         return Ok(Vec::new());
     }
-    let source = resolve_runtime_source(app, zip_path)?;
-    let runtime = get_runtime(app, &source)?;
     let limit = limit.unwrap_or(50).clamp(1, 200);
-    match search_entries_tantivy(app, &source, &q, limit, &runtime.entries) {
-        Ok(v) => Ok(v),
-        Err(_) => Ok(search_entries_linear(
-            &q,
-            limit,
-            &runtime.entries,
-            &runtime.entry_keys,
-        )),
+    federate(app, zip_path, |source, dictionary| {
+        let mut hits = search_source(app, source, &q, limit)?;
+        for hit in &mut hits {
+            hit.dictionary_id = dictionary.id.clone();
+            hit.dictionary_name = dictionary.name.clone();
+        }
+        let best = best_match_class(&q, &hits);
+        Ok((best, hits))
+    })
+}
+
+/// Dictionary a query runs against.
+struct QueryTarget {
+    id: String,
+    name: String,
+}
+
+/// Resolve the dictionaries a query covers: the given ZIP, or every enabled dictionary
+/// whose runtime can answer without a synchronous parse.
+///
+/// # Errors
+///
+/// Returns an error when `zip_path` is invalid, no dictionary has been added yet, or
+/// [`DokhanError::NotBuilt`] when none of the enabled dictionaries has been built.
+fn query_targets(
+    app: &AppHandle,
+    zip_path: Option<String>,
+) -> Result<Vec<(RuntimeSource, QueryTarget)>, DokhanError> {
+    if zip_path.is_some() {
+        let source = resolve_runtime_source(app, zip_path)?;
+        let target = QueryTarget {
+            id: dictionary_id_for_source(&source),
+            name: dictionary_name_for_source(app, &source),
+        };
+        return Ok(vec![(source, target)]);
+    }
+    let dictionaries = registered_dictionaries(app)?;
+    if dictionaries.is_empty() {
+        return Err(DokhanError::ZipPathRequired);
+    }
+    let mut out = Vec::new();
+    let mut unbuilt = Vec::new();
+    for d in dictionaries.into_iter().filter(|d| d.enabled) {
+        let source = RuntimeSource::ZipPath(d.zip_path);
+        if runtime_ready(app, &source)? {
+            out.push((source, QueryTarget { id: d.id, name: d.name }));
+        } else {
+            unbuilt.push(d.id);
+        }
+    }
+    if out.is_empty() && !unbuilt.is_empty() {
+        return Err(DokhanError::NotBuilt(unbuilt));
+    }
+    Ok(out)
+}
+
+/// Run `query` per dictionary and merge the groups; a failing dictionary is skipped
+/// unless every one fails.
+fn federate<T, F>(app: &AppHandle, zip_path: Option<String>, query: F) -> Result<Vec<T>, DokhanError>
+where
+    F: FnMut(&RuntimeSource, &QueryTarget) -> Result<(usize, Vec<T>), DokhanError>,
+{
+    federate_targets(query_targets(app, zip_path)?, query)
+}
+
+/// [`federate`] over already resolved targets.
+fn federate_targets<T, F>(targets: Vec<(RuntimeSource, QueryTarget)>, mut query: F) -> Result<Vec<T>, DokhanError>
+where
+    F: FnMut(&RuntimeSource, &QueryTarget) -> Result<(usize, Vec<T>), DokhanError>,
+{
+    let mut groups = Vec::new();
+    let mut first_err = None;
+    for (source, target) in targets {
+        match query(&source, &target) {
+            Ok(group) => groups.push(group),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
     }
+    if groups.is_empty() {
+        if let Some(err) = first_err {
+            return Err(err);
+        }
+    }
+    Ok(rank_dictionary_groups(groups))
+}
+
+/// Group rank of a dictionary whose best hit only matched elsewhere, e.g. in the body.
+const MATCH_OTHER: usize = 1;
+/// Group rank of a dictionary whose best hit starts with the query.
+const MATCH_PREFIX: usize = 2;
+/// Group rank of a dictionary whose best hit equals the query or is its lemma.
+const MATCH_EXACT: usize = 3;
+
+/// Strongest match class among one dictionary's hits, for [`rank_dictionary_groups`].
+///
+/// Raw hit scores depend on the backend that answered (Tantivy BM25, the linear scan, or
+/// `$FIftiMain` location counts), so groups are ranked by how the headword matched instead.
+fn best_match_class(query: &str, hits: &[SearchHit]) -> usize {
+    let q_key = normalize_search_key(query);
+    hits.iter()
+        .map(|hit| {
+            let key = normalize_search_key(&hit.headword);
+            if hit.matched_via == Some("lemma") || key == q_key {
+                MATCH_EXACT
+            } else if key.starts_with(&q_key) {
+                MATCH_PREFIX
+            } else {
+                MATCH_OTHER
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// [`best_match_class`] for reverse hits, matching the query against their Korean glosses.
+fn best_gloss_match_class(query: &str, hits: &[SearchHit], entries: &EntryTable) -> usize {
+    let q = query.replace(' ', "");
+    hits.iter()
+        .filter_map(|hit| entries.by_id(hit.id))
+        .map(|entry| {
+            entry
+                .gloss_keys()
+                .map(|(gloss, _, _)| {
+                    let gloss = gloss.replace(' ', "");
+                    if gloss == q {
+                        MATCH_EXACT
+                    } else if gloss.starts_with(&q) {
+                        MATCH_PREFIX
+                    } else {
+                        MATCH_OTHER
+                    }
+                })
+                .max()
+                .unwrap_or(MATCH_OTHER)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Order `(best match class, rows)` groups by class, keeping registry order on ties.
+fn rank_dictionary_groups<T>(mut groups: Vec<(usize, Vec<T>)>) -> Vec<T> {
+    groups.retain(|(_, rows)| !rows.is_empty());
+    groups.sort_by_key(|(best, _)| std::cmp::Reverse(*best));
+    groups.into_iter().flat_map(|(_, rows)| rows).collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_search_key;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::runtime::state::{build_runtime_index, loaded_runtime, set_search_pending};
+    use crate::runtime::zip::parse_runtime_from_zip_with_progress;
+
+    fn find_dataset_zip() -> Option<PathBuf> {
+        let cwd = std::env::current_dir().ok()?;
+        cwd.ancestors()
+            .map(|p| p.join("asset/dictionary_v77.zip"))
+            .find(|p| p.exists())
+    }
 
     #[test]
     fn normalize_search_key_handles_upper_umlaut() {
@@ -681,4 +1543,243 @@ mod tests {
         assert_eq!(normalize_search_key("Öl"), "oel");
         assert_eq!(normalize_search_key("Übung"), "uebung");
     }
+
+    #[test]
+    fn dictionary_groups_rank_by_best_score_then_registry_order() {
+        let groups = vec![
+            (3, vec!["a1", "a2"]),
+            (9, vec!["b1"]),
+            (0, Vec::new()),
+            (3, vec!["d1"]),
+        ];
+        assert_eq!(rank_dictionary_groups(groups), ["b1", "a1", "a2", "d1"]);
+    }
+
+    #[test]
+    fn dictionary_groups_rank_by_match_class_across_backends() {
+        let hit = |headword: &str, score: usize, matched_via: Option<&'static str>| SearchHit {
+            id: 1,
+            headword: headword.to_string(),
+            source_path: "merge01.chm".to_string(),
+            score,
+            snippet: String::new(),
+            dictionary_id: String::new(),
+            dictionary_name: String::new(),
+            matched_via,
+        };
+        // `$FIftiMain` location counts, linear-scan points and BM25 scores on different scales.
+        let fulltext = vec![hit("Hausbau", 340, None), hit("Gebäude", 120, None)];
+        let scan = vec![hit("Gebäude", 90, None)];
+        let tantivy = vec![hit("Haus", 7, None)];
+        let lemma = vec![hit("Haus", 1, Some("lemma"))];
+        let groups = [("fulltext", fulltext), ("scan", scan), ("tantivy", tantivy), ("lemma", lemma)]
+            .into_iter()
+            .map(|(name, hits)| (best_match_class("haus", &hits), vec![name]))
+            .collect::<Vec<_>>();
+        assert_eq!(rank_dictionary_groups(groups), ["tantivy", "lemma", "fulltext", "scan"]);
+    }
+
+    #[test]
+    fn suggestions_score_transpositions_and_stacked_spelling_variants() {
+        let entry = |id: usize, headword: &str| EntryDetail {
+            id,
+            headword: headword.to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: String::new(),
+            definition_html: String::new(),
+            encoding: "EUC-KR".to_string(),
+        };
+        let entries = EntryTable::owned(vec![entry(1, "Haus"), entry(2, "Häuserstraße"), entry(3, "Hand")]);
+        let headwords = Arc::new(HeadwordIndex::build(&entries).expect("headwords"));
+        let runtime = build_runtime_index(Vec::new(), entries, headwords, None);
+        let dictionary = QueryTarget {
+            id: "dict".to_string(),
+            name: "dict".to_string(),
+        };
+        let suggest = |query: &str| {
+            suggest_headwords_in(&runtime, &dictionary, query)
+                .expect("suggest")
+                .into_iter()
+                .map(|(cost, s)| (s.headword, cost))
+                .collect::<Vec<_>>()
+        };
+
+        // A transposition is two plain edits, beyond the one allowed for short queries.
+        assert_eq!(suggest("Hasu"), [("Haus".to_string(), EDIT_COST)]);
+        // Three missing letters, two of them spelling variants, still cost less than two typos.
+        assert_eq!(suggest("Hausrstrase"), [("Häuserstraße".to_string(), EDIT_COST + 6)]);
+    }
+
+    #[test]
+    fn federated_lookup_serves_a_runtime_still_being_indexed() {
+        let entries = EntryTable::owned(vec![EntryDetail {
+            id: 1,
+            headword: "Haus".to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: "집".to_string(),
+            definition_html: String::new(),
+            encoding: "EUC-KR".to_string(),
+        }]);
+        let headwords = Arc::new(HeadwordIndex::build(&entries).expect("headwords"));
+        let runtime = Arc::new(build_runtime_index(Vec::new(), entries, headwords, None));
+        let zip = |name: &str| {
+            RuntimeSource::ZipPath(std::env::temp_dir().join(format!("dokhan-{name}-{}.zip", std::process::id())))
+        };
+        let (building, unloaded) = (zip("building"), zip("unloaded"));
+        set_search_pending(&building, Some(runtime)).expect("publish pending runtime");
+
+        let target = |id: &str| QueryTarget {
+            id: id.to_string(),
+            name: id.to_string(),
+        };
+        let targets = vec![(building.clone(), target("building")), (unloaded, target("unloaded"))];
+        let rows = federate_targets(targets, |source, dictionary| {
+            let runtime = loaded_runtime(source)?.ok_or_else(|| DokhanError::not_found("runtime", &dictionary.id))?;
+            let rows = lookup_headwords_in(&runtime, dictionary, "hau", HeadwordLookupMode::Prefix, None, 10)?;
+            Ok((0, rows))
+        });
+        set_search_pending(&building, None).expect("withdraw pending runtime");
+        let rows = rows.expect("federated lookup");
+        assert_eq!(
+            rows.iter().map(|r| (r.id, r.dictionary_id.as_str())).collect::<Vec<_>>(),
+            [(1, "building")]
+        );
+    }
+
+    #[test]
+    fn cancelled_rebuild_removes_partial_index() {
+        let entries = (0..3)
+            .map(|id| EntryDetail {
+                id,
+                headword: format!("Haus{id}"),
+                aliases: Vec::new(),
+                source_path: "merge01.chm".to_string(),
+                target_local: String::new(),
+                definition_text: "집".to_string(),
+                definition_html: String::new(),
+                encoding: "utf-8".to_string(),
+            })
+            .collect::<Vec<_>>();
+        let entries = EntryTable::owned(entries);
+        let dir = std::env::temp_dir().join(format!("dokhan-cancel-index-{}", std::process::id()));
+        let token = CancelToken::new();
+        token.cancel();
+        let err = rebuild_search_index(&dir, &entries, Some(&token)).err().expect("cancelled");
+        assert!(matches!(err, crate::error::DokhanError::Cancelled));
+        assert!(!dir.exists());
+
+        let idx = rebuild_search_index(&dir, &entries, Some(&CancelToken::new())).expect("build index");
+        assert_eq!(idx.reader.searcher().num_docs(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn korean_body_search_matches_inflected_and_compound_words() {
+        let entry = |id: usize, headword: &str, alias: &str, body: &str| EntryDetail {
+            id,
+            headword: headword.to_string(),
+            aliases: vec![alias.to_string()],
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: body.to_string(),
+            definition_html: String::new(),
+            encoding: "utf-8".to_string(),
+        };
+        let entries = EntryTable::owned(vec![
+            entry(1, "Apfel", "Äpfel", "사과를 먹다"),
+            entry(2, "Apfelbaum", "", "사과나무"),
+            entry(3, "Birne", "", "배, 배나무"),
+            entry(4, "Straße", "Strasse", "거리, 도로"),
+        ]);
+        let dir = std::env::temp_dir().join(format!("dokhan-korean-index-{}", std::process::id()));
+        let idx = rebuild_search_index(&dir, &entries, None).expect("build index");
+        let ids = |query: &str| {
+            let mut ids = search_tantivy_index(&idx, query, 10, &entries)
+                .expect("search")
+                .into_iter()
+                .map(|h| h.id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(ids("사과"), [1, 2]);
+        assert_eq!(ids("사과를"), [1, 2]);
+        assert_eq!(ids("사과나무"), [2]);
+        assert_eq!(ids("배"), [3]);
+        assert_eq!(ids("aepfel"), [1]);
+        drop(idx);
+        let reopened = load_search_index(&dir).expect("reopen index");
+        assert_eq!(search_tantivy_index(&reopened, "도로", 10, &entries).expect("search").len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reverse_search_ranks_exact_and_leading_glosses_first() {
+        let entry = |id: usize, headword: &str, body: &str| EntryDetail {
+            id,
+            headword: headword.to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: body.to_string(),
+            definition_html: String::new(),
+            encoding: "utf-8".to_string(),
+        };
+        let entries = EntryTable::owned(vec![
+            entry(1, "Apfelbaum", "m. 사과나무"),
+            entry(2, "Entschuldigung", "f. 용서; 사과"),
+            entry(3, "Apfel", "m. ① 사과 ② 둥근 것"),
+            entry(4, "Birne", "f. 배; 배나무; 서양배. Er isst einen Apfel. 사과"),
+        ]);
+        let dir = std::env::temp_dir().join(format!("dokhan-reverse-index-{}", std::process::id()));
+        let idx = rebuild_search_index(&dir, &entries, None).expect("build index");
+        let ids = |query: &str| {
+            search_reverse_index(&idx, query, 10, &entries)
+                .expect("reverse search")
+                .into_iter()
+                .map(|h| h.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("사과"), [3, 2, 4, 1]);
+        assert_eq!(ids("사과를"), [3, 2, 4, 1]);
+        assert_eq!(ids("둥근 것"), [3]);
+        assert!(search_reverse_index(&idx, "(사과 -\"용서\": ", 10, &entries).is_ok());
+        let headwords = HeadwordIndex::build(&entries).expect("headwords");
+        let jamo_ids = |query: &str| {
+            search_reverse_jamo(query, 10, &entries, &headwords)
+                .into_iter()
+                .map(|h| h.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(jamo_ids("ㅅㄱ"), [3, 2, 4, 1]);
+        assert_eq!(jamo_ids("삭"), [3, 1, 2, 4]);
+        assert_eq!(jamo_ids("사ㄱ"), jamo_ids("삭"));
+        assert_eq!(jamo_ids("ㄷㄱ"), [3]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[ignore = "needs asset/dictionary_v77.zip"]
+    fn fulltext_hits_overlap_tantivy_results() {
+        let zip_path = find_dataset_zip().expect("asset/dictionary_v77.zip");
+        let runtime = parse_runtime_from_zip_with_progress(&zip_path, None, None, None).expect("parse runtime");
+        let source = RuntimeSource::ZipPath(zip_path);
+        let handles = PendingFullText::open(&source, &runtime).expect("open fulltext indexes");
+        let fulltext = search_entries_fulltext(&handles, "Haus", 50, &runtime);
+        assert!(!fulltext.is_empty(), "dataset has no $FIftiMain hits for Haus");
+
+        let dir = std::env::temp_dir().join(format!("dokhan-fts-crosscheck-{}", std::process::id()));
+        let idx = rebuild_search_index(&dir, &runtime.entries, None).expect("build tantivy index");
+        let tantivy = search_tantivy_index(&idx, "Haus", 200, &runtime.entries).expect("tantivy search");
+        let _ = fs::remove_dir_all(&dir);
+        let tantivy_ids = tantivy.iter().map(|h| h.id).collect::<BTreeSet<_>>();
+        assert!(
+            fulltext.iter().any(|h| tantivy_ids.contains(&h.id)),
+            "no overlap between $FIftiMain and tantivy hits"
+        );
+    }
 }
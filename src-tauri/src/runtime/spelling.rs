@@ -0,0 +1,66 @@
+//! German-aware weighted edit distance for spelling suggestions.
+//!
+//! Inputs are expected in [`normalize_search_key`](crate::runtime::search::normalize_search_key)
+//! form, where `ä`/`ö`/`ü` are spelled `ae`/`oe`/`ue` and `ß` is `ss`. Dropping or adding the
+//! `e` of such a digraph, or one `s` of a double `s`, is a spelling variant rather than a typo and
+//! costs less than an ordinary edit.
+
+/// Cost of one ordinary insertion, deletion, substitution, or adjacent transposition.
+pub(crate) const EDIT_COST: u32 = 10;
+/// Cost of inserting or deleting the `e` of an umlaut digraph or one `s` of `ss`.
+pub(crate) const SPELLING_VARIANT_COST: u32 = 3;
+
+/// Cost of inserting or deleting `chars[i]` given its neighbours in the same word.
+fn indel_cost(chars: &[char], i: usize) -> u32 {
+    let prev = i.checked_sub(1).map(|p| chars[p]);
+    let next = chars.get(i + 1).copied();
+    let umlaut_e = chars[i] == 'e' && matches!(prev, Some('a' | 'o' | 'u'));
+    let double_s = chars[i] == 's' && (prev == Some('s') || next == Some('s'));
+    if umlaut_e || double_s {
+        SPELLING_VARIANT_COST
+    } else {
+        EDIT_COST
+    }
+}
+
+/// Weighted restricted Damerau–Levenshtein distance between two normalized keys.
+pub(crate) fn german_edit_distance(a: &str, b: &str) -> u32 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let width = b.len() + 1;
+    let mut d = vec![0u32; (a.len() + 1) * width];
+    for j in 1..=b.len() {
+        d[j] = d[j - 1] + indel_cost(&b, j - 1);
+    }
+    for i in 1..=a.len() {
+        d[i * width] = d[(i - 1) * width] + indel_cost(&a, i - 1);
+        for j in 1..=b.len() {
+            let substitution = if a[i - 1] == b[j - 1] { 0 } else { EDIT_COST };
+            let mut best = (d[(i - 1) * width + j] + indel_cost(&a, i - 1))
+                .min(d[i * width + j - 1] + indel_cost(&b, j - 1))
+                .min(d[(i - 1) * width + j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[(i - 2) * width + j - 2] + EDIT_COST);
+            }
+            d[i * width + j] = best;
+        }
+    }
+    d[a.len() * width + b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spelling_variants_cost_less_than_typos() {
+        assert_eq!(german_edit_distance("haus", "haus"), 0);
+        assert_eq!(german_edit_distance("stasse", "strasse"), EDIT_COST);
+        assert_eq!(german_edit_distance("hasu", "haus"), EDIT_COST);
+        assert_eq!(german_edit_distance("strase", "strasse"), SPELLING_VARIANT_COST);
+        assert_eq!(german_edit_distance("hauser", "haeuser"), SPELLING_VARIANT_COST);
+        assert_eq!(german_edit_distance("haeuser", "hauser"), SPELLING_VARIANT_COST);
+        assert_eq!(german_edit_distance("", "ab"), 2 * EDIT_COST);
+        assert!(german_edit_distance("hauser", "heuser") > german_edit_distance("hauser", "haeuser"));
+    }
+}
@@ -1,22 +1,44 @@
 //! Runtime cache/state management and async build lifecycle.
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
 
 use tauri::AppHandle;
 
 use crate::app::model::{
-    BuildProgress, BuildStatus, ContentItem, ContentPage, EntryDetail, MasterFeatureSummary,
-    RuntimeIndex, RuntimeSource,
+    BuildProgress, BuildStatus, ContentItem, ContentPage, DictionaryBuildState, EntryDetail, MasterFeatureSummary,
+    RuntimeCacheReport, RuntimeIndex, RuntimeSource,
 };
+use crate::error::DokhanError;
 use crate::resolve_runtime_source;
-use crate::runtime::search::{build_entry_search_keys, warm_search_index};
-use crate::runtime::storage::{load_runtime_cache, save_runtime_cache, PersistedRuntime};
+use crate::runtime::cancel::{check_cancelled, CancelToken};
+use crate::runtime::entries::{EntryRef, EntryTable};
+use crate::runtime::events::{emit_build_status, next_build_seq};
+use crate::runtime::headwords::HeadwordIndex;
+use crate::runtime::registry::touch_dictionary;
+use crate::runtime::search::{check_search_index, warm_search_index, PendingFullText};
+use crate::runtime::pages::PageStore;
+use crate::runtime::storage::{
+    discard_partial_runtime_cache, has_runtime_cache, load_runtime_cache, page_store_path, save_runtime_cache,
+    verify_runtime_cache, CacheLoad, PersistedRuntime,
+};
 use crate::runtime::zip::{
-    hydrate_zip_entry_detail, parse_runtime_from_zip_with_progress, read_content_page_from_zip,
+    hydrate_entry_detail, parse_runtime_from_zip_with_progress, read_content_page_from_zip,
 };
 
 static RUNTIME_CACHE: OnceLock<Mutex<BTreeMap<String, Arc<RuntimeIndex>>>> = OnceLock::new();
 static BUILD_STATUS: OnceLock<Mutex<BTreeMap<String, BuildStatus>>> = OnceLock::new();
+static SEARCH_PENDING: OnceLock<Mutex<BTreeMap<String, Arc<PendingRuntime>>>> = OnceLock::new();
+static BUILD_CANCEL: OnceLock<Mutex<BTreeMap<String, CancelToken>>> = OnceLock::new();
+static BUILD_WORKERS: OnceLock<Mutex<BTreeMap<String, JoinHandle<()>>>> = OnceLock::new();
+
+/// Runtime parsed by a background build whose Tantivy index is not ready yet.
+pub(crate) struct PendingRuntime {
+    pub(crate) runtime: Arc<RuntimeIndex>,
+    /// CHM full-text handles, opened by the first search and kept until the build finishes;
+    /// `None` when they could not be opened.
+    pub(crate) fulltext: OnceLock<Option<PendingFullText>>,
+}
 
 /// Build a stable cache key for a runtime source.
 fn cache_key(source: &RuntimeSource) -> String {
@@ -42,31 +64,39 @@ fn status_key(source: &RuntimeSource) -> String {
     cache_key(source)
 }
 
-/// Insert or replace build status entry.
+/// Insert or replace build status entry and emit the matching build event.
+///
+/// The event is emitted under the status lock so events leave in `seq` order.
 ///
 /// # Errors
 ///
 /// Returns an error when the build status mutex is poisoned.
-fn set_build_status(key: &str, status: BuildStatus) -> Result<(), String> {
+fn set_build_status(app: &AppHandle, key: &str, mut status: BuildStatus) -> Result<(), DokhanError> {
     let map = BUILD_STATUS.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let mut guard = map.lock().map_err(|_| "build status lock poisoned".to_string())?;
+    let mut guard = map.lock().map_err(|_| DokhanError::LockPoisoned("build status"))?;
+    status.seq = next_build_seq();
+    emit_build_status(app, key, &status);
     guard.insert(key.to_string(), status);
     Ok(())
 }
 
-/// Update an existing build status entry in place.
+/// Update an existing build status entry in place and emit the matching build event.
+///
+/// Like [`set_build_status`], the event is emitted before the status lock is released.
 ///
 /// # Errors
 ///
 /// Returns an error when the build status mutex is poisoned.
-fn update_build_status<F>(key: &str, updater: F) -> Result<(), String>
+fn update_build_status<F>(app: &AppHandle, key: &str, updater: F) -> Result<(), DokhanError>
 where
     F: FnOnce(&mut BuildStatus),
 {
     let map = BUILD_STATUS.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let mut guard = map.lock().map_err(|_| "build status lock poisoned".to_string())?;
+    let mut guard = map.lock().map_err(|_| DokhanError::LockPoisoned("build status"))?;
     if let Some(st) = guard.get_mut(key) {
         updater(st);
+        st.seq = next_build_seq();
+        emit_build_status(app, key, st);
     }
     Ok(())
 }
@@ -76,9 +106,9 @@ where
 /// # Errors
 ///
 /// Returns an error when the build status mutex is poisoned.
-fn get_build_status_internal(key: &str) -> Result<Option<BuildStatus>, String> {
+fn get_build_status_internal(key: &str) -> Result<Option<BuildStatus>, DokhanError> {
     let map = BUILD_STATUS.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let guard = map.lock().map_err(|_| "build status lock poisoned".to_string())?;
+    let guard = map.lock().map_err(|_| DokhanError::LockPoisoned("build status"))?;
     Ok(guard.get(key).cloned())
 }
 
@@ -87,10 +117,10 @@ fn get_build_status_internal(key: &str) -> Result<Option<BuildStatus>, String> {
 /// # Errors
 ///
 /// Returns an error when the runtime cache mutex is poisoned.
-fn cache_get(source: &RuntimeSource) -> Result<Option<Arc<RuntimeIndex>>, String> {
+fn cache_get(source: &RuntimeSource) -> Result<Option<Arc<RuntimeIndex>>, DokhanError> {
     let key = cache_key(source);
     let cache = RUNTIME_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let guard = cache.lock().map_err(|_| "runtime cache lock poisoned".to_string())?;
+    let guard = cache.lock().map_err(|_| DokhanError::LockPoisoned("runtime cache"))?;
     Ok(guard.get(&key).cloned())
 }
 
@@ -99,14 +129,125 @@ fn cache_get(source: &RuntimeSource) -> Result<Option<Arc<RuntimeIndex>>, String
 /// # Errors
 ///
 /// Returns an error when the runtime cache mutex is poisoned.
-fn cache_put(source: &RuntimeSource, runtime: Arc<RuntimeIndex>) -> Result<(), String> {
+fn cache_put(source: &RuntimeSource, runtime: Arc<RuntimeIndex>) -> Result<(), DokhanError> {
     let key = cache_key(source);
     let cache = RUNTIME_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let mut guard = cache.lock().map_err(|_| "runtime cache lock poisoned".to_string())?;
+    let mut guard = cache.lock().map_err(|_| DokhanError::LockPoisoned("runtime cache"))?;
     guard.insert(key, runtime);
     Ok(())
 }
 
+/// Publish or withdraw a parsed runtime whose search index is still being built.
+///
+/// # Errors
+///
+/// Returns an error when the pending runtime mutex is poisoned.
+pub(crate) fn set_search_pending(
+    source: &RuntimeSource,
+    runtime: Option<Arc<RuntimeIndex>>,
+) -> Result<(), DokhanError> {
+    let key = cache_key(source);
+    let pending = SEARCH_PENDING.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let mut guard = pending
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("pending runtime"))?;
+    match runtime {
+        Some(runtime) => guard.insert(
+            key,
+            Arc::new(PendingRuntime {
+                runtime,
+                fulltext: OnceLock::new(),
+            }),
+        ),
+        None => guard.remove(&key),
+    };
+    Ok(())
+}
+
+/// Return a runtime that a background build has parsed but not yet search-indexed.
+///
+/// # Errors
+///
+/// Returns an error when the pending runtime mutex is poisoned.
+pub(crate) fn get_search_pending_runtime(source: &RuntimeSource) -> Result<Option<Arc<PendingRuntime>>, DokhanError> {
+    let key = cache_key(source);
+    let pending = SEARCH_PENDING.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let guard = pending
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("pending runtime"))?;
+    Ok(guard.get(&key).cloned())
+}
+
+/// Runtime already in memory: cached, or parsed by a build that is still search-indexing.
+///
+/// # Errors
+///
+/// Returns an error when a runtime mutex is poisoned.
+pub(crate) fn loaded_runtime(source: &RuntimeSource) -> Result<Option<Arc<RuntimeIndex>>, DokhanError> {
+    if let Some(runtime) = cache_get(source)? {
+        return Ok(Some(runtime));
+    }
+    Ok(get_search_pending_runtime(source)?.map(|pending| pending.runtime.clone()))
+}
+
+/// Whether `source` can answer queries without a synchronous parse.
+///
+/// # Errors
+///
+/// Returns an error when a runtime mutex is poisoned or the cache directory cannot be resolved.
+pub(crate) fn runtime_ready(app: &AppHandle, source: &RuntimeSource) -> Result<bool, DokhanError> {
+    Ok(loaded_runtime(source)?.is_some() || has_runtime_cache(app, source)?)
+}
+
+/// Coarse build state for library listings.
+///
+/// # Errors
+///
+/// Returns an error when a runtime mutex is poisoned or the cache directory cannot be resolved.
+pub(crate) fn dictionary_build_state(
+    app: &AppHandle,
+    source: &RuntimeSource,
+) -> Result<DictionaryBuildState, DokhanError> {
+    let status = get_build_status_internal(&status_key(source))?;
+    if status.as_ref().is_some_and(|st| !st.done) {
+        return Ok(DictionaryBuildState::Building);
+    }
+    if cache_get(source)?.is_some() {
+        return Ok(DictionaryBuildState::Ready);
+    }
+    if let Some(st) = status.filter(|st| !st.success && st.phase != "idle") {
+        return Ok(if st.phase == "cancelled" {
+            DictionaryBuildState::Cancelled
+        } else {
+            DictionaryBuildState::Error
+        });
+    }
+    if has_runtime_cache(app, source)? {
+        return Ok(DictionaryBuildState::Cached);
+    }
+    Ok(DictionaryBuildState::Idle)
+}
+
+/// Drop every in-memory runtime, pending index, and build status held for `source`.
+///
+/// # Errors
+///
+/// Returns an error when a runtime mutex is poisoned.
+pub(crate) fn evict_runtime(source: &RuntimeSource) -> Result<(), DokhanError> {
+    let key = cache_key(source);
+    let cache = RUNTIME_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
+    cache
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("runtime cache"))?
+        .remove(&key);
+    set_search_pending(source, None)?;
+    let map = BUILD_STATUS.get_or_init(|| Mutex::new(BTreeMap::new()));
+    map.lock()
+        .map_err(|_| DokhanError::LockPoisoned("build status"))?
+        .remove(&status_key(source));
+    Ok(())
+}
+
 /// Convert runtime snapshot to API summary payload.
 fn summary_from_runtime(source: &RuntimeSource, runtime: &RuntimeIndex) -> MasterFeatureSummary {
     MasterFeatureSummary {
@@ -122,11 +263,13 @@ fn summary_from_runtime(source: &RuntimeSource, runtime: &RuntimeIndex) -> Maste
 ///
 /// Returns an error when status storage is unavailable.
 fn set_build_done_status(
+    app: &AppHandle,
     key: &str,
     summary: MasterFeatureSummary,
     message: &str,
-) -> Result<(), String> {
+) -> Result<(), DokhanError> {
     set_build_status(
+        app,
         key,
         BuildStatus {
             phase: "done".to_string(),
@@ -137,6 +280,7 @@ fn set_build_done_status(
             success: true,
             error: None,
             summary: Some(summary),
+            seq: 0,
         },
     )
 }
@@ -146,8 +290,14 @@ fn set_build_done_status(
 /// # Errors
 ///
 /// Returns an error when status storage is unavailable.
-fn set_build_error_status(key: &str, message: &str, error: String) -> Result<(), String> {
+fn set_build_error_status(
+    app: &AppHandle,
+    key: &str,
+    message: &str,
+    error: DokhanError,
+) -> Result<(), DokhanError> {
     set_build_status(
+        app,
         key,
         BuildStatus {
             phase: "error".to_string(),
@@ -156,130 +306,291 @@ fn set_build_error_status(key: &str, message: &str, error: String) -> Result<(),
             message: message.to_string(),
             done: true,
             success: false,
-            error: Some(error),
+            error: Some(error.payload()),
             summary: None,
+            seq: 0,
         },
     )
 }
 
+/// Update a running build's status unless it has been cancelled.
+///
+/// A cancelled worker may still be unwinding while a restarted build owns the same key.
+fn update_build_progress<F>(app: &AppHandle, key: &str, cancel: &CancelToken, updater: F)
+where
+    F: FnOnce(&mut BuildStatus),
+{
+    if !cancel.is_cancelled() {
+        let _ = update_build_status(app, key, updater);
+    }
+}
+
+/// Store terminal status for a build stopped by `cancel_master_build`.
+///
+/// # Errors
+///
+/// Returns an error when status storage is unavailable.
+fn set_build_cancelled_status(app: &AppHandle, key: &str) -> Result<(), DokhanError> {
+    set_build_status(
+        app,
+        key,
+        BuildStatus {
+            phase: "cancelled".to_string(),
+            current: 0,
+            total: 1,
+            message: "Build cancelled".to_string(),
+            done: true,
+            success: false,
+            error: Some(DokhanError::Cancelled.payload()),
+            summary: None,
+            seq: 0,
+        },
+    )
+}
+
+/// Register a fresh cancellation token for `key`, replacing any previous one.
+///
+/// # Errors
+///
+/// Returns an error when the cancel token mutex is poisoned.
+fn register_build_token(key: &str) -> Result<CancelToken, DokhanError> {
+    let tokens = BUILD_CANCEL.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let mut guard = tokens.lock().map_err(|_| DokhanError::LockPoisoned("build cancel"))?;
+    let token = CancelToken::new();
+    guard.insert(key.to_string(), token.clone());
+    Ok(token)
+}
+
+/// Drop `token` from the registry; returns whether it still owned `key`.
+fn release_build_token(key: &str, token: &CancelToken) -> bool {
+    let tokens = BUILD_CANCEL.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let Ok(mut guard) = tokens.lock() else {
+        return false;
+    };
+    match guard.get(key) {
+        Some(current) if current.same_as(token) => {
+            guard.remove(key);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Whether the build registered for `key` has been asked to stop.
+///
+/// # Errors
+///
+/// Returns an error when the cancel token mutex is poisoned.
+fn build_cancel_requested(key: &str) -> Result<bool, DokhanError> {
+    let tokens = BUILD_CANCEL.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let guard = tokens.lock().map_err(|_| DokhanError::LockPoisoned("build cancel"))?;
+    Ok(guard.get(key).is_some_and(CancelToken::is_cancelled))
+}
+
 /// Build runtime for a source while streaming progress updates.
 ///
 /// # Errors
 ///
-/// Returns an error when CHM/ZIP parsing fails or status updates cannot be persisted.
+/// Returns an error when CHM/ZIP parsing fails or status updates cannot be persisted,
+/// or [`DokhanError::Cancelled`] once `cancel` fires.
 fn build_runtime_for_source(
     app: &AppHandle,
     source: &RuntimeSource,
     key: &str,
-) -> Result<Arc<RuntimeIndex>, String> {
-    if let Some(persisted) = load_runtime_cache(app, source)? {
-        let runtime = Arc::new(build_runtime_index(
-            persisted.contents,
-            persisted.entries,
-            BTreeMap::new(),
-        ));
-        let _ = update_build_status(key, |st| {
+    cancel: &CancelToken,
+) -> Result<Arc<RuntimeIndex>, DokhanError> {
+    match load_runtime_cache(app, source)? {
+        CacheLoad::Loaded(persisted) => {
+            let runtime = Arc::new(build_runtime_index(
+                persisted.contents,
+                persisted.entries,
+                persisted.headwords,
+                persisted.pages,
+            ));
+            update_build_progress(app, key, cancel, |st| {
+                st.phase = "cache".to_string();
+                st.message = "Loaded runtime cache".to_string();
+            });
+            set_search_pending(source, Some(runtime.clone()))?;
+            warm_search_index(app, source, &runtime.entries, Some(cancel))?;
+            return Ok(runtime);
+        }
+        CacheLoad::Rejected(report) => update_build_progress(app, key, cancel, |st| {
             st.phase = "cache".to_string();
-            st.message = "Loaded runtime cache".to_string();
-        });
-        warm_search_index(app, source, &runtime.entries)?;
-        return Ok(runtime);
+            st.message = format!(
+                "Runtime cache {} ({}); rebuilding",
+                report.status,
+                report.problems.join("; ")
+            );
+        }),
+        CacheLoad::Stale(persisted, report) => {
+            // Queries are served from the stale cache until the rebuilt runtime replaces it.
+            let stale = Arc::new(build_runtime_index(
+                persisted.contents,
+                persisted.entries,
+                persisted.headwords,
+                persisted.pages,
+            ));
+            set_search_pending(source, Some(stale))?;
+            update_build_progress(app, key, cancel, |st| {
+                st.phase = "cache".to_string();
+                st.message = format!("Runtime cache {} ({}); rebuilding", report.status, report.problems.join("; "));
+            });
+        }
+        CacheLoad::Missing => {}
     }
 
     let runtime = match source {
         RuntimeSource::ZipPath(zip_path) => {
             let mut cb = |p: BuildProgress| {
-                let _ = update_build_status(key, |st| {
+                update_build_progress(app, key, cancel, |st| {
                     st.phase = p.phase;
                     st.current = p.current;
                     st.total = p.total;
                     st.message = p.message;
                 });
             };
-            parse_runtime_from_zip_with_progress(zip_path, Some(&mut cb)).map(Arc::new)?
+            let pages = page_store_path(app, source)?;
+            parse_runtime_from_zip_with_progress(zip_path, Some(&mut cb), Some(cancel), Some(&pages)).map(Arc::new)?
         }
     };
 
-    let _ = update_build_status(key, |st| {
+    update_build_progress(app, key, cancel, |st| {
         st.phase = "search-index".to_string();
         st.message = "Building search index".to_string();
     });
-    warm_search_index(app, source, &runtime.entries)?;
+    // Searches use the CHM full-text indexes until Tantivy is ready.
+    set_search_pending(source, Some(runtime.clone()))?;
+    warm_search_index(app, source, &runtime.entries, Some(cancel))?;
     let _ = save_runtime_cache(
         app,
         source,
         &PersistedRuntime {
             contents: runtime.contents.clone(),
             entries: runtime.entries.clone(),
+            headwords: runtime.headwords.clone(),
+            pages: runtime.pages.clone(),
         },
     );
     Ok(runtime)
 }
 
-/// Spawn detached worker that builds runtime and updates status.
-fn spawn_build_worker(app: AppHandle, source: RuntimeSource, key: String) {
-    std::thread::spawn(move || {
-        let runtime = match build_runtime_for_source(&app, &source, &key) {
+/// Spawn a worker that builds runtime and updates status.
+///
+/// A worker still running for `key` has been cancelled; the new one waits for it to exit,
+/// since both would share the cache dir, page store staging file, Tantivy dir and pending
+/// runtime slot that the old worker cleans up while unwinding. Terminal status is only
+/// written while `cancel` is still the key's registered token, so a cancelled worker never
+/// overwrites a restarted build.
+///
+/// # Errors
+///
+/// Returns an error when the worker registry mutex is poisoned.
+fn spawn_build_worker(app: AppHandle, source: RuntimeSource, key: String, cancel: CancelToken) -> Result<(), DokhanError> {
+    let workers = BUILD_WORKERS.get_or_init(|| Mutex::new(BTreeMap::new()));
+    let mut guard = workers.lock().map_err(|_| DokhanError::LockPoisoned("build workers"))?;
+    let previous = guard.remove(&key);
+    let slot = key.clone();
+    let handle = std::thread::spawn(move || {
+        if let Some(previous) = previous {
+            let _ = previous.join();
+        }
+        let built = build_runtime_for_source(&app, &source, &key, &cancel)
+            .and_then(|runtime| check_cancelled(Some(&cancel)).map(|_| runtime));
+        let runtime = match built {
             Ok(runtime) => runtime,
             Err(err) => {
-                let _ = set_build_error_status(&key, "Failed parsing zip/chm", err);
+                let _ = set_search_pending(&source, None);
+                let cancelled = matches!(err, DokhanError::Cancelled);
+                if cancelled {
+                    let _ = discard_partial_runtime_cache(&app, &source);
+                }
+                if release_build_token(&key, &cancel) {
+                    let _ = if cancelled {
+                        set_build_cancelled_status(&app, &key)
+                    } else {
+                        set_build_error_status(&app, &key, "Failed parsing zip/chm", err)
+                    };
+                }
                 return;
             }
         };
+        if !release_build_token(&key, &cancel) {
+            return;
+        }
 
         let summary = summary_from_runtime(&source, &runtime);
-        if let Err(err) = cache_put(&source, runtime) {
-            let _ = set_build_error_status(&key, "Cache write failed", err);
+        let cached = cache_put(&source, runtime);
+        // Withdraw only after the cache holds the runtime so searches never fall into a sync rebuild.
+        let _ = set_search_pending(&source, None);
+        if let Err(err) = cached {
+            let _ = set_build_error_status(&app, &key, "Cache write failed", err);
             return;
         }
-        let _ = set_build_done_status(&key, summary, "Build complete");
+        let _ = set_build_done_status(&app, &key, summary, "Build complete");
     });
+    guard.insert(slot, handle);
+    Ok(())
 }
 
-/// Build immutable runtime index with precomputed search keys.
+/// Build immutable runtime index from parsed or mapped parts.
 pub(crate) fn build_runtime_index(
     contents: Vec<ContentItem>,
-    entries: Vec<EntryDetail>,
-    content_pages: BTreeMap<String, ContentPage>,
+    entries: EntryTable,
+    headwords: Arc<HeadwordIndex>,
+    pages: Option<Arc<PageStore>>,
 ) -> RuntimeIndex {
-    let entry_keys = build_entry_search_keys(&entries);
     RuntimeIndex {
         contents,
         entries,
-        content_pages,
-        entry_keys,
+        headwords,
+        pages,
     }
 }
 
-/// Return cached runtime or build it synchronously.
+/// Return the cached or pending runtime, or build it synchronously.
+///
+/// A runtime still being search-indexed by a background build is served as is, so queries
+/// never parse the ZIP or write the cache alongside the build worker.
 ///
 /// # Errors
 ///
-/// Returns an error when parsing fails or cache/status storage is unavailable.
-pub(crate) fn get_runtime(app: &AppHandle, source: &RuntimeSource) -> Result<Arc<RuntimeIndex>, String> {
-    if let Some(v) = cache_get(source)? {
+/// Returns [`DokhanError::SourceUnavailable`] while a build is still parsing `source`, or an
+/// error when parsing fails or cache/status storage is unavailable.
+pub(crate) fn get_runtime(app: &AppHandle, source: &RuntimeSource) -> Result<Arc<RuntimeIndex>, DokhanError> {
+    if let Some(v) = loaded_runtime(source)? {
         return Ok(v);
     }
-    if let Some(persisted) = load_runtime_cache(app, source)? {
+    if let CacheLoad::Loaded(persisted) = load_runtime_cache(app, source)? {
         let runtime = Arc::new(build_runtime_index(
             persisted.contents,
             persisted.entries,
-            BTreeMap::new(),
+            persisted.headwords,
+            persisted.pages,
         ));
-        warm_search_index(app, source, &runtime.entries)?;
+        warm_search_index(app, source, &runtime.entries, None)?;
         cache_put(source, runtime.clone())?;
         return Ok(runtime);
     }
+    // A build still parsing owns the page store and cache directory until it publishes.
+    if get_build_status_internal(&status_key(source))?.is_some_and(|st| !st.done) {
+        return Err(DokhanError::SourceUnavailable("dictionary is still building".to_string()));
+    }
     let runtime = match source {
-        RuntimeSource::ZipPath(zip_path) => Arc::new(parse_runtime_from_zip_with_progress(zip_path, None)?),
+        RuntimeSource::ZipPath(zip_path) => {
+            let pages = page_store_path(app, source)?;
+            Arc::new(parse_runtime_from_zip_with_progress(zip_path, None, None, Some(&pages))?)
+        }
     };
-    warm_search_index(app, source, &runtime.entries)?;
+    warm_search_index(app, source, &runtime.entries, None)?;
     let _ = save_runtime_cache(
         app,
         source,
         &PersistedRuntime {
             contents: runtime.contents.clone(),
             entries: runtime.entries.clone(),
+            headwords: runtime.headwords.clone(),
+            pages: runtime.pages.clone(),
         },
     );
     cache_put(source, runtime.clone())?;
@@ -293,41 +604,90 @@ pub(crate) fn get_runtime(app: &AppHandle, source: &RuntimeSource) -> Result<Arc
 /// # Errors
 ///
 /// Returns an error when source resolution fails or status/cache storage is unavailable.
-pub(crate) fn start_master_build_impl(app: &AppHandle, zip_path: Option<String>) -> Result<String, String> {
+pub(crate) fn start_master_build_impl(app: &AppHandle, zip_path: Option<String>) -> Result<String, DokhanError> {
     let source = resolve_runtime_source(app, zip_path)?;
     let key = status_key(&source);
-
-    if let Some(runtime) = cache_get(&source)? {
-        let summary = summary_from_runtime(&source, &runtime);
-        set_build_done_status(&key, summary, "Loaded from cache")?;
-        return Ok(key);
-    }
-
-    if let Some(st) = get_build_status_internal(&key)? {
-        if !st.done {
-            return Ok(key);
+    // Registered under the registry lock so the dictionary cannot be deleted meanwhile.
+    touch_dictionary(app, &source, || {
+        if let Some(runtime) = cache_get(&source)? {
+            let summary = summary_from_runtime(&source, &runtime);
+            set_build_done_status(app, &key, summary, "Loaded from cache")?;
+            return Ok(());
         }
-    }
 
-    set_build_status(
-        &key,
-        BuildStatus {
-            phase: "start".to_string(),
-            current: 0,
-            total: 1,
-            message: "Starting build".to_string(),
-            done: false,
-            success: false,
-            error: None,
-            summary: None,
-        },
-    )?;
+        if let Some(st) = get_build_status_internal(&key)? {
+            // A cancelled build may still be unwinding; the restarted worker waits for it to exit.
+            if !st.done && !build_cancel_requested(&key)? {
+                return Ok(());
+            }
+        }
 
-    spawn_build_worker(app.clone(), source, key.clone());
+        let cancel = register_build_token(&key)?;
+        set_build_status(
+            app,
+            &key,
+            BuildStatus {
+                phase: "start".to_string(),
+                current: 0,
+                total: 1,
+                message: "Starting build".to_string(),
+                done: false,
+                success: false,
+                error: None,
+                summary: None,
+                seq: 0,
+            },
+        )?;
+
+        spawn_build_worker(app.clone(), source.clone(), key.clone(), cancel)
+    })?;
 
     Ok(key)
 }
 
+/// Check the runtime cache and search index of the given ZIP source without rebuilding them.
+///
+/// # Errors
+///
+/// Returns an error when `zip_path` is missing/invalid or the cache directory cannot be resolved.
+pub(crate) fn verify_runtime_cache_impl(
+    app: &AppHandle,
+    zip_path: Option<String>,
+) -> Result<RuntimeCacheReport, DokhanError> {
+    let source = resolve_runtime_source(app, zip_path)?;
+    verify_runtime_cache(app, &source, check_search_index)
+}
+
+/// Ask the running build for a source to stop.
+///
+/// Returns `false` when no build is running. The worker publishes the `cancelled`
+/// phase once it has unwound and removed partial cache directories.
+///
+/// # Errors
+///
+/// Returns an error when source resolution fails or status storage is unavailable.
+pub(crate) fn cancel_master_build_impl(app: &AppHandle, zip_path: Option<String>) -> Result<bool, DokhanError> {
+    let source = resolve_runtime_source(app, zip_path)?;
+    let key = status_key(&source);
+    let token = {
+        let tokens = BUILD_CANCEL.get_or_init(|| Mutex::new(BTreeMap::new()));
+        let guard = tokens.lock().map_err(|_| DokhanError::LockPoisoned("build cancel"))?;
+        guard.get(&key).cloned()
+    };
+    let Some(token) = token else {
+        return Ok(false);
+    };
+    if token.is_cancelled() {
+        return Ok(true);
+    }
+    token.cancel();
+    update_build_status(app, &key, |st| {
+        st.phase = "cancelling".to_string();
+        st.message = "Cancelling build".to_string();
+    })?;
+    Ok(true)
+}
+
 /// Get current build status, returning idle if not started.
 ///
 /// # Errors
@@ -336,7 +696,7 @@ pub(crate) fn start_master_build_impl(app: &AppHandle, zip_path: Option<String>)
 pub(crate) fn get_master_build_status_impl(
     app: &AppHandle,
     zip_path: Option<String>,
-) -> Result<BuildStatus, String> {
+) -> Result<BuildStatus, DokhanError> {
     let source = resolve_runtime_source(app, zip_path)?;
     let key = status_key(&source);
     if let Some(st) = get_build_status_internal(&key)? {
@@ -351,6 +711,7 @@ pub(crate) fn get_master_build_status_impl(
         success: false,
         error: None,
         summary: None,
+        seq: 0,
     })
 }
 
@@ -359,7 +720,7 @@ pub(crate) fn get_master_build_status_impl(
 /// # Errors
 ///
 /// Returns an error when source resolution or runtime loading fails.
-pub(crate) fn get_master_contents_impl(app: &AppHandle, zip_path: Option<String>) -> Result<Vec<ContentItem>, String> {
+pub(crate) fn get_master_contents_impl(app: &AppHandle, zip_path: Option<String>) -> Result<Vec<ContentItem>, DokhanError> {
     let source = resolve_runtime_source(app, zip_path)?;
     Ok(get_runtime(app, &source)?.contents.clone())
 }
@@ -373,22 +734,21 @@ pub(crate) fn get_entry_detail_impl(
     app: &AppHandle,
     id: usize,
     zip_path: Option<String>,
-) -> Result<EntryDetail, String> {
+) -> Result<EntryDetail, DokhanError> {
     let source = resolve_runtime_source(app, zip_path)?;
     let runtime = get_runtime(app, &source)?;
     let entry = runtime
         .entries
-        .iter()
-        .find(|e| e.id == id)
-        .cloned()
-        .ok_or_else(|| format!("entry not found: {id}"))?;
+        .by_id(id)
+        .map(EntryRef::to_detail)
+        .ok_or_else(|| DokhanError::not_found("entry", id.to_string()))?;
 
     match source {
-        RuntimeSource::ZipPath(zip_path) => Ok(hydrate_zip_entry_detail(&zip_path, entry)),
+        RuntimeSource::ZipPath(zip_path) => Ok(hydrate_entry_detail(&zip_path, runtime.pages.as_deref(), entry)),
     }
 }
 
-/// Return content page HTML/text from runtime cache or CHM object.
+/// Return content page HTML/text from the persisted page store or CHM object.
 ///
 /// # Errors
 ///
@@ -398,7 +758,7 @@ pub(crate) fn get_content_page_impl(
     local: &str,
     source_path: Option<&str>,
     zip_path: Option<String>,
-) -> Result<ContentPage, String> {
+) -> Result<ContentPage, DokhanError> {
     let source_path = source_path
         .unwrap_or("master.chm")
         .to_ascii_lowercase();
@@ -406,10 +766,13 @@ pub(crate) fn get_content_page_impl(
     match &source {
         RuntimeSource::ZipPath(zip_path) => {
             let runtime = get_runtime(app, &source)?;
-            if source_path == "master.chm" {
-                if let Some(v) = runtime.content_pages.get(local).cloned() {
-                    return Ok(v);
-                }
+            // A page missing from (or unreadable in) the store is decoded from the CHM instead.
+            if let Some(page) = runtime
+                .pages
+                .as_ref()
+                .and_then(|pages| pages.get(&source_path, local).ok().flatten())
+            {
+                return Ok(page);
             }
             read_content_page_from_zip(zip_path, &source_path, local)
         }
@@ -1,43 +1,134 @@
 //! Persistent storage for managed ZIP files and runtime index caches.
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
 
-use crate::app::model::{ContentItem, EntryDetail, RuntimeSource};
+use crate::app::model::{
+    CacheFileCheck, CacheGcReport, ContentItem, EntryDetail, RuntimeCacheReport, RuntimeSource,
+};
+use crate::error::DokhanError;
+use crate::runtime::entries::{write_entry_index, EntryTable, MappedEntries};
+use crate::runtime::headwords::HeadwordIndex;
+use crate::runtime::pages::PageStore;
+use crate::runtime::search::SEARCH_SCHEMA_VERSION;
+use crate::runtime::zip::{open_zip_archive, RUNTIME_PARSER_VERSION};
 
 const MANAGED_ZIP_DIR: &str = "zips";
+const REGISTRY_FILE: &str = "registry.json";
 const RUNTIME_CACHE_DIR: &str = "runtime-cache";
 const SEARCH_INDEX_DIR: &str = "tantivy";
-const RUNTIME_CACHE_VERSION: u32 = 1;
+const RUNTIME_CACHE_VERSION: u32 = 2;
+/// Manifest version written by the first release, which is still migrated in place.
+const V1_CACHE_VERSION: u32 = 1;
+/// Parser and search schema version that produced every v1 cache; older than the current
+/// parser, so migrated caches are served only until their rebuild.
+const V1_COMPONENT_VERSION: u32 = 1;
+/// Text encoding of every dictionary the first release could index.
+const V1_ENCODING: &str = "EUC-KR";
 const CACHE_MANIFEST_FILE: &str = "manifest.bin";
 const CACHE_CONTENTS_FILE: &str = "contents.bin.zst";
+/// Bincode entry list, replaced by [`CACHE_ENTRY_INDEX_FILE`] since v2.
 const CACHE_ENTRIES_FILE: &str = "entries.bin.zst";
+const CACHE_ENTRY_INDEX_FILE: &str = "entries.idx";
+const CACHE_HEADWORDS_FILE: &str = "headwords.fst";
+const CACHE_PAGES_FILE: &str = "pages.bin";
 const ZSTD_LEVEL: i32 = 3;
+/// Hex digits of the BLAKE3 content hash kept in managed names and cache keys.
+const CONTENT_HASH_HEX_LEN: usize = 32;
+/// Hex digits of the pre-content-hash FNV path/size/mtime fingerprint.
+const LEGACY_FINGERPRINT_HEX_LEN: usize = 16;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Held while a ZIP is copied into the managed directory and while temp files there are
+/// swept, so the sweep never removes a `.zip.tmp` that is still being written.
+static MANAGED_ZIP_WRITES: OnceLock<Mutex<()>> = OnceLock::new();
+
+#[derive(Debug)]
 pub(crate) struct PersistedRuntime {
     pub(crate) contents: Vec<ContentItem>,
-    pub(crate) entries: Vec<EntryDetail>,
+    pub(crate) entries: EntryTable,
+    pub(crate) headwords: Arc<HeadwordIndex>,
+    /// Decoded pages written during the build; `None` for caches that predate the store.
+    pub(crate) pages: Option<Arc<PageStore>>,
+}
+
+/// Managed ZIP copy found under the app cache directory.
+#[derive(Debug, Clone)]
+pub(crate) struct ManagedZip {
+    pub(crate) path: PathBuf,
+    pub(crate) modified: std::time::SystemTime,
+    pub(crate) size: u64,
+}
+
+/// Outcome of reading a persisted runtime cache.
+pub(crate) enum CacheLoad {
+    Loaded(PersistedRuntime),
+    Missing,
+    /// Cache existed but was unusable and has been discarded; the report says why.
+    Rejected(RuntimeCacheReport),
+    /// Cache is intact but was written by an older parser; it can be served until a rebuild
+    /// replaces it. Its search index has been dropped.
+    Stale(PersistedRuntime, RuntimeCacheReport),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct CacheFileDigest {
+    name: String,
+    len: u64,
+    blake3: String,
 }
 
+/// Manifest written by the first release, before checksums and component versions.
 #[derive(Debug, Serialize, Deserialize)]
+struct ManifestV1 {
+    version: u32,
+    contents_count: usize,
+    entries_count: usize,
+}
+
+/// Entry as stored in a v1 entry list, before the source encoding was recorded.
+#[derive(Debug, Deserialize)]
+struct EntryDetailV1 {
+    id: usize,
+    headword: String,
+    aliases: Vec<String>,
+    source_path: String,
+    target_local: String,
+    definition_text: String,
+    definition_html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct RuntimeCacheManifest {
     version: u32,
+    parser_version: u32,
+    search_schema_version: u32,
     contents_count: usize,
     entries_count: usize,
+    /// Eagerly loaded data files, verified on every load.
+    files: Vec<CacheFileDigest>,
+    /// Memory-mapped entry index; loads only check its length and header.
+    entry_index: CacheFileDigest,
+    /// Memory-mapped headword FST, checked like the entry index.
+    headword_index: CacheFileDigest,
+    /// Page store, verified only by [`verify_runtime_cache`] since it is read page by page.
+    page_store: Option<CacheFileDigest>,
 }
 
-fn fnv1a64(input: &[u8]) -> u64 {
-    let mut hash: u64 = 0xcbf29ce484222325;
-    for b in input {
-        hash ^= *b as u64;
-        hash = hash.wrapping_mul(0x100000001b3);
-    }
-    hash
+/// Manifest plus data file bytes that matched their checksums, keyed by file name.
+type VerifiedCache = (RuntimeCacheManifest, BTreeMap<String, Vec<u8>>);
+
+enum ManifestState {
+    Missing,
+    Current(RuntimeCacheManifest),
+    Outdated { version: u32, bytes: Vec<u8> },
+    Unsupported(u32),
+    Unreadable(String),
 }
 
 fn sanitize_name(name: &str) -> String {
@@ -56,47 +147,92 @@ fn sanitize_name(name: &str) -> String {
     }
 }
 
-fn source_fingerprint(path: &Path) -> Result<String, String> {
-    let canonical = path
-        .canonicalize()
-        .unwrap_or_else(|_| path.to_path_buf())
-        .to_string_lossy()
-        .to_string();
-    let meta = fs::metadata(path).map_err(|e| format!("failed to stat source zip: {e}"))?;
-    let mtime = meta
-        .modified()
-        .ok()
-        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    let payload = format!("{canonical}:{}:{mtime}", meta.len());
-    Ok(format!("{:016x}", fnv1a64(payload.as_bytes())))
+/// Content identity of a dataset ZIP: BLAKE3 over its central directory.
+///
+/// Entry names, uncompressed sizes, and CRC-32s (which cover every CHM) are hashed in name
+/// order, so copies, renames, and re-compression keep the same identity while any content
+/// change yields a new one. Only the central directory is read.
+///
+/// # Errors
+///
+/// Returns an error when the ZIP cannot be opened or its directory is malformed.
+pub(crate) fn dataset_content_hash(zip_path: &Path) -> Result<String, DokhanError> {
+    let mut archive = open_zip_archive(zip_path)?;
+    let mut rows = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| DokhanError::zip("failed to read zip entry", e))?;
+        rows.push((entry.name().to_string(), entry.size(), entry.crc32()));
+    }
+    rows.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"dokhan-dataset-v1");
+    for (name, size, crc) in rows {
+        hasher.update(&(name.len() as u64).to_le_bytes());
+        hasher.update(name.as_bytes());
+        hasher.update(&size.to_le_bytes());
+        hasher.update(&crc.to_le_bytes());
+    }
+    Ok(hasher.finalize().to_hex()[..CONTENT_HASH_HEX_LEN].to_string())
+}
+
+/// Split a managed file stem into its base name and `-<hash>` suffix.
+///
+/// Accepts both content hashes and legacy path fingerprints.
+pub(crate) fn split_managed_stem(stem: &str) -> (&str, Option<&str>) {
+    match stem.rsplit_once('-') {
+        Some((head, tail))
+            if !head.is_empty()
+                && (tail.len() == CONTENT_HASH_HEX_LEN || tail.len() == LEGACY_FINGERPRINT_HEX_LEN)
+                && tail.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            (head, Some(tail))
+        }
+        _ => (stem, None),
+    }
+}
+
+/// Content hash embedded in a managed ZIP name, if it is not a legacy one.
+fn managed_content_hash(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    split_managed_stem(stem)
+        .1
+        .filter(|hash| hash.len() == CONTENT_HASH_HEX_LEN)
 }
 
-fn managed_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+fn managed_root(app: &tauri::AppHandle) -> Result<PathBuf, DokhanError> {
     app.path()
         .app_cache_dir()
-        .map_err(|e| format!("failed to resolve app cache dir: {e}"))
+        .map_err(|e| DokhanError::Internal(format!("failed to resolve app cache dir: {e}")))
+}
+
+fn lock_managed_zip_writes() -> Result<MutexGuard<'static, ()>, DokhanError> {
+    MANAGED_ZIP_WRITES
+        .get_or_init(|| Mutex::new(()))
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("managed zip writes"))
 }
 
-fn managed_zip_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+fn managed_zip_dir(app: &tauri::AppHandle) -> Result<PathBuf, DokhanError> {
     let dir = managed_root(app)?.join(MANAGED_ZIP_DIR);
-    fs::create_dir_all(&dir).map_err(|e| format!("failed to create managed zip dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| DokhanError::io("failed to create managed zip dir", e))?;
     Ok(dir)
 }
 
-/// Pick the newest managed ZIP from app cache, if available.
+/// List managed ZIP files with their modification times and sizes, in directory order.
 ///
 /// # Errors
 ///
 /// Returns an error when managed ZIP directory cannot be resolved/read.
-pub(crate) fn latest_managed_zip(app: &tauri::AppHandle) -> Result<Option<PathBuf>, String> {
+pub(crate) fn list_managed_zips(app: &tauri::AppHandle) -> Result<Vec<ManagedZip>, DokhanError> {
     let dir = managed_zip_dir(app)?;
-    let mut best: Option<(std::time::SystemTime, PathBuf)> = None;
+    let mut out = Vec::new();
 
-    let iter = fs::read_dir(&dir).map_err(|e| format!("failed to read managed zip dir: {e}"))?;
+    let iter = fs::read_dir(&dir).map_err(|e| DokhanError::io("failed to read managed zip dir", e))?;
     for entry in iter {
-        let entry = entry.map_err(|e| format!("failed to read managed zip entry: {e}"))?;
+        let entry = entry.map_err(|e| DokhanError::io("failed to read managed zip entry", e))?;
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -109,33 +245,92 @@ pub(crate) fn latest_managed_zip(app: &tauri::AppHandle) -> Result<Option<PathBu
         if !is_zip {
             continue;
         }
-        let mtime = match entry.metadata().and_then(|m| m.modified()) {
-            Ok(t) => t,
-            Err(_) => std::time::SystemTime::UNIX_EPOCH,
-        };
+        let meta = entry.metadata().ok();
+        let modified = meta
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+        let size = meta.map(|m| m.len()).unwrap_or(0);
+        out.push(ManagedZip { path, modified, size });
+    }
+
+    Ok(out)
+}
+
+/// Pick the newest managed ZIP from app cache, if available.
+///
+/// # Errors
+///
+/// Returns an error when managed ZIP directory cannot be resolved/read.
+pub(crate) fn latest_managed_zip(app: &tauri::AppHandle) -> Result<Option<PathBuf>, DokhanError> {
+    let mut best: Option<(std::time::SystemTime, PathBuf)> = None;
+    for zip in list_managed_zips(app)? {
         match &best {
-            Some((best_time, _)) if *best_time >= mtime => {}
-            _ => best = Some((mtime, path)),
+            Some((best_time, _)) if *best_time >= zip.modified => {}
+            _ => best = Some((zip.modified, zip.path)),
         }
     }
-
     Ok(best.map(|(_, p)| p))
 }
 
-fn runtime_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+/// Load the dictionary registry JSON, if it has been written.
+///
+/// An unreadable registry is treated as missing; it is rebuilt from the managed ZIPs.
+///
+/// # Errors
+///
+/// Returns an error when the app cache directory cannot be resolved.
+pub(crate) fn load_registry_file<T: DeserializeOwned>(app: &tauri::AppHandle) -> Result<Option<T>, DokhanError> {
+    let path = managed_root(app)?.join(REGISTRY_FILE);
+    let Ok(bytes) = fs::read(&path) else {
+        return Ok(None);
+    };
+    Ok(serde_json::from_slice(&bytes).ok())
+}
+
+/// Save the dictionary registry JSON atomically.
+///
+/// # Errors
+///
+/// Returns an error when serialization or the atomic write fails.
+pub(crate) fn save_registry_file<T: Serialize>(app: &tauri::AppHandle, value: &T) -> Result<(), DokhanError> {
+    let root = managed_root(app)?;
+    fs::create_dir_all(&root).map_err(|e| DokhanError::io("failed to create app cache dir", e))?;
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|e| DokhanError::Internal(format!("failed to encode dictionary registry: {e}")))?;
+    write_atomic(&root.join(REGISTRY_FILE), &bytes)
+}
+
+/// Whether a completed runtime cache has been saved for `source`.
+///
+/// # Errors
+///
+/// Returns an error when the cache directory cannot be resolved.
+pub(crate) fn has_runtime_cache(app: &tauri::AppHandle, source: &RuntimeSource) -> Result<bool, DokhanError> {
+    let source_dir = runtime_cache_dir(app)?.join(runtime_cache_source_id(source));
+    Ok(source_dir.join(CACHE_MANIFEST_FILE).exists())
+}
+
+fn runtime_cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, DokhanError> {
     let dir = managed_root(app)?.join(RUNTIME_CACHE_DIR);
-    fs::create_dir_all(&dir).map_err(|e| format!("failed to create runtime cache dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| DokhanError::io("failed to create runtime cache dir", e))?;
     Ok(dir)
 }
 
+/// Runtime cache directory name: the dataset content hash.
 fn runtime_cache_source_id(source: &RuntimeSource) -> String {
     match source {
         RuntimeSource::ZipPath(path) => {
-            let file = path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("zip-runtime");
-            sanitize_name(file.trim_end_matches(".zip"))
+            if let Some(hash) = managed_content_hash(path) {
+                return hash.to_string();
+            }
+            dataset_content_hash(path).unwrap_or_else(|_| {
+                let file = path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("zip-runtime");
+                sanitize_name(file.trim_end_matches(".zip"))
+            })
         }
     }
 }
@@ -143,12 +338,21 @@ fn runtime_cache_source_id(source: &RuntimeSource) -> String {
 fn runtime_cache_source_dir(
     app: &tauri::AppHandle,
     source: &RuntimeSource,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, DokhanError> {
     let dir = runtime_cache_dir(app)?.join(runtime_cache_source_id(source));
-    fs::create_dir_all(&dir).map_err(|e| format!("failed to create source cache dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| DokhanError::io("failed to create source cache dir", e))?;
     Ok(dir)
 }
 
+/// Path the build writes the decoded page store to.
+///
+/// # Errors
+///
+/// Returns an error when the source cache directory cannot be created.
+pub(crate) fn page_store_path(app: &tauri::AppHandle, source: &RuntimeSource) -> Result<PathBuf, DokhanError> {
+    Ok(runtime_cache_source_dir(app, source)?.join(CACHE_PAGES_FILE))
+}
+
 /// Return per-source Tantivy index directory under runtime cache root.
 ///
 /// # Errors
@@ -157,50 +361,51 @@ fn runtime_cache_source_dir(
 pub(crate) fn search_index_dir(
     app: &tauri::AppHandle,
     source: &RuntimeSource,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, DokhanError> {
     let dir = runtime_cache_source_dir(app, source)?.join(SEARCH_INDEX_DIR);
-    fs::create_dir_all(&dir).map_err(|e| format!("failed to create search index dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| DokhanError::io("failed to create search index dir", e))?;
     Ok(dir)
 }
 
-fn encode_bin<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
-    bincode::serialize(value).map_err(|e| format!("bincode encode failed: {e}"))
+pub(crate) fn encode_bin<T: Serialize>(value: &T) -> Result<Vec<u8>, DokhanError> {
+    bincode::serialize(value).map_err(|e| DokhanError::cache("bincode encode failed", e))
 }
 
-fn decode_bin<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
-    bincode::deserialize(bytes).map_err(|e| format!("bincode decode failed: {e}"))
+pub(crate) fn decode_bin<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DokhanError> {
+    bincode::deserialize(bytes).map_err(|e| DokhanError::cache("bincode decode failed", e))
 }
 
-fn compress_zstd(bytes: &[u8]) -> Result<Vec<u8>, String> {
-    zstd::stream::encode_all(bytes, ZSTD_LEVEL).map_err(|e| format!("zstd encode failed: {e}"))
+pub(crate) fn compress_zstd(bytes: &[u8]) -> Result<Vec<u8>, DokhanError> {
+    zstd::stream::encode_all(bytes, ZSTD_LEVEL).map_err(|e| DokhanError::io("zstd encode failed", e))
 }
 
-fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>, String> {
-    zstd::stream::decode_all(bytes).map_err(|e| format!("zstd decode failed: {e}"))
+pub(crate) fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>, DokhanError> {
+    zstd::stream::decode_all(bytes).map_err(|e| DokhanError::io("zstd decode failed", e))
 }
 
-fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), DokhanError> {
     let tmp = path.with_extension("tmp");
-    let mut out = fs::File::create(&tmp).map_err(|e| format!("failed to create temp file: {e}"))?;
+    let mut out = fs::File::create(&tmp).map_err(|e| DokhanError::io("failed to create temp file", e))?;
     out.write_all(bytes)
-        .map_err(|e| format!("failed to write temp file: {e}"))?;
+        .map_err(|e| DokhanError::io("failed to write temp file", e))?;
     out.flush()
-        .map_err(|e| format!("failed to flush temp file: {e}"))?;
-    fs::rename(&tmp, path).map_err(|e| format!("failed to rename temp file: {e}"))?;
+        .map_err(|e| DokhanError::io("failed to flush temp file", e))?;
+    fs::rename(&tmp, path).map_err(|e| DokhanError::io("failed to rename temp file", e))?;
     Ok(())
 }
 
 /// Copy source ZIP into app-managed cache directory and return managed path.
 ///
-/// Existing files with matching fingerprint are reused.
+/// Managed copies are named `<stem>-<content hash>.zip`; an existing copy with the same
+/// content is reused whatever its name or original location.
 ///
 /// # Errors
 ///
-/// Returns an error when source metadata/copy or directory resolution fails.
+/// Returns an error when the source cannot be hashed/copied or directory resolution fails.
 pub(crate) fn ensure_managed_zip_copy(
     app: &tauri::AppHandle,
     source_zip: &Path,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, DokhanError> {
     if source_zip.exists()
         && source_zip
             .parent()
@@ -212,90 +417,550 @@ pub(crate) fn ensure_managed_zip_copy(
         return Ok(source_zip.to_path_buf());
     }
 
+    let hash = dataset_content_hash(source_zip)?;
+    for zip in list_managed_zips(app)? {
+        if managed_content_hash(&zip.path) == Some(hash.as_str()) {
+            return Ok(zip.path);
+        }
+    }
+
     let dir = managed_zip_dir(app)?;
     let stem = source_zip
         .file_stem()
         .and_then(|s| s.to_str())
         .map(sanitize_name)
         .unwrap_or_else(|| "dictionary".to_string());
-    let fp = source_fingerprint(source_zip)?;
-    let managed = dir.join(format!("{stem}-{fp}.zip"));
+    let managed = dir.join(format!("{stem}-{hash}.zip"));
+    let _writes = lock_managed_zip_writes()?;
     if managed.exists() {
         return Ok(managed);
     }
-    fs::copy(source_zip, &managed).map_err(|e| format!("failed to copy zip to managed dir: {e}"))?;
+    let tmp = managed.with_extension("zip.tmp");
+    fs::copy(source_zip, &tmp).map_err(|e| DokhanError::io("failed to copy zip to managed dir", e))?;
+    fs::rename(&tmp, &managed).map_err(|e| DokhanError::io("failed to rename managed zip", e))?;
     Ok(managed)
 }
 
-/// Load persisted runtime cache, if present.
+/// Rename managed ZIPs still keyed by the legacy path/mtime fingerprint to their content
+/// hash, moving their runtime cache and Tantivy index along.
+///
+/// Returns the `(old, new)` file names; duplicates of an already migrated copy are removed.
 ///
 /// # Errors
 ///
-/// Returns an error when cache directory resolution fails.
-pub(crate) fn load_runtime_cache(
+/// Returns an error when the managed directories cannot be read or an entry cannot be moved.
+pub(crate) fn migrate_legacy_managed_zips(app: &tauri::AppHandle) -> Result<Vec<(String, String)>, DokhanError> {
+    let cache_root = runtime_cache_dir(app)?;
+    let mut renames = Vec::new();
+    for zip in list_managed_zips(app)? {
+        let Some(stem) = zip.path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let (base, Some(fingerprint)) = split_managed_stem(stem) else {
+            continue;
+        };
+        if fingerprint.len() != LEGACY_FINGERPRINT_HEX_LEN {
+            continue;
+        }
+        // Unreadable ZIPs keep their legacy name; they fail the same way when opened.
+        let Ok(hash) = dataset_content_hash(&zip.path) else {
+            continue;
+        };
+
+        let legacy_cache = cache_root.join(sanitize_name(stem));
+        let cache = cache_root.join(&hash);
+        if legacy_cache.exists() {
+            if cache.exists() {
+                fs::remove_dir_all(&legacy_cache)
+                    .map_err(|e| DokhanError::io("failed to remove legacy cache dir", e))?;
+            } else {
+                fs::rename(&legacy_cache, &cache).map_err(|e| DokhanError::io("failed to migrate cache dir", e))?;
+            }
+        }
+
+        let new_name = format!("{base}-{hash}.zip");
+        let target = zip.path.with_file_name(&new_name);
+        if target.exists() {
+            fs::remove_file(&zip.path).map_err(|e| DokhanError::io("failed to remove duplicate managed zip", e))?;
+        } else {
+            fs::rename(&zip.path, &target).map_err(|e| DokhanError::io("failed to migrate managed zip", e))?;
+        }
+        renames.push((format!("{stem}.zip"), new_name));
+    }
+    Ok(renames)
+}
+
+/// Remove a source cache directory left without a completed runtime cache.
+///
+/// Directories holding a saved manifest are kept so a cancelled rebuild does not drop a valid cache.
+///
+/// # Errors
+///
+/// Returns an error when the cache directory cannot be resolved or removed.
+pub(crate) fn discard_partial_runtime_cache(
     app: &tauri::AppHandle,
     source: &RuntimeSource,
-) -> Result<Option<PersistedRuntime>, String> {
-    let source_dir = runtime_cache_source_dir(app, source)?;
-    let manifest_file = source_dir.join(CACHE_MANIFEST_FILE);
-    let contents_file = source_dir.join(CACHE_CONTENTS_FILE);
-    let entries_file = source_dir.join(CACHE_ENTRIES_FILE);
+) -> Result<(), DokhanError> {
+    let source_dir = runtime_cache_dir(app)?.join(runtime_cache_source_id(source));
+    if !source_dir.exists() || source_dir.join(CACHE_MANIFEST_FILE).exists() {
+        return Ok(());
+    }
+    fs::remove_dir_all(&source_dir).map_err(|e| DokhanError::io("failed to remove partial cache dir", e))
+}
 
-    if !manifest_file.exists() || !contents_file.exists() || !entries_file.exists() {
-        return Ok(None);
+/// Delete a managed ZIP together with its runtime cache and search index.
+///
+/// # Errors
+///
+/// Returns an error when the files exist but cannot be removed.
+pub(crate) fn remove_managed_dictionary(
+    app: &tauri::AppHandle,
+    source: &RuntimeSource,
+) -> Result<(), DokhanError> {
+    let source_dir = runtime_cache_dir(app)?.join(runtime_cache_source_id(source));
+    if source_dir.exists() {
+        fs::remove_dir_all(&source_dir).map_err(|e| DokhanError::io("failed to remove runtime cache dir", e))?;
     }
+    match source {
+        RuntimeSource::ZipPath(path) => {
+            if path.exists() {
+                fs::remove_file(path).map_err(|e| DokhanError::io("failed to remove managed zip", e))?;
+            }
+        }
+    }
+    Ok(())
+}
 
-    let fallback_none = || {
-        let _ = fs::remove_dir_all(&source_dir);
-        Ok(None)
+fn dir_size(path: &Path) -> u64 {
+    let Ok(iter) = fs::read_dir(path) else {
+        return 0;
     };
+    iter.filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
 
-    let manifest_bytes = match fs::read(&manifest_file) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
-    };
-    let manifest: RuntimeCacheManifest = match decode_bin(&manifest_bytes) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
+/// Remove runtime-cache directories (and their Tantivy indexes) whose managed ZIP is gone,
+/// plus temp files left by interrupted writes.
+///
+/// # Errors
+///
+/// Returns an error when the cache directories cannot be read or an orphan cannot be removed.
+pub(crate) fn collect_orphaned_caches(app: &tauri::AppHandle) -> Result<CacheGcReport, DokhanError> {
+    let live = list_managed_zips(app)?
+        .into_iter()
+        .map(|zip| runtime_cache_source_id(&RuntimeSource::ZipPath(zip.path)))
+        .collect::<BTreeSet<_>>();
+    collect_orphans_in(&runtime_cache_dir(app)?, &managed_zip_dir(app)?, &live)
+}
+
+fn collect_orphans_in(cache_root: &Path, zip_dir: &Path, live: &BTreeSet<String>) -> Result<CacheGcReport, DokhanError> {
+    let mut report = CacheGcReport {
+        removed: Vec::new(),
+        freed_bytes: 0,
     };
-    if manifest.version != RUNTIME_CACHE_VERSION {
-        return fallback_none();
+    let iter = fs::read_dir(cache_root).map_err(|e| DokhanError::io("failed to read runtime cache dir", e))?;
+    for entry in iter {
+        let entry = entry.map_err(|e| DokhanError::io("failed to read runtime cache entry", e))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || live.contains(&name) {
+            continue;
+        }
+        let size = dir_size(&path);
+        fs::remove_dir_all(&path).map_err(|e| DokhanError::io("failed to remove orphaned cache dir", e))?;
+        report.freed_bytes += size;
+        report.removed.push(name);
     }
 
-    let contents_encoded = match fs::read(&contents_file) {
+    let _writes = lock_managed_zip_writes()?;
+    let iter = fs::read_dir(zip_dir).map_err(|e| DokhanError::io("failed to read managed zip dir", e))?;
+    for entry in iter.filter_map(Result::ok) {
+        let path = entry.path();
+        let is_tmp = path.extension().and_then(|x| x.to_str()) == Some("tmp");
+        if !is_tmp || !path.is_file() {
+            continue;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if fs::remove_file(&path).is_ok() {
+            report.freed_bytes += size;
+            report.removed.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    Ok(report)
+}
+
+fn file_digest(name: &str, bytes: &[u8]) -> CacheFileDigest {
+    CacheFileDigest {
+        name: name.to_string(),
+        len: bytes.len() as u64,
+        blake3: blake3::hash(bytes).to_hex().to_string(),
+    }
+}
+
+/// Digest of a file too large to hold in memory; `None` when it cannot be read.
+fn streamed_digest(path: &Path) -> Option<CacheFileDigest> {
+    let file = fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(std::io::BufReader::new(file)).ok()?;
+    Some(CacheFileDigest {
+        name: path.file_name()?.to_str()?.to_string(),
+        len: hasher.count(),
+        blake3: hasher.finalize().to_hex().to_string(),
+    })
+}
+
+/// Digest of a file this process just wrote.
+fn written_digest(path: &Path) -> Result<CacheFileDigest, DokhanError> {
+    streamed_digest(path).ok_or_else(|| DokhanError::Internal(format!("{} unreadable after write", path.display())))
+}
+
+fn file_problem(digest: &CacheFileDigest, actual_bytes: Option<u64>) -> String {
+    match actual_bytes {
+        None => format!("{} is missing", digest.name),
+        Some(len) if len != digest.len => format!("{} has {len} bytes, expected {}", digest.name, digest.len),
+        Some(_) => format!("{} checksum mismatch", digest.name),
+    }
+}
+
+/// Read the manifest, dispatching on the leading version field.
+fn read_manifest(dir: &Path) -> ManifestState {
+    let bytes = match fs::read(dir.join(CACHE_MANIFEST_FILE)) {
         Ok(v) => v,
-        Err(_) => return fallback_none(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return ManifestState::Missing,
+        Err(e) => return ManifestState::Unreadable(format!("manifest unreadable: {e}")),
     };
-    let entries_encoded = match fs::read(&entries_file) {
+    let version = match decode_bin::<u32>(&bytes) {
         Ok(v) => v,
-        Err(_) => return fallback_none(),
+        Err(e) => return ManifestState::Unreadable(format!("manifest undecodable: {e}")),
     };
-    let contents_bytes = match decompress_zstd(&contents_encoded) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
+    match version {
+        RUNTIME_CACHE_VERSION => decode_bin(&bytes)
+            .map(ManifestState::Current)
+            .unwrap_or_else(|e| ManifestState::Unreadable(format!("manifest undecodable: {e}"))),
+        V1_CACHE_VERSION => ManifestState::Outdated {
+            version: V1_CACHE_VERSION,
+            bytes,
+        },
+        other => ManifestState::Unsupported(other),
+    }
+}
+
+/// Read a v1 data file and decode its compressed bincode list, checking the manifest count.
+fn read_v1_list<T: DeserializeOwned>(
+    dir: &Path,
+    name: &str,
+    expected: usize,
+) -> Result<(Vec<u8>, Vec<T>), DokhanError> {
+    let raw = fs::read(dir.join(name)).map_err(|e| DokhanError::io(format!("failed to read {name}"), e))?;
+    let items: Vec<T> = decode_bin(&decompress_zstd(&raw)?)?;
+    if items.len() != expected {
+        return Err(DokhanError::Internal(format!(
+            "{name} holds {} items, manifest expects {expected}",
+            items.len()
+        )));
+    }
+    Ok((raw, items))
+}
+
+/// Lift a v1 manifest to the current layout.
+///
+/// v1 recorded no checksums, so the data files are decoded to check them against the manifest
+/// counts and checksummed now. Entries gain the EUC-KR encoding and are rewritten as the mapped
+/// entry index, from which the headword FST is built; v1 caches have no page store, so pages
+/// fall back to the CHM.
+fn upgrade_manifest(dir: &Path, bytes: &[u8]) -> Result<RuntimeCacheManifest, DokhanError> {
+    let v1: ManifestV1 = decode_bin(bytes)?;
+    let (contents, _) = read_v1_list::<ContentItem>(dir, CACHE_CONTENTS_FILE, v1.contents_count)?;
+    let (_, entries) = read_v1_list::<EntryDetailV1>(dir, CACHE_ENTRIES_FILE, v1.entries_count)?;
+    let entries = entries
+        .into_iter()
+        .map(|e| EntryDetail {
+            id: e.id,
+            headword: e.headword,
+            aliases: e.aliases,
+            source_path: e.source_path,
+            target_local: e.target_local,
+            definition_text: e.definition_text,
+            definition_html: e.definition_html,
+            encoding: V1_ENCODING.to_string(),
+        })
+        .collect();
+    let index_path = dir.join(CACHE_ENTRY_INDEX_FILE);
+    write_entry_index(&index_path, &EntryTable::owned(entries))?;
+    let headwords = HeadwordIndex::build(&EntryTable::Mapped(Arc::new(MappedEntries::open(&index_path)?)))?;
+    write_atomic(&dir.join(CACHE_HEADWORDS_FILE), headwords.as_bytes())?;
+
+    Ok(RuntimeCacheManifest {
+        version: RUNTIME_CACHE_VERSION,
+        parser_version: V1_COMPONENT_VERSION,
+        search_schema_version: V1_COMPONENT_VERSION,
+        contents_count: v1.contents_count,
+        entries_count: v1.entries_count,
+        files: vec![file_digest(CACHE_CONTENTS_FILE, &contents)],
+        entry_index: written_digest(&index_path)?,
+        headword_index: file_digest(CACHE_HEADWORDS_FILE, headwords.as_bytes()),
+        page_store: None,
+    })
+}
+
+/// Rewrite an older cache in place so it loads without a rebuild.
+///
+/// The superseded entry list is removed only after the new manifest is written.
+fn migrate_runtime_cache(dir: &Path) -> Result<(), DokhanError> {
+    let ManifestState::Outdated { bytes, .. } = read_manifest(dir) else {
+        return Ok(());
     };
-    let entries_bytes = match decompress_zstd(&entries_encoded) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
+    let manifest = upgrade_manifest(dir, &bytes)?;
+    write_atomic(&dir.join(CACHE_MANIFEST_FILE), &encode_bin(&manifest)?)?;
+    let _ = fs::remove_file(dir.join(CACHE_ENTRIES_FILE));
+    Ok(())
+}
+
+fn empty_report(cache_key: &str) -> RuntimeCacheReport {
+    RuntimeCacheReport {
+        cache_key: cache_key.to_string(),
+        status: "missing",
+        version: None,
+        parser_version: None,
+        search_schema_version: None,
+        search_index: "missing",
+        files: Vec::new(),
+        problems: Vec::new(),
+    }
+}
+
+/// Check a cache directory against its manifest without modifying it.
+///
+/// Eager data files are always checksummed; mapped files only when `deep` is set, otherwise
+/// just their length is compared. When every check passes, the manifest and eager file
+/// bytes are returned too.
+fn inspect_cache_dir(
+    dir: &Path,
+    cache_key: &str,
+    deep: bool,
+) -> (RuntimeCacheReport, Option<VerifiedCache>) {
+    let mut report = empty_report(cache_key);
+    let manifest = match read_manifest(dir) {
+        ManifestState::Missing => return (report, None),
+        ManifestState::Current(manifest) => manifest,
+        ManifestState::Outdated { version, .. } => {
+            report.status = "outdated";
+            report.version = Some(version);
+            report.problems.push(format!(
+                "cache format v{version} is migrated to v{RUNTIME_CACHE_VERSION} on next load"
+            ));
+            return (report, None);
+        }
+        ManifestState::Unsupported(version) => {
+            report.status = "outdated";
+            report.version = Some(version);
+            report
+                .problems
+                .push(format!("cache format v{version} cannot be migrated to v{RUNTIME_CACHE_VERSION}"));
+            return (report, None);
+        }
+        ManifestState::Unreadable(problem) => {
+            report.status = "corrupt";
+            report.problems.push(problem);
+            return (report, None);
+        }
     };
+    report.status = "ok";
+    report.version = Some(manifest.version);
+    report.parser_version = Some(manifest.parser_version);
+    report.search_schema_version = Some(manifest.search_schema_version);
 
-    let contents: Vec<ContentItem> = match decode_bin(&contents_bytes) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
+    let mut verified = BTreeMap::new();
+    for digest in &manifest.files {
+        let bytes = fs::read(dir.join(&digest.name)).ok();
+        let actual_bytes = bytes.as_ref().map(|b| b.len() as u64);
+        let ok = bytes
+            .as_ref()
+            .is_some_and(|b| b.len() as u64 == digest.len && blake3::hash(b).to_hex().as_str() == digest.blake3);
+        if !ok {
+            report.status = "corrupt";
+            report.problems.push(file_problem(digest, actual_bytes));
+        }
+        report.files.push(CacheFileCheck {
+            name: digest.name.clone(),
+            expected_bytes: digest.len,
+            actual_bytes,
+            ok,
+        });
+        if let Some(bytes) = bytes.filter(|_| ok) {
+            verified.insert(digest.name.clone(), bytes);
+        }
+    }
+    let mapped = [&manifest.entry_index, &manifest.headword_index]
+        .into_iter()
+        .chain(manifest.page_store.iter().filter(|_| deep));
+    for digest in mapped {
+        let path = dir.join(&digest.name);
+        let (actual_bytes, ok) = if deep {
+            let actual = streamed_digest(&path);
+            (actual.as_ref().map(|d| d.len), actual.as_ref() == Some(digest))
+        } else {
+            let len = fs::metadata(&path).ok().map(|m| m.len());
+            (len, len == Some(digest.len))
+        };
+        if !ok {
+            report.status = "corrupt";
+            report.problems.push(file_problem(digest, actual_bytes));
+        }
+        report.files.push(CacheFileCheck {
+            name: digest.name.clone(),
+            expected_bytes: digest.len,
+            actual_bytes,
+            ok,
+        });
+    }
+    if report.status == "corrupt" {
+        return (report, None);
+    }
+
+    if manifest.parser_version != RUNTIME_PARSER_VERSION {
+        report.status = "outdated";
+        report.problems.push(format!(
+            "written by parser v{}, current is v{RUNTIME_PARSER_VERSION}",
+            manifest.parser_version
+        ));
+    }
+    if manifest.search_schema_version != SEARCH_SCHEMA_VERSION {
+        report.status = "outdated";
+        report.problems.push(format!(
+            "search index schema v{}, current is v{SEARCH_SCHEMA_VERSION}; the index is rebuilt on next load",
+            manifest.search_schema_version
+        ));
+    }
+    (report, Some((manifest, verified)))
+}
+
+/// Decode the eager files and map the entry index of a verified cache in `dir`.
+fn decode_persisted(
+    dir: &Path,
+    manifest: &RuntimeCacheManifest,
+    mut files: BTreeMap<String, Vec<u8>>,
+) -> Result<PersistedRuntime, DokhanError> {
+    let mut take = |name: &str| files.remove(name).ok_or_else(|| DokhanError::not_found("cache file", name));
+    let contents: Vec<ContentItem> = decode_bin(&decompress_zstd(&take(CACHE_CONTENTS_FILE)?)?)?;
+    let entries = EntryTable::Mapped(Arc::new(MappedEntries::open(&dir.join(&manifest.entry_index.name))?));
+    let headwords = Arc::new(HeadwordIndex::open(&dir.join(&manifest.headword_index.name))?);
+    if contents.len() != manifest.contents_count || entries.len() != manifest.entries_count {
+        return Err(DokhanError::Internal(format!(
+            "cache holds {} contents and {} entries, manifest expects {} and {}",
+            contents.len(),
+            entries.len(),
+            manifest.contents_count,
+            manifest.entries_count
+        )));
+    }
+    Ok(PersistedRuntime {
+        contents,
+        entries,
+        headwords,
+        pages: None,
+    })
+}
+
+/// Load persisted runtime cache, migrating older formats where possible.
+///
+/// A search schema bump only drops the Tantivy index, and a cache from an older parser is
+/// returned as [`CacheLoad::Stale`] to be rebuilt; a checksum failure discards the cache
+/// and returns [`CacheLoad::Rejected`] with the reason.
+///
+/// # Errors
+///
+/// Returns an error when cache directory resolution fails.
+pub(crate) fn load_runtime_cache(app: &tauri::AppHandle, source: &RuntimeSource) -> Result<CacheLoad, DokhanError> {
+    let source_dir = runtime_cache_source_dir(app, source)?;
+    // A failed migration leaves the legacy manifest, which is then rejected below.
+    let _ = migrate_runtime_cache(&source_dir);
+
+    let (mut report, verified) = inspect_cache_dir(&source_dir, &runtime_cache_source_id(source), false);
+    if report.status == "missing" {
+        return Ok(CacheLoad::Missing);
+    }
+    let reject = |report: RuntimeCacheReport| -> Result<CacheLoad, DokhanError> {
+        let _ = fs::remove_dir_all(&source_dir);
+        Ok(CacheLoad::Rejected(report))
     };
-    let entries: Vec<EntryDetail> = match decode_bin(&entries_bytes) {
-        Ok(v) => v,
-        Err(_) => return fallback_none(),
+    let Some((mut manifest, files)) = verified else {
+        return reject(report);
     };
 
-    if contents.len() != manifest.contents_count || entries.len() != manifest.entries_count {
-        return fallback_none();
+    let stale = manifest.parser_version != RUNTIME_PARSER_VERSION;
+    if stale || manifest.search_schema_version != SEARCH_SCHEMA_VERSION {
+        let index_dir = source_dir.join(SEARCH_INDEX_DIR);
+        if index_dir.exists() {
+            fs::remove_dir_all(&index_dir).map_err(|e| DokhanError::io("failed to drop outdated search index", e))?;
+        }
+    }
+    // A stale manifest is left as is, so the cache stays outdated until the rebuild saves.
+    if !stale && manifest.search_schema_version != SEARCH_SCHEMA_VERSION {
+        manifest.search_schema_version = SEARCH_SCHEMA_VERSION;
+        write_atomic(&source_dir.join(CACHE_MANIFEST_FILE), &encode_bin(&manifest)?)?;
     }
 
-    Ok(Some(PersistedRuntime { contents, entries }))
+    match decode_persisted(&source_dir, &manifest, files) {
+        Ok(mut persisted) => {
+            // An unreadable page store only costs CHM reads, so it does not reject the cache.
+            persisted.pages = manifest
+                .page_store
+                .as_ref()
+                .and_then(|digest| PageStore::open(&source_dir.join(&digest.name)).ok())
+                .map(Arc::new);
+            if stale {
+                Ok(CacheLoad::Stale(persisted, report))
+            } else {
+                Ok(CacheLoad::Loaded(persisted))
+            }
+        }
+        Err(err) => {
+            report.status = "corrupt";
+            report.problems.push(err.to_string());
+            reject(report)
+        }
+    }
 }
 
-/// Save runtime cache atomically.
+/// Verify checksums and recorded versions of the runtime cache for `source` without changing it.
+///
+/// `check_index` is run on the Tantivy directory when an index has been written.
+///
+/// # Errors
+///
+/// Returns an error when the cache directory cannot be resolved.
+pub(crate) fn verify_runtime_cache(
+    app: &tauri::AppHandle,
+    source: &RuntimeSource,
+    check_index: impl FnOnce(&Path) -> Result<(), DokhanError>,
+) -> Result<RuntimeCacheReport, DokhanError> {
+    let cache_key = runtime_cache_source_id(source);
+    let source_dir = runtime_cache_dir(app)?.join(&cache_key);
+    let (mut report, _) = inspect_cache_dir(&source_dir, &cache_key, true);
+
+    let index_dir = source_dir.join(SEARCH_INDEX_DIR);
+    if index_dir.join("meta.json").exists() {
+        match check_index(&index_dir) {
+            Ok(()) => report.search_index = "ok",
+            Err(err) => {
+                report.search_index = "corrupt";
+                report.problems.push(err.to_string());
+                if report.status == "ok" {
+                    report.status = "corrupt";
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Save runtime cache atomically with per-file checksums.
 ///
 /// # Errors
 ///
@@ -304,26 +969,153 @@ pub(crate) fn save_runtime_cache(
     app: &tauri::AppHandle,
     source: &RuntimeSource,
     persisted: &PersistedRuntime,
-) -> Result<(), String> {
+) -> Result<(), DokhanError> {
     let source_dir = runtime_cache_source_dir(app, source)?;
-    let manifest_file = source_dir.join(CACHE_MANIFEST_FILE);
-    let contents_file = source_dir.join(CACHE_CONTENTS_FILE);
-    let entries_file = source_dir.join(CACHE_ENTRIES_FILE);
+    let contents_comp = compress_zstd(&encode_bin(&persisted.contents)?)?;
+    let entry_index_path = source_dir.join(CACHE_ENTRY_INDEX_FILE);
 
-    let contents_raw = encode_bin(&persisted.contents)?;
-    let entries_raw = encode_bin(&persisted.entries)?;
-    let contents_comp = compress_zstd(&contents_raw)?;
-    let entries_comp = compress_zstd(&entries_raw)?;
-
-    write_atomic(&contents_file, &contents_comp)?;
-    write_atomic(&entries_file, &entries_comp)?;
+    write_atomic(&source_dir.join(CACHE_CONTENTS_FILE), &contents_comp)?;
+    write_entry_index(&entry_index_path, &persisted.entries)?;
+    write_atomic(&source_dir.join(CACHE_HEADWORDS_FILE), persisted.headwords.as_bytes())?;
 
+    let page_store = if persisted.pages.is_some() {
+        streamed_digest(&source_dir.join(CACHE_PAGES_FILE))
+    } else {
+        None
+    };
     let manifest = RuntimeCacheManifest {
         version: RUNTIME_CACHE_VERSION,
+        parser_version: RUNTIME_PARSER_VERSION,
+        search_schema_version: SEARCH_SCHEMA_VERSION,
         contents_count: persisted.contents.len(),
         entries_count: persisted.entries.len(),
+        files: vec![file_digest(CACHE_CONTENTS_FILE, &contents_comp)],
+        entry_index: written_digest(&entry_index_path)?,
+        headword_index: file_digest(CACHE_HEADWORDS_FILE, persisted.headwords.as_bytes()),
+        page_store,
     };
-    let manifest_bytes = encode_bin(&manifest)?;
-    write_atomic(&manifest_file, &manifest_bytes)?;
+    write_atomic(&source_dir.join(CACHE_MANIFEST_FILE), &encode_bin(&manifest)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    fn write_zip(path: &Path, members: &[(&str, &[u8])], method: CompressionMethod) {
+        let file = fs::File::create(path).expect("create zip");
+        let mut writer = zip::ZipWriter::new(file);
+        for (name, bytes) in members {
+            writer
+                .start_file(*name, FileOptions::default().compression_method(method))
+                .expect("start member");
+            writer.write_all(bytes).expect("write member");
+        }
+        writer.finish().expect("finish zip");
+    }
+
+    #[test]
+    fn content_hash_ignores_location_order_and_compression() {
+        let root = std::env::temp_dir().join(format!("dokhan-hash-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("tmp dir");
+        let a = root.join("dictionary.zip");
+        let b = root.join("copy-elsewhere.zip");
+        let c = root.join("other.zip");
+        write_zip(&a, &[("merge01.chm", b"one"), ("master.chm", b"toc")], CompressionMethod::Stored);
+        write_zip(&b, &[("master.chm", b"toc"), ("merge01.chm", b"one")], CompressionMethod::Deflated);
+        write_zip(&c, &[("merge01.chm", b"two"), ("master.chm", b"toc")], CompressionMethod::Stored);
+
+        let hash = dataset_content_hash(&a).expect("hash a");
+        assert_eq!(hash.len(), CONTENT_HASH_HEX_LEN);
+        assert_eq!(dataset_content_hash(&b).expect("hash b"), hash);
+        assert_ne!(dataset_content_hash(&c).expect("hash c"), hash);
+
+        let managed = root.join(format!("dictionary-{hash}.zip"));
+        assert_eq!(managed_content_hash(&managed), Some(hash.as_str()));
+        assert_eq!(managed_content_hash(&root.join("dictionary-0123456789abcdef.zip")), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn legacy_cache_migrates_and_corruption_is_reported() {
+        let dir = std::env::temp_dir().join(format!("dokhan-cache-verify-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("tmp dir");
+        let contents = compress_zstd(&encode_bin(&Vec::<ContentItem>::new()).expect("encode")).expect("compress");
+        // Bincode is positional, so the shipped entry layout is written as a tuple.
+        let entry = (1usize, "Straße", vec!["Strasse"], "merge01.chm", "strasse.htm", "길", "<p>길</p>");
+        let entries = compress_zstd(&encode_bin(&vec![entry]).expect("encode")).expect("compress");
+        fs::write(dir.join(CACHE_CONTENTS_FILE), &contents).expect("contents");
+        fs::write(dir.join(CACHE_ENTRIES_FILE), &entries).expect("entries");
+        let legacy = ManifestV1 {
+            version: V1_CACHE_VERSION,
+            contents_count: 0,
+            entries_count: 1,
+        };
+        fs::write(dir.join(CACHE_MANIFEST_FILE), encode_bin(&legacy).expect("encode")).expect("manifest");
+
+        let (report, verified) = inspect_cache_dir(&dir, "key", false);
+        assert_eq!(report.status, "outdated");
+        assert!(verified.is_none());
+
+        migrate_runtime_cache(&dir).expect("migrate");
+        assert!(!dir.join(CACHE_ENTRIES_FILE).exists());
+        let (report, verified) = inspect_cache_dir(&dir, "key", true);
+        // Legacy caches predate the current parser and search schema, so they are rebuilt.
+        assert_eq!(report.status, "outdated");
+        assert!(
+            report.problems.len() == 2
+                && report.problems[0].starts_with("written by parser v1")
+                && report.problems[1].starts_with("search index schema v1"),
+            "{:?}",
+            report.problems
+        );
+        assert_eq!(report.version, Some(RUNTIME_CACHE_VERSION));
+        assert!(report.files.len() == 3 && report.files.iter().all(|f| f.ok));
+        let (manifest, files) = verified.expect("verified");
+        let persisted = decode_persisted(&dir, &manifest, files).expect("decode");
+        assert!(persisted.contents.is_empty());
+        let entry = persisted.entries.by_id(1).expect("mapped entry");
+        assert_eq!((entry.headword(), entry.headword_key()), ("Straße", "strasse"));
+        assert_eq!(entry.to_detail().encoding, V1_ENCODING);
+        assert_eq!(entry.aliases().collect::<Vec<_>>(), ["Strasse"]);
+        assert_eq!(entry.gloss_keys().collect::<Vec<_>>(), [("길", "ㄱㅣㄹ", "ㄱ")]);
+        assert_eq!(persisted.headwords.prefix("STRA"), [1]);
+        // Release the mapped entry index and FST before damaging them.
+        drop(persisted);
+
+        let mut index = fs::read(dir.join(CACHE_ENTRY_INDEX_FILE)).expect("index");
+        let last = index.len() - 1;
+        index[last] ^= 0xff;
+        fs::write(dir.join(CACHE_ENTRY_INDEX_FILE), &index).expect("corrupt");
+        let (report, _) = inspect_cache_dir(&dir, "key", false);
+        assert_eq!(report.status, "outdated", "same-length damage is only caught by a deep check");
+        let (report, verified) = inspect_cache_dir(&dir, "key", true);
+        assert_eq!(report.status, "corrupt");
+        assert!(verified.is_none());
+        assert!(!report.files.iter().find(|f| f.name == CACHE_ENTRY_INDEX_FILE).expect("entries").ok);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn gc_removes_only_orphaned_cache_dirs() {
+        let root = std::env::temp_dir().join(format!("dokhan-gc-{}", std::process::id()));
+        let cache_root = root.join(RUNTIME_CACHE_DIR);
+        let zip_dir = root.join(MANAGED_ZIP_DIR);
+        fs::create_dir_all(cache_root.join("live").join(SEARCH_INDEX_DIR)).expect("live dir");
+        fs::create_dir_all(cache_root.join("gone").join(SEARCH_INDEX_DIR)).expect("orphan dir");
+        fs::write(cache_root.join("gone").join(CACHE_MANIFEST_FILE), [0u8; 10]).expect("orphan manifest");
+        fs::create_dir_all(&zip_dir).expect("zip dir");
+        fs::write(zip_dir.join("live.zip"), b"zip").expect("zip");
+        fs::write(zip_dir.join("half-copied.tmp"), [0u8; 5]).expect("tmp");
+
+        let live = BTreeSet::from(["live".to_string()]);
+        let report = collect_orphans_in(&cache_root, &zip_dir, &live).expect("gc");
+        assert_eq!(report.removed, ["gone", "half-copied.tmp"]);
+        assert_eq!(report.freed_bytes, 15);
+        assert!(cache_root.join("live").join(SEARCH_INDEX_DIR).exists());
+        assert!(zip_dir.join("live.zip").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,213 @@
+//! Tantivy tokenizer for mixed Korean and German dictionary text.
+//!
+//! Latin-script words are lowercased and German-folded like
+//! [`normalize_search_key`](crate::runtime::search::normalize_search_key). Hangul runs become
+//! syllable bigrams, so `사과` matches inside `사과를` and `사과나무`. Trailing particles are
+//! handled asymmetrically: indexing keeps every bigram and adds a one-syllable stem (`배를` → `배`),
+//! while queries drop a particle when at least two syllables remain (`사과를` → `사과`).
+
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+use crate::parsing::text::is_hangul;
+use crate::runtime::search::fold_german;
+
+/// Name the tokenizer is registered under for the `aliases` and `body` fields.
+pub(crate) const KOREAN_TOKENIZER: &str = "dokhan_ko";
+/// Longer Latin tokens are dropped, as by Tantivy's default analyzer.
+const MAX_TOKEN_BYTES: usize = 40;
+
+/// Case and postposition particles, longest first.
+const PARTICLES: &[&str] = &[
+    "에서는", "에게는", "으로는", "에서", "에게", "으로", "까지", "부터", "처럼", "보다", "한테", "께서", "이나",
+    "이랑", "마다", "조차", "에는", "에도", "은", "는", "이", "가", "을", "를", "의", "에", "로", "와", "과", "도",
+    "만", "나", "랑",
+];
+
+/// Whether the tokenizer analyzes documents or query text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenizerMode {
+    Index,
+    Query,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DokhanTokenizer {
+    mode: TokenizerMode,
+}
+
+impl DokhanTokenizer {
+    pub(crate) fn new(mode: TokenizerMode) -> Self {
+        Self { mode }
+    }
+}
+
+/// Pre-computed tokens of one text.
+pub(crate) struct DokhanTokenStream {
+    tokens: Vec<Token>,
+    cursor: usize,
+}
+
+impl TokenStream for DokhanTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.cursor < self.tokens.len() {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.cursor - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.cursor - 1]
+    }
+}
+
+/// Number of syllables left after removing a trailing particle, if one ends `run`.
+fn particle_stem_len(run: &[(usize, char)]) -> Option<usize> {
+    PARTICLES.iter().find_map(|particle| {
+        let len = particle.chars().count();
+        let stem = run.len().checked_sub(len).filter(|stem| *stem >= 1)?;
+        run[stem..].iter().map(|(_, c)| *c).eq(particle.chars()).then_some(stem)
+    })
+}
+
+/// `word` without a trailing particle when at least two syllables remain (`사과를` → `사과`).
+pub(crate) fn strip_particle(word: &str) -> &str {
+    let run = word.char_indices().collect::<Vec<_>>();
+    match particle_stem_len(&run) {
+        Some(len) if len >= 2 => &word[..run[len].0],
+        _ => word,
+    }
+}
+
+struct TokenSink {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl TokenSink {
+    fn push(&mut self, run: &[(usize, char)], position: usize) {
+        let (Some((from, _)), Some((last, c))) = (run.first(), run.last()) else {
+            return;
+        };
+        self.tokens.push(Token {
+            offset_from: *from,
+            offset_to: last + c.len_utf8(),
+            position,
+            text: run.iter().map(|(_, c)| *c).collect(),
+            position_length: 1,
+        });
+    }
+
+    /// Syllable bigrams of `run`, or the syllable itself for a one-syllable run.
+    fn push_ngrams(&mut self, run: &[(usize, char)]) {
+        if run.len() == 1 {
+            self.push(run, self.position);
+            self.position += 1;
+            return;
+        }
+        for pair in run.windows(2) {
+            self.push(pair, self.position);
+            self.position += 1;
+        }
+    }
+
+    fn push_hangul(&mut self, run: &[(usize, char)], mode: TokenizerMode) {
+        let stem = particle_stem_len(run);
+        match mode {
+            TokenizerMode::Index => {
+                let start = self.position;
+                self.push_ngrams(run);
+                if stem == Some(1) {
+                    self.push(&run[..1], start);
+                }
+            }
+            TokenizerMode::Query => {
+                let len = stem.filter(|len| *len >= 2).unwrap_or(run.len());
+                self.push_ngrams(&run[..len]);
+            }
+        }
+    }
+
+    fn push_word(&mut self, text: &str, from: usize, to: usize) {
+        let folded = fold_german(&text[from..to]);
+        if folded.len() <= MAX_TOKEN_BYTES {
+            self.tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: self.position,
+                text: folded,
+                position_length: 1,
+            });
+        }
+        self.position += 1;
+    }
+}
+
+impl Tokenizer for DokhanTokenizer {
+    type TokenStream<'a> = DokhanTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> DokhanTokenStream {
+        let mut sink = TokenSink { tokens: Vec::new(), position: 0 };
+        let mut hangul = Vec::new();
+        let mut word_start = None;
+        for (at, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+            if is_hangul(c) {
+                hangul.push((at, c));
+            } else if !hangul.is_empty() {
+                sink.push_hangul(&hangul, self.mode);
+                hangul.clear();
+            }
+            if c.is_alphanumeric() && !is_hangul(c) {
+                word_start.get_or_insert(at);
+            } else if let Some(from) = word_start.take() {
+                sink.push_word(text, from, at);
+            }
+        }
+        DokhanTokenStream { tokens: sink.tokens, cursor: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(mode: TokenizerMode, text: &str) -> Vec<(String, usize)> {
+        let mut tokenizer = DokhanTokenizer::new(mode);
+        let mut stream = tokenizer.token_stream(text);
+        let mut out = Vec::new();
+        while let Some(token) = stream.next() {
+            out.push((token.text.clone(), token.position));
+        }
+        out
+    }
+
+    fn texts(mode: TokenizerMode, text: &str) -> Vec<String> {
+        tokens(mode, text).into_iter().map(|(t, _)| t).collect()
+    }
+
+    #[test]
+    fn splits_hangul_into_bigrams_and_folds_german() {
+        assert_eq!(
+            tokens(TokenizerMode::Index, "Straße: 사과나무, 배를"),
+            [
+                ("strasse".to_string(), 0),
+                ("사과".to_string(), 1),
+                ("과나".to_string(), 2),
+                ("나무".to_string(), 3),
+                ("배를".to_string(), 4),
+                ("배".to_string(), 4),
+            ]
+        );
+        assert_eq!(texts(TokenizerMode::Query, "사과를"), ["사과"]);
+        assert_eq!(texts(TokenizerMode::Query, "사과"), ["사과"]);
+        assert_eq!(texts(TokenizerMode::Query, "배"), ["배"]);
+        assert_eq!((strip_particle("사과를"), strip_particle("사과")), ("사과", "사과"));
+        assert_eq!(texts(TokenizerMode::Query, "Äpfel3"), ["aepfel3"]);
+        assert!(texts(TokenizerMode::Index, &"x".repeat(MAX_TOKEN_BYTES + 1)).is_empty());
+    }
+}
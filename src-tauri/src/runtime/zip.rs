@@ -1,38 +1,53 @@
 //! ZIP-backed CHM reading and runtime index construction.
 use std::collections::BTreeMap;
-use std::fs;
-use std::io::Cursor;
-use std::path::Path;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use rayon::prelude::*;
-use zip::ZipArchive;
+use zip::{CompressionMethod, ZipArchive};
 
-use crate::chm;
-use crate::app::model::{BuildProgress, ContentItem, ContentPage, EntryDetail, RuntimeIndex};
-use crate::parsing::index::{extract_index_entries_from_open_chm, parse_master_hhc_text};
+use crate::chm::{self, CacheStats, LruCache, ReadSeekSource};
+use crate::error::DokhanError;
+use crate::app::model::{
+    BuildProgress, CacheStatsReport, CacheUsage, ContentItem, ContentPage, EntryDetail, RuntimeIndex,
+};
+use crate::parsing::index::{
+    chm_declared_encoding, extract_index_entries_from_open_chm, parse_master_hhc_text,
+};
 use crate::parsing::text::{
-    compact_ws, decode_euc_kr, extract_first_bold_text, extract_html_fragments,
+    compact_ws, decode_chm_text, extract_first_bold_text, extract_html_fragments,
     sanitize_html_fragment, strip_html_tags,
 };
-use crate::runtime::link_media::read_chm_binary_object;
+use crate::runtime::cancel::{check_cancelled, CancelToken};
+use crate::runtime::link_media::{normalize_path, read_chm_binary_object};
+use crate::runtime::entries::EntryTable;
+use crate::runtime::headwords::HeadwordIndex;
+use crate::runtime::pages::{PageStore, PageStoreWriter};
 use crate::runtime::search::normalize_search_key;
 use crate::runtime::state::build_runtime_index;
 
-type ChmBytes = Arc<[u8]>;
-type ZipBytes = Arc<[u8]>;
-
-static CHM_BYTES_CACHE: OnceLock<Mutex<BTreeMap<String, ChmBytes>>> = OnceLock::new();
-static ZIP_BYTES_CACHE: OnceLock<Mutex<BTreeMap<String, ZipBytes>>> = OnceLock::new();
-static CHM_ARCHIVE_CACHE: OnceLock<Mutex<BTreeMap<String, Arc<chm::ChmArchive>>>> = OnceLock::new();
-const MAX_CHM_BYTES_CACHE_ITEMS: usize = 24;
-const MAX_CHM_ARCHIVE_CACHE_ITEMS: usize = 16;
+static CHM_ARCHIVE_CACHE: OnceLock<Mutex<LruCache<String, Arc<chm::ChmArchive>>>> = OnceLock::new();
+/// Budget for parsed archives, weighted by their resident directory and source bytes;
+/// the inflated bytes of deflate-compressed members are only held by their archive.
+const CHM_ARCHIVE_CACHE_BUDGET: usize = 192 * 1024 * 1024;
+/// Most parsed archives kept at once. Each stored member holds an open ZIP handle, so this
+/// keeps the cache well under the 256-descriptor default of macOS and iOS.
+const CHM_ARCHIVE_CACHE_MAX_ENTRIES: usize = 64;
+/// Version of the parsed contents/entries model; bump when parsing output changes so
+/// runtime caches written by an older parser are rebuilt.
+pub(crate) const RUNTIME_PARSER_VERSION: u32 = 2;
 
 /// Decode CHM page bytes into normalized content payload.
-fn decode_content_page(local: String, source_path: String, bytes: &[u8]) -> ContentPage {
-    let text = decode_euc_kr(bytes);
+fn decode_content_page(
+    local: String,
+    source_path: String,
+    bytes: &[u8],
+    chm_encoding: Option<&'static encoding_rs::Encoding>,
+) -> ContentPage {
+    let (text, encoding) = decode_chm_text(bytes, chm_encoding);
     let fragments = extract_html_fragments(&text);
     let title = fragments
         .title
@@ -49,6 +64,7 @@ fn decode_content_page(local: String, source_path: String, bytes: &[u8]) -> Cont
         title,
         body_text: b_text,
         body_html: b_html,
+        encoding: encoding.name().to_string(),
     }
 }
 
@@ -74,7 +90,7 @@ fn resolve_local_candidates(local: &str) -> Vec<String> {
 }
 
 /// Read CHM object with filename and basename fallback candidates.
-fn read_chm_object_with_candidates(chm: &mut chm::ChmArchive, local: &str) -> Option<Vec<u8>> {
+fn read_chm_object_with_candidates(chm: &chm::ChmArchive, local: &str) -> Option<Vec<u8>> {
     let candidates = resolve_local_candidates(local);
     for c in &candidates {
         if let Ok(v) = chm.read_object(c) {
@@ -92,6 +108,7 @@ fn read_chm_object_with_candidates(chm: &mut chm::ChmArchive, local: &str) -> Op
         .collect::<Vec<_>>();
     let matched_paths = chm
         .entries()
+        .ok()?
         .iter()
         .filter_map(|e| {
             let base = e.path.rsplit('/').next().unwrap_or(&e.path).to_ascii_lowercase();
@@ -129,175 +146,184 @@ fn chm_cache_key(zip_path: &Path, chm_name: &str) -> String {
     format!("{}::{}", zip_cache_prefix(zip_path), chm_basename_lower(chm_name))
 }
 
-fn get_cached_chm_bytes(zip_path: &Path, chm_name: &str) -> Result<Option<ChmBytes>, String> {
-    let cache = CHM_BYTES_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let guard = cache.lock().map_err(|_| "chm cache lock poisoned".to_string())?;
-    Ok(guard.get(&chm_cache_key(zip_path, chm_name)).cloned())
+fn chm_archive_cache() -> &'static Mutex<LruCache<String, Arc<chm::ChmArchive>>> {
+    CHM_ARCHIVE_CACHE.get_or_init(|| {
+        Mutex::new(LruCache::with_entry_limit(
+            CHM_ARCHIVE_CACHE_BUDGET,
+            CHM_ARCHIVE_CACHE_MAX_ENTRIES,
+        ))
+    })
 }
 
-fn cache_chm_bytes(zip_path: &Path, chm_name: &str, bytes: ChmBytes) -> Result<(), String> {
-    let cache = CHM_BYTES_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let mut guard = cache.lock().map_err(|_| "chm cache lock poisoned".to_string())?;
+/// Cached archive for `chm_name`, re-weighed since its listing may have been materialised.
+fn get_cached_chm_archive(zip_path: &Path, chm_name: &str) -> Result<Option<Arc<chm::ChmArchive>>, DokhanError> {
+    let mut guard = chm_archive_cache()
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("chm archive cache"))?;
     let key = chm_cache_key(zip_path, chm_name);
-    guard.insert(key, bytes);
-    while guard.len() > MAX_CHM_BYTES_CACHE_ITEMS {
-        let Some(oldest_key) = guard.keys().next().cloned() else {
-            break;
-        };
-        guard.remove(&oldest_key);
+    let archive = guard.get(&key);
+    if let Some(archive) = &archive {
+        guard.reweigh(&key, archive.resident_bytes());
     }
+    Ok(archive)
+}
+
+/// Charge a cached archive for the listing and other state it has loaded since insertion.
+fn reweigh_cached_chm_archive(zip_path: &Path, chm_name: &str, archive: &chm::ChmArchive) -> Result<(), DokhanError> {
+    chm_archive_cache()
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("chm archive cache"))?
+        .reweigh(&chm_cache_key(zip_path, chm_name), archive.resident_bytes());
     Ok(())
 }
 
-fn get_cached_chm_archive(zip_path: &Path, chm_name: &str) -> Result<Option<chm::ChmArchive>, String> {
-    let cache = CHM_ARCHIVE_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let guard = cache
+fn cache_chm_archive(zip_path: &Path, chm_name: &str, archive: Arc<chm::ChmArchive>) -> Result<(), DokhanError> {
+    let mut guard = chm_archive_cache()
         .lock()
-        .map_err(|_| "chm archive cache lock poisoned".to_string())?;
-    Ok(guard
-        .get(&chm_cache_key(zip_path, chm_name))
-        .map(|arch| (**arch).clone()))
+        .map_err(|_| DokhanError::LockPoisoned("chm archive cache"))?;
+    let weight = archive.resident_bytes();
+    guard.insert(chm_cache_key(zip_path, chm_name), archive, weight);
+    Ok(())
 }
 
-fn cache_chm_archive(zip_path: &Path, chm_name: &str, archive: chm::ChmArchive) -> Result<(), String> {
-    let cache = CHM_ARCHIVE_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    let mut guard = cache
+/// Drop every parsed archive of `zip_path`, releasing the ZIP file handles they hold.
+///
+/// # Errors
+///
+/// Returns an error when the archive cache mutex is poisoned.
+pub(crate) fn evict_chm_archives(zip_path: &Path) -> Result<(), DokhanError> {
+    let prefix = format!("{}::", zip_cache_prefix(zip_path));
+    chm_archive_cache()
         .lock()
-        .map_err(|_| "chm archive cache lock poisoned".to_string())?;
-    let key = chm_cache_key(zip_path, chm_name);
-    guard.insert(key, Arc::new(archive));
-    while guard.len() > MAX_CHM_ARCHIVE_CACHE_ITEMS {
-        let Some(oldest_key) = guard.keys().next().cloned() else {
-            break;
-        };
-        guard.remove(&oldest_key);
-    }
+        .map_err(|_| DokhanError::LockPoisoned("chm archive cache"))?
+        .remove_matching(|key| key.starts_with(&prefix));
     Ok(())
 }
 
-fn get_zip_bytes(zip_path: &Path) -> Result<ZipBytes, String> {
-    let key = zip_cache_prefix(zip_path);
-    let cache = ZIP_BYTES_CACHE.get_or_init(|| Mutex::new(BTreeMap::new()));
-    {
-        let guard = cache.lock().map_err(|_| "zip cache lock poisoned".to_string())?;
-        if let Some(found) = guard.get(&key) {
-            return Ok(Arc::clone(found));
-        }
-    }
+/// Inflate a compressed CHM member into memory.
+fn inflate_chm_member(entry: &mut zip::read::ZipFile<'_>) -> Result<Arc<[u8]>, DokhanError> {
+    let mut bytes = Vec::new();
+    std::io::copy(entry, &mut bytes)
+        .map_err(|e| DokhanError::io(format!("failed to load {} from zip", entry.name()), e))?;
+    Ok(bytes.into())
+}
 
-    let bytes = fs::read(zip_path).map_err(|e| format!("failed to read zip file: {e}"))?;
-    let shared: ZipBytes = Arc::from(bytes.into_boxed_slice());
-    let mut guard = cache.lock().map_err(|_| "zip cache lock poisoned".to_string())?;
-    guard.insert(key, Arc::clone(&shared));
-    Ok(shared)
+/// Report hit/miss/eviction counters of the LZX block and parsed-archive caches.
+pub(crate) fn get_cache_stats_impl() -> Result<CacheStatsReport, DokhanError> {
+    let archives = chm_archive_cache()
+        .lock()
+        .map_err(|_| DokhanError::LockPoisoned("chm archive cache"))?
+        .stats();
+    Ok(CacheStatsReport {
+        lzx_blocks: cache_usage(chm::lzx_block_cache_stats()),
+        archives: cache_usage(archives),
+    })
 }
 
-fn open_zip_archive_from_memory(zip_path: &Path) -> Result<ZipArchive<Cursor<ZipBytes>>, String> {
-    let zip_bytes = get_zip_bytes(zip_path)?;
-    ZipArchive::new(Cursor::new(zip_bytes)).map_err(|e| format!("failed to open zip: {e}"))
+fn cache_usage(stats: CacheStats) -> CacheUsage {
+    CacheUsage {
+        hits: stats.hits,
+        misses: stats.misses,
+        evictions: stats.evictions,
+        entries: stats.entries,
+        bytes: stats.bytes,
+        budget_bytes: stats.budget_bytes,
+    }
 }
 
-/// Read raw CHM file bytes from dataset ZIP by filename.
+/// Open a dataset ZIP read-only.
 ///
 /// # Errors
 ///
-/// Returns an error when the ZIP cannot be opened/read or the named CHM does not exist.
-pub(crate) fn read_named_chm_from_zip(zip_path: &Path, chm_name: &str) -> Result<ChmBytes, String> {
-    if let Some(cached) = get_cached_chm_bytes(zip_path, chm_name)? {
-        return Ok(cached);
-    }
+/// Returns an error when the file cannot be opened or is not a ZIP.
+pub(crate) fn open_zip_archive(zip_path: &Path) -> Result<ZipArchive<File>, DokhanError> {
+    let file = File::open(zip_path).map_err(|e| DokhanError::io("failed to read zip file", e))?;
+    ZipArchive::new(file).map_err(|e| DokhanError::zip("failed to open zip", e))
+}
 
-    let mut archive = open_zip_archive_from_memory(zip_path)?;
+/// Find the ZIP entry index of a CHM by case-insensitive basename.
+fn find_chm_member(archive: &mut ZipArchive<File>, chm_name: &str) -> Result<Option<usize>, DokhanError> {
     let target = chm_basename_lower(chm_name);
     for i in 0..archive.len() {
-        let mut entry = archive
-            .by_index(i)
-            .map_err(|e| format!("failed to read zip entry #{i}: {e}"))?;
-        if entry.is_dir() {
-            continue;
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| DokhanError::zip(format!("failed to read zip entry #{i}"), e))?;
+        if !entry.is_dir() && chm_basename_lower(entry.name()) == target {
+            return Ok(Some(i));
         }
-        let entry_base = chm_basename_lower(entry.name());
-        if entry_base != target {
-            continue;
-        }
-        let mut bytes = Vec::new();
-        std::io::copy(&mut entry, &mut bytes)
-            .map_err(|e| format!("failed to load {chm_name} from zip: {e}"))?;
-        let shared: ChmBytes = Arc::from(bytes.into_boxed_slice());
-        let _ = cache_chm_bytes(zip_path, &entry_base, Arc::clone(&shared));
-        return Ok(shared);
     }
+    Ok(None)
+}
 
-    Err(format!("chm not found in zip: {chm_name}"))
+/// Open the ZIP member at `index` as a CHM archive.
+///
+/// Stored members are read in place through a window over the ZIP file; deflated
+/// members cannot be seeked, so they are inflated into memory first.
+///
+/// # Errors
+///
+/// Returns an error when the member cannot be read or is not a valid CHM.
+fn open_chm_member(
+    zip_path: &Path,
+    archive: &mut ZipArchive<File>,
+    index: usize,
+) -> Result<chm::ChmArchive, DokhanError> {
+    let mut entry = archive
+        .by_index(index)
+        .map_err(|e| DokhanError::zip(format!("failed to read zip entry #{index}"), e))?;
+    let name = entry.name().to_string();
+    let opened = if entry.compression() == CompressionMethod::Stored {
+        let file = File::open(zip_path).map_err(|e| DokhanError::io("failed to read zip file", e))?;
+        let source = ReadSeekSource::window(file, entry.data_start(), entry.size());
+        chm::ChmArchive::open_source(Arc::new(source))
+    } else {
+        chm::ChmArchive::open(inflate_chm_member(&mut entry)?)
+    };
+    opened.map_err(|e| DokhanError::chm(format!("failed to open {name}"), e))
 }
 
-/// Open CHM archive from ZIP with parsed-archive template cache.
+/// Open a CHM archive from the dataset ZIP by filename, reusing parsed archives.
 ///
 /// # Errors
 ///
-/// Returns an error when CHM bytes cannot be loaded or archive parsing fails.
-pub(crate) fn open_named_chm_from_zip(zip_path: &Path, chm_name: &str) -> Result<chm::ChmArchive, String> {
+/// Returns an error when the ZIP cannot be read, the named CHM does not exist, or archive parsing fails.
+pub(crate) fn open_named_chm_from_zip(zip_path: &Path, chm_name: &str) -> Result<Arc<chm::ChmArchive>, DokhanError> {
     if let Some(arch) = get_cached_chm_archive(zip_path, chm_name)? {
         return Ok(arch);
     }
-    let bytes = read_named_chm_from_zip(zip_path, chm_name)?;
-    let archive = chm::ChmArchive::open(bytes).map_err(|e| format!("failed to open {chm_name}: {e}"))?;
-    let _ = cache_chm_archive(zip_path, chm_name, archive.clone());
-    Ok(archive)
+    let mut archive = open_zip_archive(zip_path)?;
+    let index = find_chm_member(&mut archive, chm_name)?
+        .ok_or_else(|| DokhanError::not_found("chm", chm_name))?;
+    let chm = Arc::new(open_chm_member(zip_path, &mut archive, index)?);
+    let _ = cache_chm_archive(zip_path, chm_name, Arc::clone(&chm));
+    Ok(chm)
 }
 
-/// Resolve entry HTML bytes using target local or headword fallback.
+/// Resolve an entry page by headword, returning its local path and bytes.
 fn read_entry_html_from_chm(
-    chm: &mut chm::ChmArchive,
+    chm: &chm::ChmArchive,
     headword: &str,
-    by_stem: Option<&BTreeMap<String, Vec<String>>>,
-) -> Option<Vec<u8>> {
+    by_stem: &BTreeMap<String, Vec<String>>,
+) -> Option<(String, Vec<u8>)> {
     for candidate in resolve_local_candidates(headword) {
         if let Ok(v) = chm.read_object(&candidate) {
-            return Some(v);
+            return Some((candidate, v));
         }
         let slash = format!("/{candidate}");
         if let Ok(v) = chm.read_object(&slash) {
-            return Some(v);
+            return Some((slash, v));
         }
     }
 
-    let wanted = normalize_search_key(headword);
-    let matches = if let Some(indexed) = by_stem {
-        indexed.get(&wanted).cloned().unwrap_or_default()
-    } else {
-        let mut scanned = chm
-            .entries()
-            .iter()
-            .filter_map(|e| {
-                let base = e.path.rsplit('/').next().unwrap_or(&e.path);
-                let stem = base.rsplit_once('.').map_or(base, |(stem, _)| stem);
-                let ext = base
-                    .rsplit_once('.')
-                    .map(|(_, x)| x.to_ascii_lowercase())
-                    .unwrap_or_default();
-                if (ext == "htm" || ext == "html") && normalize_search_key(stem) == wanted {
-                    Some(e.path.clone())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-        scanned.sort();
-        scanned.dedup();
-        scanned
-    };
-    for path in matches {
-        if let Ok(v) = chm.read_object(&path) {
-            return Some(v);
-        }
-    }
-    None
+    let matches = by_stem.get(&normalize_search_key(headword))?;
+    matches
+        .iter()
+        .find_map(|path| chm.read_object(path).ok().map(|v| (path.clone(), v)))
 }
 
-fn build_html_path_index(chm: &chm::ChmArchive) -> BTreeMap<String, Vec<String>> {
+fn build_html_path_index(chm: &chm::ChmArchive) -> Result<BTreeMap<String, Vec<String>>, chm::ChmError> {
     let mut by_stem = BTreeMap::<String, Vec<String>>::new();
-    for entry in chm.entries() {
+    for entry in chm.entries()? {
         let base = entry.path.rsplit('/').next().unwrap_or(&entry.path);
         let Some((stem, ext)) = base.rsplit_once('.') else {
             continue;
@@ -315,57 +341,93 @@ fn build_html_path_index(chm: &chm::ChmArchive) -> BTreeMap<String, Vec<String>>
         paths.sort();
         paths.dedup();
     }
-    by_stem
+    Ok(by_stem)
 }
 
-fn hydrate_entries_from_open_chm(chm: &mut chm::ChmArchive, entries: &mut [EntryDetail]) {
-    let path_index = build_html_path_index(&chm);
+/// Fill an entry's definition and aliases from its page HTML.
+fn apply_entry_html(entry: &mut EntryDetail, html_text: &str) {
+    let fragments = extract_html_fragments(html_text);
+    let paragraph_html = fragments.first_paragraph_html.unwrap_or_default();
+    let paragraph_text = compact_ws(&strip_html_tags(&paragraph_html));
+    let body = fragments.body_html.unwrap_or_default();
+    let body_text = compact_ws(&strip_html_tags(&body));
+
+    if !paragraph_html.is_empty() {
+        entry.definition_html = sanitize_html_fragment(&paragraph_html);
+    } else if !body.is_empty() {
+        entry.definition_html = sanitize_html_fragment(&body);
+    }
+    if !paragraph_text.is_empty() {
+        entry.definition_text = paragraph_text;
+    } else if !body_text.is_empty() {
+        entry.definition_text = body_text;
+    }
+
+    if let Some(title_alias) = fragments
+        .title
+        .as_ref()
+        .map(|x| compact_ws(&strip_html_tags(x)))
+        .filter(|x| !x.is_empty())
+    {
+        if !entry.aliases.contains(&title_alias) {
+            entry.aliases.push(title_alias);
+        }
+    }
+    if let Some(bold) = extract_first_bold_text(html_text) {
+        let bold = compact_ws(&bold);
+        if !bold.is_empty() && !entry.aliases.contains(&bold) {
+            entry.aliases.push(bold);
+        }
+    }
+}
+
+/// Fill entry bodies from their CHM pages, storing every resolved page in `pages`.
+///
+/// Entries found by headword are pointed at the page they resolved to, so later views
+/// are served from the page store.
+///
+/// # Errors
+///
+/// Returns an error when the CHM directory listing is corrupt.
+fn hydrate_entries_from_open_chm(
+    chm: &chm::ChmArchive,
+    entries: &mut [EntryDetail],
+    pages: Option<&PageStoreWriter>,
+) -> Result<(), chm::ChmError> {
+    let path_index = build_html_path_index(chm)?;
+    let declared = chm_declared_encoding(chm);
     for entry in entries.iter_mut() {
-        let html_bytes = if entry.target_local.is_empty() {
-            read_entry_html_from_chm(chm, &entry.headword, Some(&path_index))
+        let direct = if entry.target_local.is_empty() {
+            None
         } else {
             read_chm_binary_object(chm, &entry.target_local)
-                .or_else(|| read_entry_html_from_chm(chm, &entry.headword, Some(&path_index)))
         };
-        let Some(html_bytes) = html_bytes else {
-            continue;
+        let html_bytes = match direct {
+            Some(bytes) => bytes,
+            None => match read_entry_html_from_chm(chm, &entry.headword, &path_index) {
+                Some((local, bytes)) => {
+                    entry.target_local = local;
+                    bytes
+                }
+                None => continue,
+            },
         };
-
-        let html_text = decode_euc_kr(&html_bytes);
-        let fragments = extract_html_fragments(&html_text);
-        let paragraph_html = fragments.first_paragraph_html.unwrap_or_default();
-        let paragraph_text = compact_ws(&strip_html_tags(&paragraph_html));
-        let body = fragments.body_html.unwrap_or_default();
-        let body_text = compact_ws(&strip_html_tags(&body));
-
-        if !paragraph_html.is_empty() {
-            entry.definition_html = sanitize_html_fragment(&paragraph_html);
-        } else if !body.is_empty() {
-            entry.definition_html = sanitize_html_fragment(&body);
-        }
-        if !paragraph_text.is_empty() {
-            entry.definition_text = paragraph_text;
-        } else if !body_text.is_empty() {
-            entry.definition_text = body_text;
-        }
-
-        if let Some(title_alias) = fragments
-            .title
-            .as_ref()
-            .map(|x| compact_ws(&strip_html_tags(x)))
-            .filter(|x| !x.is_empty())
-        {
-            if !entry.aliases.contains(&title_alias) {
-                entry.aliases.push(title_alias);
-            }
-        }
-        if let Some(bold) = extract_first_bold_text(&html_text) {
-            let bold = compact_ws(&bold);
-            if !bold.is_empty() && !entry.aliases.contains(&bold) {
-                entry.aliases.push(bold);
+        if let Some(pages) = pages {
+            if !pages.contains(&entry.source_path, &entry.target_local) {
+                pages.insert(&decode_content_page(
+                    entry.target_local.clone(),
+                    entry.source_path.clone(),
+                    &html_bytes,
+                    declared,
+                ));
             }
         }
+
+        let (html_text, encoding) = decode_chm_text(&html_bytes, declared);
+        entry.encoding = encoding.name().to_string();
+        apply_entry_html(entry, &html_text);
     }
+    Ok(())
 }
 
 fn finalize_entries(mut entries: Vec<EntryDetail>) -> Vec<EntryDetail> {
@@ -394,6 +456,42 @@ fn finalize_entries(mut entries: Vec<EntryDetail>) -> Vec<EntryDetail> {
     out
 }
 
+/// Extract and hydrate index entries from one CHM member; unreadable CHMs yield nothing.
+///
+/// The opened archive is kept in the archive cache so later lookups reuse the blocks
+/// decompressed here.
+fn parse_chm_member(
+    zip_path: &Path,
+    zip: &mut ZipArchive<File>,
+    name: &str,
+    index: usize,
+    pages: Option<&PageStoreWriter>,
+) -> Vec<EntryDetail> {
+    let chm = match get_cached_chm_archive(zip_path, name) {
+        Ok(Some(chm)) => chm,
+        _ => {
+            let Ok(chm) = open_chm_member(zip_path, zip, index) else {
+                return Vec::new();
+            };
+            let chm = Arc::new(chm);
+            let _ = cache_chm_archive(zip_path, name, Arc::clone(&chm));
+            chm
+        }
+    };
+    // A CHM with a corrupt directory is skipped like one that fails to open.
+    let parsed = extract_index_entries_from_open_chm(name, &chm)
+        .and_then(|mut parsed| {
+            hydrate_entries_from_open_chm(&chm, &mut parsed, pages)?;
+            if let Some(pages) = pages {
+                store_html_pages(&chm, &chm_basename_lower(name), pages)?;
+            }
+            Ok(parsed)
+        })
+        .unwrap_or_default();
+    let _ = reweigh_cached_chm_archive(zip_path, name, &chm);
+    parsed
+}
+
 fn recommended_parse_threads(task_count: usize) -> usize {
     let available = std::thread::available_parallelism()
         .map(|n| n.get())
@@ -422,57 +520,52 @@ fn emit_progress_throttled(
     *last_emit = Instant::now();
 }
 
-/// Fill empty entry body fields by reading original CHM HTML.
-pub(crate) fn hydrate_zip_entry_detail(zip_path: &Path, mut entry: EntryDetail) -> EntryDetail {
+/// Fill empty entry body fields from the entry's page in the page store.
+///
+/// Entries without a stored page (no store, a migrated cache, or a page that failed to
+/// write) are read from the CHM, by target local or else by headword.
+pub(crate) fn hydrate_entry_detail(zip_path: &Path, pages: Option<&PageStore>, mut entry: EntryDetail) -> EntryDetail {
     if !entry.definition_text.is_empty() {
         return entry;
     }
-    let Ok(mut chm) = open_named_chm_from_zip(zip_path, &entry.source_path) else {
-        return entry;
-    };
-    let html_bytes = if entry.target_local.is_empty() {
-        read_entry_html_from_chm(&mut chm, &entry.headword, None)
+    let stored = if entry.target_local.is_empty() {
+        None
     } else {
-        read_chm_binary_object(&mut chm, &entry.target_local)
-            .or_else(|| read_entry_html_from_chm(&mut chm, &entry.headword, None))
+        pages.and_then(|pages| pages.get(&entry.source_path, &entry.target_local).ok().flatten())
     };
-    let Some(html_bytes) = html_bytes else {
+    if let Some(page) = stored {
+        entry.encoding = page.encoding;
+        apply_entry_html(&mut entry, &page.body_html);
+        if entry.definition_text.is_empty() {
+            entry.definition_html = page.body_html;
+            entry.definition_text = page.body_text;
+        }
         return entry;
-    };
-
-    let html_text = decode_euc_kr(&html_bytes);
-    let fragments = extract_html_fragments(&html_text);
-    let paragraph_html = fragments.first_paragraph_html.unwrap_or_default();
-    let paragraph_text = compact_ws(&strip_html_tags(&paragraph_html));
-    let body = fragments.body_html.unwrap_or_default();
-    let body_text = compact_ws(&strip_html_tags(&body));
-    if !paragraph_html.is_empty() {
-        entry.definition_html = sanitize_html_fragment(&paragraph_html);
-    } else if !body.is_empty() {
-        entry.definition_html = sanitize_html_fragment(&body);
-    }
-    if !paragraph_text.is_empty() {
-        entry.definition_text = paragraph_text;
-    } else if !body_text.is_empty() {
-        entry.definition_text = body_text;
     }
 
-    if let Some(title_alias) = fragments
-        .title
-        .as_ref()
-        .map(|x| compact_ws(&strip_html_tags(x)))
-        .filter(|x| !x.is_empty())
-    {
-        if !entry.aliases.contains(&title_alias) {
-            entry.aliases.push(title_alias);
-        }
-    }
-    if let Some(bold) = extract_first_bold_text(&html_text) {
-        let bold = compact_ws(&bold);
-        if !bold.is_empty() && !entry.aliases.contains(&bold) {
-            entry.aliases.push(bold);
+    let Ok(chm) = open_named_chm_from_zip(zip_path, &entry.source_path) else {
+        return entry;
+    };
+    let direct = if entry.target_local.is_empty() {
+        None
+    } else {
+        read_chm_object_with_candidates(&chm, &entry.target_local)
+    };
+    let html_bytes = match direct {
+        Some(bytes) => bytes,
+        None => {
+            let Ok(path_index) = build_html_path_index(&chm) else {
+                return entry;
+            };
+            let Some((_, bytes)) = read_entry_html_from_chm(&chm, &entry.headword, &path_index) else {
+                return entry;
+            };
+            bytes
         }
-    }
+    };
+    let (html_text, encoding) = decode_chm_text(&html_bytes, chm_declared_encoding(&chm));
+    entry.encoding = encoding.name().to_string();
+    apply_entry_html(&mut entry, &html_text);
     entry
 }
 
@@ -485,60 +578,219 @@ pub(crate) fn read_content_page_from_zip(
     zip_path: &Path,
     source_path: &str,
     local: &str,
-) -> Result<ContentPage, String> {
-    let mut chm = open_named_chm_from_zip(zip_path, source_path)?;
-    if let Some(v) = read_chm_object_with_candidates(&mut chm, local) {
-        return Ok(decode_content_page(local.to_string(), source_path.to_string(), &v));
+) -> Result<ContentPage, DokhanError> {
+    let chm = open_named_chm_from_zip(zip_path, source_path)?;
+    if let Some(v) = read_chm_object_with_candidates(&chm, local) {
+        let declared = chm_declared_encoding(&chm);
+        return Ok(decode_content_page(local.to_string(), source_path.to_string(), &v, declared));
+    }
+    Err(DokhanError::not_found("content page", format!("{source_path}::{local}")))
+}
+
+/// Topic hit from a CHM's built-in `$FIftiMain` index.
+#[derive(Debug, Clone)]
+pub(crate) struct FullTextTopic {
+    pub(crate) source_path: String,
+    pub(crate) local: String,
+    pub(crate) score: usize,
+}
+
+/// `$FIftiMain` index of one CHM, opened together with the archive it reads from.
+#[derive(Debug)]
+struct ChmFullText {
+    source_path: String,
+    chm: Arc<chm::ChmArchive>,
+    index: chm::FullTextIndex,
+    encoding: &'static encoding_rs::Encoding,
+}
+
+impl ChmFullText {
+    /// Score topics that contain every term, keeping the best `limit`.
+    fn search(&self, terms: &[String], limit: usize) -> Vec<(String, usize)> {
+        let mut scores: Option<BTreeMap<u32, usize>> = None;
+        for term in terms {
+            let Ok(matches) = self.index.search(&self.chm, term, true) else {
+                return Vec::new();
+            };
+            let mut term_scores = BTreeMap::<u32, usize>::new();
+            for m in matches {
+                let title_bonus = if m.in_title { 5 } else { 0 };
+                for hit in m.topics {
+                    *term_scores.entry(hit.topic).or_default() += hit.locations.len().max(1) + title_bonus;
+                }
+            }
+            scores = Some(match scores {
+                None => term_scores,
+                Some(prev) => prev
+                    .into_iter()
+                    .filter_map(|(topic, score)| term_scores.get(&topic).map(|s| (topic, score + s)))
+                    .collect(),
+            });
+        }
+
+        let mut ranked = scores.unwrap_or_default().into_iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+            .into_iter()
+            .filter_map(|(topic, score)| {
+                let local = chm::system::read_topic(&self.chm, topic, self.encoding).ok()??.local?;
+                Some((normalize_path(&local), score))
+            })
+            .collect()
+    }
+}
+
+/// Publisher `$FIftiMain` indexes of a dataset's CHMs, opened once and queried repeatedly.
+#[derive(Debug)]
+pub(crate) struct ZipFullText {
+    indexes: Vec<ChmFullText>,
+}
+
+impl ZipFullText {
+    /// Open the full-text index of each named CHM.
+    ///
+    /// CHMs compiled without a full-text index, or with one that fails to decode, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a CHM cannot be loaded from the ZIP.
+    pub(crate) fn open(zip_path: &Path, chm_names: &[String]) -> Result<Self, DokhanError> {
+        let mut indexes = Vec::new();
+        for name in chm_names {
+            let chm = open_named_chm_from_zip(zip_path, name)?;
+            let encoding = chm_declared_encoding(&chm).unwrap_or(encoding_rs::EUC_KR);
+            let Ok(Some(index)) = chm::FullTextIndex::open(&chm, encoding) else {
+                continue;
+            };
+            indexes.push(ChmFullText {
+                source_path: chm_basename_lower(name),
+                chm,
+                index,
+                encoding,
+            });
+        }
+        Ok(Self { indexes })
+    }
+
+    /// Topics containing every term, up to `limit` per CHM.
+    pub(crate) fn search(&self, terms: &[String], limit: usize) -> Vec<FullTextTopic> {
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        for fulltext in &self.indexes {
+            for (local, score) in fulltext.search(terms, limit) {
+                out.push(FullTextTopic {
+                    source_path: fulltext.source_path.clone(),
+                    local,
+                    score,
+                });
+            }
+        }
+        out
+    }
+}
+
+/// Decode every table-of-contents page of `master.chm` into the page store.
+fn store_content_pages(chm: &chm::ChmArchive, source_path: &str, contents: &[ContentItem], pages: &PageStoreWriter) {
+    let declared = chm_declared_encoding(chm);
+    for item in contents {
+        if item.local.is_empty() || pages.contains(source_path, &item.local) {
+            continue;
+        }
+        if let Some(bytes) = read_chm_object_with_candidates(chm, &item.local) {
+            pages.insert(&decode_content_page(
+                item.local.clone(),
+                source_path.to_string(),
+                &bytes,
+                declared,
+            ));
+        }
+    }
+}
+
+/// Decode every HTML object of `chm` that is not stored yet into the page store.
+///
+/// Pages reached only through links inside other pages are covered this way too.
+///
+/// # Errors
+///
+/// Returns an error when the CHM directory listing is corrupt.
+fn store_html_pages(chm: &chm::ChmArchive, source_path: &str, pages: &PageStoreWriter) -> Result<(), chm::ChmError> {
+    let declared = chm_declared_encoding(chm);
+    for entry in chm.entries()? {
+        let lower = entry.path.to_ascii_lowercase();
+        if !(lower.ends_with(".htm") || lower.ends_with(".html")) || pages.contains(source_path, &entry.path) {
+            continue;
+        }
+        if let Ok(bytes) = chm.read_object(&entry.path) {
+            pages.insert(&decode_content_page(
+                entry.path.trim_start_matches('/').to_string(),
+                source_path.to_string(),
+                &bytes,
+                declared,
+            ));
+        }
     }
-    Err(format!(
-        "content page not found in zip runtime: {source_path}::{local}"
-    ))
+    Ok(())
 }
 
 /// Parse full runtime index from ZIP and emit progress events.
 ///
 /// The callback receives best-effort progress snapshots during CHM iteration.
+/// `cancel` is polled per ZIP entry while scanning and per CHM in the parse workers.
+/// When `page_store` is given, every HTML page of `master.chm` and the merge CHMs is
+/// written there so page views never reopen the CHM.
 ///
 /// # Errors
 ///
-/// Returns an error when ZIP/CHM reading fails during runtime construction.
+/// Returns an error when ZIP/CHM reading fails during runtime construction or the page store
+/// cannot be written, or [`DokhanError::Cancelled`] once `cancel` fires.
 pub(crate) fn parse_runtime_from_zip_with_progress(
     zip_path: &Path,
     mut progress: Option<&mut dyn FnMut(BuildProgress)>,
-) -> Result<RuntimeIndex, String> {
-    let mut archive = open_zip_archive_from_memory(zip_path)?;
+    cancel: Option<&CancelToken>,
+    page_store: Option<&Path>,
+) -> Result<RuntimeIndex, DokhanError> {
+    let mut archive = open_zip_archive(zip_path)?;
+    let page_writer = page_store
+        .map(PageStoreWriter::create)
+        .transpose()?
+        .map(Arc::new);
     let total = archive.len();
     let mut contents = Vec::<ContentItem>::new();
-    let mut merge_chms = Vec::<(String, ChmBytes)>::new();
+    let mut merge_chms = Vec::<(String, usize)>::new();
     let mut progress_last_emit = Instant::now();
     let progress_interval = Duration::from_millis(120);
 
     for i in 0..total {
-        let mut entry = archive
-            .by_index(i)
-            .map_err(|e| format!("failed to read zip entry #{i}: {e}"))?;
-        if entry.is_dir() {
-            continue;
-        }
-        let name = entry.name().to_string();
+        check_cancelled(cancel)?;
+        let name = {
+            let entry = archive
+                .by_index_raw(i)
+                .map_err(|e| DokhanError::zip(format!("failed to read zip entry #{i}"), e))?;
+            if entry.is_dir() {
+                continue;
+            }
+            entry.name().to_string()
+        };
         let lower = name.to_ascii_lowercase();
         if !lower.ends_with(".chm") {
             continue;
         }
-        let mut bytes = Vec::new();
-        std::io::copy(&mut entry, &mut bytes).map_err(|e| format!("failed to load {name}: {e}"))?;
-        let shared: ChmBytes = Arc::from(bytes.into_boxed_slice());
-        let entry_base = chm_basename_lower(&name);
-        let _ = cache_chm_bytes(zip_path, &entry_base, Arc::clone(&shared));
 
         if lower.ends_with("master.chm") {
-            if let Ok(mut chm) = chm::ChmArchive::open(Arc::clone(&shared)) {
-                if let Some(hhc) = read_chm_object_with_candidates(&mut chm, "master.hhc") {
-                    let text = decode_euc_kr(&hhc);
-                    let parsed = parse_master_hhc_text(&text);
-                    if !parsed.is_empty() {
-                        contents = parsed;
-                    }
+            let master = open_chm_member(zip_path, &mut archive, i).ok();
+            if let Some(hhc) = master
+                .as_ref()
+                .and_then(|chm| read_chm_object_with_candidates(chm, "master.hhc"))
+            {
+                let declared = master.as_ref().and_then(chm_declared_encoding);
+                let (text, _) = decode_chm_text(&hhc, declared);
+                let parsed = parse_master_hhc_text(&text);
+                if !parsed.is_empty() {
+                    contents = parsed;
                 }
             }
             if contents.is_empty() {
@@ -547,6 +799,10 @@ pub(crate) fn parse_runtime_from_zip_with_progress(
                     local: "master".to_string(),
                 });
             }
+            if let (Some(chm), Some(pages)) = (master.as_ref(), page_writer.as_deref()) {
+                store_content_pages(chm, &chm_basename_lower(&name), &contents, pages);
+                let _ = store_html_pages(chm, &chm_basename_lower(&name), pages);
+            }
         }
 
         emit_progress_throttled(
@@ -563,7 +819,7 @@ pub(crate) fn parse_runtime_from_zip_with_progress(
         );
 
         if lower.starts_with("merge") {
-            merge_chms.push((name, shared));
+            merge_chms.push((name, i));
         }
     }
     let parse_total = merge_chms.len();
@@ -585,27 +841,35 @@ pub(crate) fn parse_runtime_from_zip_with_progress(
     );
 
     let (tx, rx) = mpsc::channel::<usize>();
+    let zip_path_owned: PathBuf = zip_path.to_path_buf();
+    let worker_cancel = cancel.cloned();
+    let worker_pages = page_writer.clone();
     let worker = std::thread::spawn(move || {
         let pool = rayon::ThreadPoolBuilder::new()
             .num_threads(parse_threads)
             .build()
-            .map_err(|e| format!("failed to build parse thread pool: {e}"))?;
+            .map_err(|e| DokhanError::Internal(format!("failed to build parse thread pool: {e}")))?;
         let rows = pool.install(|| {
             merge_chms
                 .into_par_iter()
-                .map(|(name, bytes)| {
-                    let Ok(mut chm) = chm::ChmArchive::open(bytes) else {
+                .map_init(
+                    || open_zip_archive(&zip_path_owned).ok(),
+                    |zip, (name, index)| {
+                        if check_cancelled(worker_cancel.as_ref()).is_err() {
+                            let _ = tx.send(1);
+                            return Vec::new();
+                        }
+                        let parsed = zip
+                            .as_mut()
+                            .map(|zip| parse_chm_member(&zip_path_owned, zip, &name, index, worker_pages.as_deref()))
+                            .unwrap_or_default();
                         let _ = tx.send(1);
-                        return Vec::new();
-                    };
-                    let mut parsed = extract_index_entries_from_open_chm(&name, &mut chm);
-                    hydrate_entries_from_open_chm(&mut chm, &mut parsed);
-                    let _ = tx.send(1);
-                    parsed
-                })
+                        parsed
+                    },
+                )
                 .collect::<Vec<_>>()
         });
-        Ok::<Vec<Vec<EntryDetail>>, String>(rows)
+        Ok::<Vec<Vec<EntryDetail>>, DokhanError>(rows)
     });
 
     let mut parsed_done = 0usize;
@@ -633,7 +897,8 @@ pub(crate) fn parse_runtime_from_zip_with_progress(
 
     let parsed_chunks = worker
         .join()
-        .map_err(|_| "multithreaded parse worker panicked".to_string())??;
+        .map_err(|_| DokhanError::Internal("multithreaded parse worker panicked".to_string()))??;
+    check_cancelled(cancel)?;
 
     let mut entries = Vec::<EntryDetail>::new();
     for mut chunk in parsed_chunks {
@@ -653,6 +918,191 @@ pub(crate) fn parse_runtime_from_zip_with_progress(
         true,
     );
 
-    let entries = finalize_entries(entries);
-    Ok(build_runtime_index(contents, entries, BTreeMap::new()))
+    let entries = EntryTable::owned(finalize_entries(entries));
+    let headwords = Arc::new(HeadwordIndex::build(&entries)?);
+    // The worker has exited, so this is the last handle to the writer.
+    let pages = page_writer
+        .map(|writer| {
+            Arc::into_inner(writer)
+                .ok_or_else(|| DokhanError::Internal("page store writer is still shared".to_string()))?
+                .finish()
+        })
+        .transpose()?
+        .map(Arc::new);
+    Ok(build_runtime_index(contents, entries, headwords, pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    use crate::chm::test_support::build_indexed_container;
+
+    #[test]
+    fn opens_stored_and_deflated_chm_members() {
+        let container = build_indexed_container(&[("/page.htm", b"<p>stored member</p>")], 0x200);
+        let zip_path = std::env::temp_dir().join(format!("dokhan-zip-member-{}.zip", std::process::id()));
+        {
+            let file = File::create(&zip_path).expect("create zip");
+            let mut writer = zip::ZipWriter::new(file);
+            for (name, method) in [
+                ("dict/stored.chm", CompressionMethod::Stored),
+                ("dict/deflated.chm", CompressionMethod::Deflated),
+            ] {
+                writer
+                    .start_file(name, FileOptions::default().compression_method(method))
+                    .expect("start member");
+                writer.write_all(&container).expect("write member");
+            }
+            writer.finish().expect("finish zip");
+        }
+
+        for name in ["STORED.chm", "deflated.chm"] {
+            let mut archive = open_zip_archive(&zip_path).expect("open zip");
+            let index = find_chm_member(&mut archive, name)
+                .expect("scan zip")
+                .expect("member present");
+            let chm = open_chm_member(&zip_path, &mut archive, index).expect("open member");
+            assert_eq!(chm.read_object("/page.htm").expect("read page"), b"<p>stored member</p>");
+        }
+        let mut archive = open_zip_archive(&zip_path).expect("open zip");
+        assert_eq!(find_chm_member(&mut archive, "missing.chm").expect("scan zip"), None);
+
+        open_named_chm_from_zip(&zip_path, "stored.chm").expect("cache member");
+        assert!(get_cached_chm_archive(&zip_path, "stored.chm").expect("cache").is_some());
+        evict_chm_archives(&zip_path).expect("evict");
+        assert!(get_cached_chm_archive(&zip_path, "stored.chm").expect("cache").is_none());
+        let _ = std::fs::remove_file(&zip_path);
+    }
+
+    #[test]
+    fn parse_fills_page_store_with_every_html_page() {
+        let hhc = br#"<OBJECT type="text/sitemap"><param name="Name" value="Intro"><param name="Local" value="intro.htm"></OBJECT>"#;
+        let container = build_indexed_container(
+            &[
+                ("/master.hhc", hhc),
+                ("/intro.htm", b"<html><head><title>Intro</title></head><body><p>Willkommen</p></body></html>"),
+                ("/Linked.htm", b"<html><head><title>Linked</title></head><body><p>Nur verlinkt</p></body></html>"),
+            ],
+            0x200,
+        );
+        let dir = std::env::temp_dir().join(format!("dokhan-zip-pages-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("tmp dir");
+        let zip_path = dir.join("dataset.zip");
+        {
+            let file = File::create(&zip_path).expect("create zip");
+            let mut writer = zip::ZipWriter::new(file);
+            writer.start_file("Master.chm", FileOptions::default()).expect("start member");
+            writer.write_all(&container).expect("write member");
+            writer.finish().expect("finish zip");
+        }
+
+        let pages_path = dir.join("pages.bin");
+        let runtime =
+            parse_runtime_from_zip_with_progress(&zip_path, None, None, Some(&pages_path)).expect("parse runtime");
+        assert_eq!(runtime.contents.len(), 1);
+        let pages = runtime.pages.as_ref().expect("page store");
+        let page = pages.get("master.chm", "intro.htm").expect("read").expect("stored page");
+        assert_eq!(page.title, "Intro");
+        assert!(page.body_html.contains("Willkommen"));
+        // Pages outside the table of contents, reachable only through links, are stored too.
+        let linked = pages.get("master.chm", "Linked.htm").expect("read").expect("linked page");
+        assert!(linked.body_html.contains("Nur verlinkt"));
+        assert!(pages_path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn headword_resolved_pages_are_stored_and_served_from_the_store() {
+        let container = build_indexed_container(
+            &[("/Apfel.htm", b"<html><head><title>Apfel</title></head><body><p>m. Obst</p></body></html>")],
+            0x200,
+        );
+        let chm = chm::ChmArchive::open(container).expect("open chm");
+        let mut entries = vec![EntryDetail {
+            id: 1,
+            headword: "Apfel".to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: String::new(),
+            definition_text: String::new(),
+            definition_html: String::new(),
+            encoding: "EUC-KR".to_string(),
+        }];
+        let pages_path = std::env::temp_dir().join(format!("dokhan-zip-resolved-{}.bin", std::process::id()));
+        let writer = PageStoreWriter::create(&pages_path).expect("page store");
+        hydrate_entries_from_open_chm(&chm, &mut entries, Some(&writer)).expect("hydrate");
+        let store = writer.finish().expect("finish");
+
+        let mut entry = entries.remove(0);
+        assert_eq!(entry.definition_text, "m. Obst");
+        assert!(store.get("merge01.chm", &entry.target_local).expect("read").is_some());
+        entry.definition_text.clear();
+        entry.definition_html.clear();
+        let entry = hydrate_entry_detail(Path::new("missing.zip"), Some(&store), entry);
+        assert_eq!(entry.definition_text, "m. Obst");
+        drop(store);
+        let _ = std::fs::remove_file(&pages_path);
+    }
+
+    #[test]
+    fn entries_without_a_stored_page_are_read_from_the_chm() {
+        let container = build_indexed_container(
+            &[("/Apfel.htm", b"<html><head><title>Apfel</title></head><body><p>m. Obst</p></body></html>")],
+            0x200,
+        );
+        let zip_path = std::env::temp_dir().join(format!("dokhan-zip-fallback-{}.zip", std::process::id()));
+        {
+            let file = File::create(&zip_path).expect("create zip");
+            let mut writer = zip::ZipWriter::new(file);
+            writer.start_file("merge01.chm", FileOptions::default()).expect("start member");
+            writer.write_all(&container).expect("write member");
+            writer.finish().expect("finish zip");
+        }
+        let entry = |target_local: &str| EntryDetail {
+            id: 1,
+            headword: "Apfel".to_string(),
+            aliases: Vec::new(),
+            source_path: "merge01.chm".to_string(),
+            target_local: target_local.to_string(),
+            definition_text: String::new(),
+            definition_html: String::new(),
+            encoding: "EUC-KR".to_string(),
+        };
+
+        for target_local in ["Apfel.htm", ""] {
+            let hydrated = hydrate_entry_detail(&zip_path, None, entry(target_local));
+            assert_eq!(hydrated.definition_text, "m. Obst");
+        }
+        let _ = std::fs::remove_file(&zip_path);
+    }
+
+    #[test]
+    fn cancelled_parse_stops_with_cancelled_error() {
+        let container = build_indexed_container(&[("/page.htm", b"<p>merge member</p>")], 0x200);
+        let zip_path = std::env::temp_dir().join(format!("dokhan-zip-cancel-{}.zip", std::process::id()));
+        {
+            let file = File::create(&zip_path).expect("create zip");
+            let mut writer = zip::ZipWriter::new(file);
+            for name in ["merge01.chm", "merge02.chm"] {
+                writer.start_file(name, FileOptions::default()).expect("start member");
+                writer.write_all(&container).expect("write member");
+            }
+            writer.finish().expect("finish zip");
+        }
+
+        let token = CancelToken::new();
+        token.cancel();
+        let err = parse_runtime_from_zip_with_progress(&zip_path, None, Some(&token), None)
+            .expect_err("cancelled parse");
+        assert!(matches!(err, DokhanError::Cancelled));
+        parse_runtime_from_zip_with_progress(&zip_path, None, Some(&CancelToken::new()), None).expect("uncancelled parse");
+        let unwritable = zip_path.with_extension("missing").join("pages.bin");
+        let err = parse_runtime_from_zip_with_progress(&zip_path, None, None, Some(&unwritable))
+            .expect_err("page store cannot be created");
+        assert!(matches!(err, DokhanError::Io { .. }), "{err:?}");
+        let _ = std::fs::remove_file(&zip_path);
+    }
 }
@@ -1,11 +1,22 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
+  BuildDoneEvent,
+  BuildErrorEvent,
+  BuildProgressEvent,
   BuildStatus,
+  CacheGcReport,
+  CacheStatsReport,
+  CompoundSplit,
   ContentItem,
   ContentPage,
   DictionaryIndexEntry,
+  DictionaryInfo,
   DictionaryLinkTarget,
   EntryDetail,
+  HeadwordLookupMode,
+  HeadwordSuggestion,
+  RuntimeCacheReport,
   SearchHit
 } from '$lib/types/dictionary';
 
@@ -21,10 +32,59 @@ export function getMasterBuildStatus(zipPath: string | null = null): Promise<Bui
   return invoke<BuildStatus>('get_master_build_status', { zipPath });
 }
 
+export function cancelMasterBuild(zipPath: string | null = null): Promise<boolean> {
+  return invoke<boolean>('cancel_master_build', { zipPath });
+}
+
+export function verifyRuntimeCache(zipPath: string | null = null): Promise<RuntimeCacheReport> {
+  return invoke<RuntimeCacheReport>('verify_runtime_cache', { zipPath });
+}
+
+export type BuildEventHandlers = {
+  onProgress: (event: BuildProgressEvent) => void;
+  onDone: (event: BuildDoneEvent) => void;
+  onError: (event: BuildErrorEvent) => void;
+};
+
+export async function listenBuildEvents(handlers: BuildEventHandlers): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all([
+    listen<BuildProgressEvent>('build://progress', (event) => handlers.onProgress(event.payload)),
+    listen<BuildDoneEvent>('build://done', (event) => handlers.onDone(event.payload)),
+    listen<BuildErrorEvent>('build://error', (event) => handlers.onError(event.payload))
+  ]);
+  return () => {
+    for (const unlisten of unlisteners) unlisten();
+  };
+}
+
 export function getMasterContents(zipPath: string | null = null): Promise<ContentItem[]> {
   return invoke<ContentItem[]>('get_master_contents', { zipPath });
 }
 
+export function listDictionaries(): Promise<DictionaryInfo[]> {
+  return invoke<DictionaryInfo[]>('list_dictionaries');
+}
+
+export function setDictionaryEnabled(id: string, enabled: boolean): Promise<DictionaryInfo> {
+  return invoke<DictionaryInfo>('set_dictionary_enabled', { id, enabled });
+}
+
+export function renameDictionary(id: string, name: string): Promise<DictionaryInfo> {
+  return invoke<DictionaryInfo>('rename_dictionary', { id, name });
+}
+
+export function setDictionaryPinned(id: string, pinned: boolean): Promise<DictionaryInfo> {
+  return invoke<DictionaryInfo>('set_dictionary_pinned', { id, pinned });
+}
+
+export function deleteDictionary(id: string): Promise<CacheGcReport> {
+  return invoke<CacheGcReport>('delete_dictionary', { id });
+}
+
+export function gcRuntimeCache(): Promise<CacheGcReport> {
+  return invoke<CacheGcReport>('gc_runtime_cache');
+}
+
 export function getIndexEntries(
   zipPath: string | null,
   prefix: string,
@@ -33,6 +93,28 @@ export function getIndexEntries(
   return invoke<DictionaryIndexEntry[]>('get_index_entries', { prefix, limit, zipPath });
 }
 
+export function lookupHeadwords(
+  zipPath: string | null,
+  query: string,
+  mode: HeadwordLookupMode = 'prefix',
+  maxDistance: number | null = null,
+  limit: number | null = null
+): Promise<DictionaryIndexEntry[]> {
+  return invoke<DictionaryIndexEntry[]>('lookup_headwords', { query, mode, maxDistance, limit, zipPath });
+}
+
+export function suggestHeadwords(
+  zipPath: string | null,
+  query: string,
+  limit = 5
+): Promise<HeadwordSuggestion[]> {
+  return invoke<HeadwordSuggestion[]>('suggest_headwords', { query, limit, zipPath });
+}
+
+export function splitCompound(zipPath: string | null, query: string): Promise<CompoundSplit[]> {
+  return invoke<CompoundSplit[]>('split_compound', { query, zipPath });
+}
+
 export function searchEntries(
   zipPath: string | null,
   query: string,
@@ -41,6 +123,14 @@ export function searchEntries(
   return invoke<SearchHit[]>('search_entries', { query, limit, zipPath });
 }
 
+export function searchReverse(
+  zipPath: string | null,
+  query: string,
+  limit = 200
+): Promise<SearchHit[]> {
+  return invoke<SearchHit[]>('search_reverse', { query, limit, zipPath });
+}
+
 export function getEntryDetail(zipPath: string | null, id: number): Promise<EntryDetail> {
   return invoke<EntryDetail>('get_entry_detail', { id, zipPath });
 }
@@ -80,3 +170,7 @@ export function resolveMediaDataUrl(
     zipPath
   });
 }
+
+export function getCacheStats(): Promise<CacheStatsReport> {
+  return invoke<CacheStatsReport>('get_cache_stats');
+}
@@ -56,6 +56,7 @@ function isRecentViewItem(value: unknown): value is RecentViewItem {
     && (value.id === null || typeof value.id === "number")
     && (value.local === null || typeof value.local === "string")
     && (value.sourcePath === null || typeof value.sourcePath === "string")
+    && (value.dictionaryId == null || typeof value.dictionaryId === "string")
     && typeof value.viewedAt === "number"
   );
 }
@@ -69,6 +70,7 @@ function isFavoriteItem(value: unknown): value is FavoriteItem {
     && (value.id === null || typeof value.id === "number")
     && (value.local === null || typeof value.local === "string")
     && (value.sourcePath === null || typeof value.sourcePath === "string")
+    && (value.dictionaryId == null || typeof value.dictionaryId === "string")
     && (typeof value.folderId === "string" || value.folderId === undefined)
   );
 }
@@ -82,6 +84,15 @@ function isBookmarkFolder(value: unknown): value is BookmarkFolder {
   );
 }
 
+// Ids and pages are only unique within one dictionary, so item keys carry its id.
+export function entryItemKey(id: number, dictionaryId: string | null): string {
+  return `entry:${dictionaryId ?? ''}:${id}`;
+}
+
+export function contentItemKey(local: string, sourcePath: string | null, dictionaryId: string | null): string {
+  return `content:${dictionaryId ?? ''}:${sourcePath ?? ''}:${local}`;
+}
+
 export function dedupeRecentViews(rows: RecentViewItem[]): RecentViewItem[] {
   const seen = new Set<string>();
   const out: RecentViewItem[] = [];
@@ -101,7 +112,11 @@ function sanitizeRecentSearches(value: unknown): string[] {
 
 function sanitizeRecentViews(value: unknown): RecentViewItem[] {
   if (!Array.isArray(value)) return [];
-  return dedupeRecentViews(value.filter(isRecentViewItem));
+  return dedupeRecentViews(
+    value
+      .filter(isRecentViewItem)
+      .map((item) => ({ ...item, dictionaryId: item.dictionaryId ?? null }))
+  );
 }
 
 function sanitizeFavorites(value: unknown): FavoriteItem[] {
@@ -111,6 +126,7 @@ function sanitizeFavorites(value: unknown): FavoriteItem[] {
     .slice(0, MAX_FAVORITES)
     .map((item) => ({
       ...item,
+      dictionaryId: item.dictionaryId ?? null,
       folderId: item.folderId ?? DEFAULT_BOOKMARK_FOLDER_ID,
     }));
 }
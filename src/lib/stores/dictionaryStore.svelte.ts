@@ -1,17 +1,31 @@
 import { open as openDialog } from '@tauri-apps/plugin-dialog';
 import {
+  cancelMasterBuild,
+  deleteDictionary as deleteDictionaryApi,
+  gcRuntimeCache,
   getContentPage,
   getEntryDetail,
   getIndexEntries,
   getMasterBuildStatus,
   getMasterContents,
+  listDictionaries,
+  listenBuildEvents,
   prepareZipSource,
+  renameDictionary as renameDictionaryApi,
   resolveLinkTarget,
   resolveMediaDataUrl,
   searchEntries,
-  startMasterBuild
+  searchReverse,
+  splitCompound,
+  suggestHeadwords,
+  setDictionaryEnabled as setDictionaryEnabledApi,
+  setDictionaryPinned as setDictionaryPinnedApi,
+  startMasterBuild,
+  verifyRuntimeCache
 } from '$lib/api/dictionary';
 import {
+  contentItemKey,
+  entryItemKey,
   loadDictionaryPrefs,
   saveDictionaryPrefs
 } from '$lib/stores/dictionaryPrefsStore';
@@ -22,26 +36,50 @@ import { createDetailState, type DetailState } from '$lib/stores/detailState.sve
 import type {
   BookmarkFolder,
   BuildProgress,
+  BuildStatus,
+  CompoundSplit,
   ContentItem,
   ContentPage,
   DictionaryIndexEntry,
+  DictionaryInfo,
   DetailMode,
+  DokhanError,
+  DokhanErrorCode,
   EntryDetail,
   FavoriteItem,
+  HeadwordSuggestion,
   MasterFeatureSummary,
   ReaderFontSize,
   ReaderLineHeight,
   ReaderWidth,
   RecentViewItem,
+  RuntimeCacheReport,
   SearchHit,
   Tab
 } from '$lib/types/dictionary';
 
-const BUILD_POLL_MS = 80;
+const BUILD_STATUS_FALLBACK_MS = 1000;
 const INDEX_DEBOUNCE_MS = 120;
+const HANGUL_QUERY = /^[\s\u3131-\u318e\uac00-\ud7a3]+$/;
+
+function isDokhanError(errorValue: unknown): errorValue is DokhanError {
+  return (
+    typeof errorValue === 'object' &&
+    errorValue !== null &&
+    typeof (errorValue as DokhanError).code === 'string' &&
+    typeof (errorValue as DokhanError).message === 'string'
+  );
+}
 
 function toErrorMessage(errorValue: unknown): string {
-  return typeof errorValue === 'string' ? errorValue : String(errorValue);
+  if (typeof errorValue === 'string') return errorValue;
+  if (isDokhanError(errorValue)) return errorValue.message;
+  if (errorValue instanceof Error) return errorValue.message;
+  return String(errorValue);
+}
+
+function toErrorCode(errorValue: unknown): DokhanErrorCode | null {
+  return isDokhanError(errorValue) ? errorValue.code : null;
 }
 
 export interface DictionaryStore {
@@ -51,6 +89,7 @@ export interface DictionaryStore {
   readonly isOpeningDetail: boolean;
   readonly autoOpenFirstContent: boolean;
   readonly error: string;
+  readonly errorCode: DokhanErrorCode | null;
   readonly zipPath: string | null;
   readonly activeTab: Tab;
   readonly mobileTab: 'home' | 'search' | 'index' | 'favorites';
@@ -76,11 +115,15 @@ export interface DictionaryStore {
   readonly searchQuery: string;
   readonly committedSearchQuery: string;
   readonly searchRows: SearchHit[];
+  readonly searchSuggestions: HeadwordSuggestion[];
+  readonly compoundSplits: CompoundSplit[];
   readonly selectedContent: ContentPage | null;
   readonly selectedEntry: EntryDetail | null;
   readonly detailMode: DetailMode;
   readonly selectedContentLocal: string;
   readonly selectedEntryId: number | null;
+  readonly selectedDictionaryId: string | null;
+  readonly dictionaries: DictionaryInfo[];
 
   dispose(): void;
   retryLastOperation(): Promise<void>;
@@ -99,17 +142,23 @@ export interface DictionaryStore {
   bootFromManagedCache(): Promise<void>;
   useZipPath(path: string): Promise<void>;
   pickZipFile(): Promise<void>;
+  cancelBuild(): Promise<void>;
   openContent(local: string, sourcePath?: string | null): Promise<void>;
-  openEntry(id: number): Promise<void>;
+  openEntry(id: number, dictionaryId?: string | null): Promise<void>;
+  setDictionaryEnabled(id: string, enabled: boolean): Promise<void>;
+  renameDictionary(id: string, name: string): Promise<void>;
+  setDictionaryPinned(id: string, pinned: boolean): Promise<void>;
+  deleteDictionary(id: string): Promise<void>;
+  verifyDictionaryCache(id: string): Promise<RuntimeCacheReport | null>;
   setIndexPrefix(value: string): void;
   setSearchQuery(value: string): void;
   submitSearch(): Promise<void>;
   useRecentSearch(query: string): void;
   openRecentView(item: RecentViewItem): void;
-  isFavoriteEntry(id: number): boolean;
-  isFavoriteContent(local: string, sourcePath: string | null): boolean;
-  toggleFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>): void;
-  toggleFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>): void;
+  isFavoriteEntry(id: number, dictionaryId?: string | null): boolean;
+  isFavoriteContent(local: string, sourcePath: string | null, dictionaryId?: string | null): boolean;
+  toggleFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>, dictionaryId?: string | null): void;
+  toggleFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>, dictionaryId?: string | null): void;
   toggleCurrentFavorite(): void;
   isCurrentFavorite(): boolean;
   removeFavorite(key: string): void;
@@ -131,7 +180,14 @@ export function createDictionaryStore(): DictionaryStore {
   let isOpeningDetail = $state(false);
   let autoOpenFirstContent = $state(true);
   let error = $state('');
+  let errorCode = $state<DokhanErrorCode | null>(null);
+  // Source of the build currently awaited; `undefined` when idle (`null` means the managed cache).
+  let buildingZipPath: string | null | undefined = undefined;
   let zipPath = $state<string | null>(null);
+  let dictionaries = $state<DictionaryInfo[]>([]);
+  // Dictionary the open entry/page came from; federated results may point outside `zipPath`.
+  let detailZipPath = $state<string | null>(null);
+  let selectedDictionaryId = $state<string | null>(null);
   let activeTab = $state<Tab>('content');
   let mobileTab = $state<'home' | 'search' | 'index' | 'favorites'>('home');
 
@@ -150,6 +206,7 @@ export function createDictionaryStore(): DictionaryStore {
   let searchBusyCount = 0;
   let detailBusyCount = 0;
   let lastRetryAction: (() => Promise<void>) | null = null;
+  let cacheGcDone = false;
 
   const libraryState: LibraryState = createLibraryState(() => persistPrefs());
   const readerPrefsState: ReaderPrefsState = createReaderPrefsState(() => persistPrefs());
@@ -206,15 +263,64 @@ export function createDictionaryStore(): DictionaryStore {
 
   function clearSelection() {
     detailState.clearSelection();
+    detailZipPath = null;
+    selectedDictionaryId = null;
+  }
+
+  function dictionaryById(dictionaryId: string | null | undefined): DictionaryInfo | null {
+    if (!dictionaryId) return null;
+    return dictionaries.find((dictionary) => dictionary.id === dictionaryId) ?? null;
+  }
+
+  function dictionaryAt(path: string | null): DictionaryInfo | null {
+    if (!path) return null;
+    return dictionaries.find((dictionary) => dictionary.zipPath === path) ?? null;
+  }
+
+  // ZIP a recent or favorite item was opened from; items saved before dictionary ids
+  // were recorded reopen against the active dictionary.
+  function itemZipPath(dictionaryId: string | null): string | null {
+    return dictionaryById(dictionaryId)?.zipPath ?? zipPath;
+  }
+
+  function detailDictionaryId(): string | null {
+    return dictionaryAt(detailZipPath)?.id ?? null;
+  }
+
+  // Search and index target the single enabled dictionary, or span the enabled set
+  // (possibly empty) otherwise; the open ZIP is used only while the list is unknown.
+  function queryZipPath(): string | null {
+    if (dictionaries.length === 0) return zipPath;
+    const enabled = dictionaries.filter((dictionary) => dictionary.enabled);
+    return enabled.length === 1 ? enabled[0].zipPath : null;
+  }
+
+  async function refreshDictionaries() {
+    try {
+      dictionaries = await listDictionaries();
+    } catch {
+      // Keep the last known list; single-dictionary queries still work.
+    }
+  }
+
+  function setError(errorValue: unknown, prefix?: string) {
+    const message = toErrorMessage(errorValue);
+    error = prefix ? `${prefix}: ${message}` : message;
+    errorCode = toErrorCode(errorValue);
+  }
+
+  function clearError() {
+    error = '';
+    errorCode = null;
   }
 
   async function withBusy<T>(kind: 'search' | 'detail', task: () => Promise<T>): Promise<T | undefined> {
     beginBusy(kind);
-    error = '';
+    clearError();
     try {
       return await task();
     } catch (e) {
-      error = toErrorMessage(e);
+      setError(e);
       return undefined;
     } finally {
       endBusy(kind);
@@ -222,7 +328,7 @@ export function createDictionaryStore(): DictionaryStore {
   }
 
   function beginSourcePrepare() {
-    error = '';
+    clearError();
     showProgress = true;
     progress = {
       phase: 'source-prepare',
@@ -260,12 +366,12 @@ export function createDictionaryStore(): DictionaryStore {
     const requestId = ++indexRequestSeq;
     searchIndexState.setIndexLoading(true);
     try {
-      const rows = await getIndexEntries(zipPath, trimmed, trimmed ? 500 : null);
+      const rows = await getIndexEntries(queryZipPath(), trimmed, trimmed ? 500 : null);
       if (requestId === indexRequestSeq && searchIndexState.indexPrefix.trim() === trimmed) {
         searchIndexState.setIndexRows(rows);
       }
     } catch (e) {
-      error = toErrorMessage(e);
+      setError(e);
     } finally {
       if (requestId === indexRequestSeq) searchIndexState.setIndexLoading(false);
     }
@@ -282,17 +388,120 @@ export function createDictionaryStore(): DictionaryStore {
     setRetryAction(async () => {
       searchIndexState.setSearchQuery(searchTerm);
       searchIndexState.setCommittedSearchQuery(searchTerm);
-      const retryRows = await withBusy('search', () => searchEntries(zipPath, searchTerm, 200));
+      const retryRows = await withBusy('search', () => fetchSearchRows(searchTerm));
       if (retryRows && requestId === searchRequestSeq) {
         searchIndexState.setSearchRows(retryRows);
       }
     });
-    const rows = await withBusy('search', () => searchEntries(zipPath, searchTerm, 200));
+    const rows = await withBusy('search', () => fetchSearchRows(searchTerm));
     if (rows && requestId === searchRequestSeq) {
       searchIndexState.setSearchRows(rows);
+      searchIndexState.setSearchSuggestions([]);
+      searchIndexState.setCompoundSplits([]);
       if (recordRecent && rows.length > 0) {
         pushRecentSearch(searchTerm);
       }
+      if (rows.length === 0) {
+        await loadSearchSuggestions(searchTerm, requestId);
+      }
+      await loadCompoundSplits(searchTerm, requestId);
+    }
+  }
+
+  async function loadCompoundSplits(searchTerm: string, requestId: number) {
+    try {
+      // Empty unless the query is a compound that is not itself a headword.
+      const splits = await splitCompound(queryZipPath(), searchTerm);
+      if (requestId === searchRequestSeq) {
+        searchIndexState.setCompoundSplits(splits);
+      }
+    } catch {
+      // Like suggestions, the split is a hint only.
+    }
+  }
+
+  // Korean queries look up German headwords by gloss first, then fall back to body search.
+  async function fetchSearchRows(searchTerm: string): Promise<SearchHit[]> {
+    if (HANGUL_QUERY.test(searchTerm)) {
+      const reverseRows = await searchReverse(queryZipPath(), searchTerm, 200);
+      if (reverseRows.length > 0) {
+        return reverseRows;
+      }
+    }
+    return searchEntries(queryZipPath(), searchTerm, 200);
+  }
+
+  async function loadSearchSuggestions(searchTerm: string, requestId: number) {
+    try {
+      const suggestions = await suggestHeadwords(queryZipPath(), searchTerm, 5);
+      if (requestId === searchRequestSeq) {
+        searchIndexState.setSearchSuggestions(suggestions);
+      }
+    } catch {
+      // Suggestions are a hint; a failure leaves the plain empty state.
+    }
+  }
+
+  async function waitForBuild(nextZipPath: string | null): Promise<MasterFeatureSummary | null> {
+    // Events drive progress; the status snapshot only covers events missed before subscribing.
+    let buildKey: string | null = null;
+    let lastSeq = 0;
+    const early: Array<{ key: string; seq: number; apply: () => void }> = [];
+    let resolveBuild!: (summary: MasterFeatureSummary | null) => void;
+    let rejectBuild!: (reason: unknown) => void;
+    const finished = new Promise<MasterFeatureSummary | null>((resolve, reject) => {
+      resolveBuild = resolve;
+      rejectBuild = reject;
+    });
+    void finished.catch(() => undefined);
+
+    const accept = (key: string, seq: number, apply: () => void) => {
+      if (buildKey === null) {
+        early.push({ key, seq, apply });
+        return;
+      }
+      if (key !== buildKey || seq <= lastSeq) return;
+      lastSeq = seq;
+      apply();
+    };
+    const applyStatus = (status: BuildStatus) => {
+      if (status.seq <= lastSeq) return;
+      lastSeq = status.seq;
+      progress = {
+        phase: status.phase,
+        current: status.current,
+        total: status.total,
+        message: status.message
+      };
+      if (!status.done) return;
+      if (status.success) {
+        resolveBuild(status.summary);
+      } else {
+        rejectBuild(status.error ?? new Error('빌드 실패'));
+      }
+    };
+
+    const unlisten = await listenBuildEvents({
+      onProgress: (event) =>
+        accept(event.key, event.seq, () => {
+          progress = event.progress;
+        }),
+      onDone: (event) => accept(event.key, event.seq, () => resolveBuild(event.summary)),
+      onError: (event) =>
+        accept(event.key, event.seq, () => rejectBuild(event.error ?? new Error(event.message)))
+    });
+    const fallback = setInterval(() => {
+      getMasterBuildStatus(nextZipPath).then(applyStatus, rejectBuild);
+    }, BUILD_STATUS_FALLBACK_MS);
+    try {
+      buildKey = await startMasterBuild(nextZipPath);
+      early.sort((a, b) => a.seq - b.seq);
+      for (const event of early.splice(0)) accept(event.key, event.seq, event.apply);
+      applyStatus(await getMasterBuildStatus(nextZipPath));
+      return await finished;
+    } finally {
+      clearInterval(fallback);
+      unlisten();
     }
   }
 
@@ -300,35 +509,29 @@ export function createDictionaryStore(): DictionaryStore {
     setRetryAction(async () => {
       await bootMasterFeaturesWithPath(nextZipPath, false);
     });
+    if (buildingZipPath !== undefined && buildingZipPath !== nextZipPath) {
+      // Stop the superseded build so it does not keep parsing in the background.
+      void cancelMasterBuild(buildingZipPath).catch(() => undefined);
+    }
+    buildingZipPath = nextZipPath;
     beginBusy('boot');
-    error = '';
+    clearError();
     showProgress = true;
     progress = { phase: 'start', current: 0, total: 1, message: '초기화 중' };
     try {
-      await startMasterBuild(nextZipPath);
-      while (true) {
-        const status = await getMasterBuildStatus(nextZipPath);
-        progress = {
-          phase: status.phase,
-          current: status.current,
-          total: status.total,
-          message: status.message
-        };
-
-        if (status.done) {
-          if (!status.success) {
-            throw new Error(status.error ?? '빌드 실패');
-          }
-          masterSummary = status.summary;
-          zipPath = status.summary?.zipPath ?? nextZipPath;
-          break;
-        }
-        await new Promise((resolve) => setTimeout(resolve, BUILD_POLL_MS));
+      const summary = await waitForBuild(nextZipPath);
+      masterSummary = summary;
+      zipPath = summary?.zipPath ?? nextZipPath;
+      if (!cacheGcDone) {
+        // Drop caches of ZIPs removed outside the app; once per session is enough.
+        cacheGcDone = true;
+        void gcRuntimeCache().catch(() => undefined);
       }
+      await refreshDictionaries();
 
       const [nextContents, nextIndex] = await Promise.all([
         getMasterContents(zipPath),
-        getIndexEntries(zipPath, '', null)
+        getIndexEntries(queryZipPath(), '', null)
       ]);
 
       contents = nextContents;
@@ -340,18 +543,28 @@ export function createDictionaryStore(): DictionaryStore {
         await openContent(contents[0].local);
       }
     } catch (e) {
-      const message = toErrorMessage(e);
-      if (silentNoCache && message.includes('no managed zip cache found')) {
-        error = '';
+      const code = toErrorCode(e);
+      if (code === 'cancelled' || (silentNoCache && code === 'zip_path_required')) {
+        clearError();
       } else {
-        error = message;
+        setError(e);
       }
     } finally {
-      showProgress = false;
+      if (buildingZipPath === nextZipPath) buildingZipPath = undefined;
+      if (bootBusyCount <= 1) showProgress = false;
       endBusy('boot');
     }
   }
 
+  async function cancelBuild() {
+    if (buildingZipPath === undefined) return;
+    try {
+      await cancelMasterBuild(buildingZipPath);
+    } catch (e) {
+      setError(e);
+    }
+  }
+
   async function retryLastOperation() {
     if (lastRetryAction) {
       await lastRetryAction();
@@ -434,7 +647,7 @@ export function createDictionaryStore(): DictionaryStore {
   async function useZipPath(path: string) {
     const nextPath = path.trim();
     if (!nextPath) {
-      error = 'ZIP 경로가 비어 있습니다.';
+      setError('ZIP 경로가 비어 있습니다.');
       return;
     }
     zipPath = nextPath;
@@ -458,51 +671,140 @@ export function createDictionaryStore(): DictionaryStore {
       const resolvedPath = await resolvePickedZipPath(selected);
       await useZipPath(resolvedPath);
     } catch (e) {
-      error = `파일 선택 실패: ${toErrorMessage(e)}`;
+      setError(e, '파일 선택 실패');
     } finally {
       endSourcePrepare();
     }
   }
 
-  async function openContent(local: string, sourcePath: string | null = null) {
+  async function openContent(
+    local: string,
+    sourcePath: string | null = null,
+    pageZipPath: string | null = zipPath
+  ) {
     setRetryAction(async () => {
-      await openContent(local, sourcePath);
+      await openContent(local, sourcePath, pageZipPath);
     });
     const requestId = ++detailRequestSeq;
-    const page = await withBusy('detail', () => getContentPage(zipPath, local, sourcePath));
+    const page = await withBusy('detail', () => getContentPage(pageZipPath, local, sourcePath));
     if (!page || requestId !== detailRequestSeq) return;
     detailState.setContent(page, local);
+    detailZipPath = pageZipPath;
+    selectedDictionaryId = null;
+    const pageDictionaryId = dictionaryAt(pageZipPath)?.id ?? null;
     pushRecentView({
-      key: `content:${page.sourcePath}:${local}`,
+      key: contentItemKey(local, page.sourcePath, pageDictionaryId),
       kind: 'content',
       label: page.title,
       id: null,
       local,
       sourcePath: page.sourcePath,
+      dictionaryId: pageDictionaryId,
       viewedAt: Date.now()
     });
   }
 
-  async function openEntry(id: number) {
+  async function openEntry(id: number, dictionaryId: string | null = null) {
     setRetryAction(async () => {
-      await openEntry(id);
+      await openEntry(id, dictionaryId);
     });
+    const entryZipPath = dictionaryById(dictionaryId)?.zipPath ?? zipPath;
+    const entryDictionaryId = dictionaryAt(entryZipPath)?.id ?? null;
     const requestId = ++detailRequestSeq;
     detailState.beginEntrySelection(id);
-    const entry = await withBusy('detail', () => getEntryDetail(zipPath, id));
+    selectedDictionaryId = entryDictionaryId;
+    const entry = await withBusy('detail', () => getEntryDetail(entryZipPath, id));
     if (!entry || requestId !== detailRequestSeq) return;
     detailState.setEntry(entry, id);
+    detailZipPath = entryZipPath;
     pushRecentView({
-      key: `entry:${id}`,
+      key: entryItemKey(id, entryDictionaryId),
       kind: 'entry',
       label: entry.headword,
       id,
       local: null,
       sourcePath: entry.sourcePath,
+      dictionaryId: entryDictionaryId,
       viewedAt: Date.now()
     });
   }
 
+  async function reloadQueries() {
+    if (!masterSummary) return;
+    await loadIndexByPrefix(searchIndexState.indexPrefix);
+    if (searchIndexState.committedSearchQuery) {
+      await runSearch(searchIndexState.committedSearchQuery, false);
+    }
+  }
+
+  async function setDictionaryEnabled(id: string, enabled: boolean) {
+    try {
+      await setDictionaryEnabledApi(id, enabled);
+    } catch (e) {
+      setError(e);
+      return;
+    }
+    await refreshDictionaries();
+    await reloadQueries();
+  }
+
+  async function renameDictionary(id: string, name: string) {
+    try {
+      await renameDictionaryApi(id, name);
+    } catch (e) {
+      setError(e);
+      return;
+    }
+    await refreshDictionaries();
+    await reloadQueries();
+  }
+
+  async function setDictionaryPinned(id: string, pinned: boolean) {
+    try {
+      await setDictionaryPinnedApi(id, pinned);
+    } catch (e) {
+      setError(e);
+      return;
+    }
+    await refreshDictionaries();
+  }
+
+  async function deleteDictionary(id: string) {
+    const removed = dictionaryById(id);
+    try {
+      await deleteDictionaryApi(id);
+    } catch (e) {
+      setError(e);
+      return;
+    }
+    await refreshDictionaries();
+    if (removed && (removed.zipPath === zipPath || removed.zipPath === detailZipPath)) {
+      clearSelection();
+    }
+    if (removed && removed.zipPath === zipPath) {
+      // The open dictionary is gone; fall back to the newest remaining one.
+      zipPath = null;
+      masterSummary = null;
+      contents = [];
+      searchIndexState.setIndexRows([]);
+      searchIndexState.setSearchRows([]);
+      await bootFromManagedCache();
+      return;
+    }
+    await reloadQueries();
+  }
+
+  async function verifyDictionaryCache(id: string): Promise<RuntimeCacheReport | null> {
+    const dictionary = dictionaryById(id);
+    if (!dictionary) return null;
+    try {
+      return await verifyRuntimeCache(dictionary.zipPath);
+    } catch (e) {
+      setError(e);
+      return null;
+    }
+  }
+
   function setIndexPrefix(value: string) {
     searchIndexState.setIndexPrefix(value);
     if (indexDebounceTimer) clearTimeout(indexDebounceTimer);
@@ -529,28 +831,38 @@ export function createDictionaryStore(): DictionaryStore {
 
   function openRecentView(item: RecentViewItem) {
     if (item.kind === 'entry' && item.id != null) {
-      void openEntry(item.id);
+      void openEntry(item.id, item.dictionaryId);
       return;
     }
     if (item.kind === 'content' && item.local) {
-      void openContent(item.local, item.sourcePath);
+      void openContent(item.local, item.sourcePath, itemZipPath(item.dictionaryId));
     }
   }
 
-  function isFavoriteEntry(id: number): boolean {
-    return libraryState.isFavoriteEntry(id);
+  function isFavoriteEntry(id: number, dictionaryId: string | null = detailDictionaryId()): boolean {
+    return libraryState.isFavoriteEntry(id, dictionaryId);
   }
 
-  function isFavoriteContent(local: string, sourcePath: string | null): boolean {
-    return libraryState.isFavoriteContent(local, sourcePath);
+  function isFavoriteContent(
+    local: string,
+    sourcePath: string | null,
+    dictionaryId: string | null = detailDictionaryId()
+  ): boolean {
+    return libraryState.isFavoriteContent(local, sourcePath, dictionaryId);
   }
 
-  function toggleFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>) {
-    libraryState.toggleFavoriteEntry(entry);
+  function toggleFavoriteEntry(
+    entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>,
+    dictionaryId: string | null = detailDictionaryId()
+  ) {
+    libraryState.toggleFavoriteEntry(entry, dictionaryId);
   }
 
-  function toggleFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>) {
-    libraryState.toggleFavoriteContent(content);
+  function toggleFavoriteContent(
+    content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>,
+    dictionaryId: string | null = detailDictionaryId()
+  ) {
+    libraryState.toggleFavoriteContent(content, dictionaryId);
   }
 
   function toggleCurrentFavorite() {
@@ -600,21 +912,21 @@ export function createDictionaryStore(): DictionaryStore {
   function addCurrentFavoriteToFolder(folderId: string) {
     if (!libraryState.bookmarkFolders.some((folder) => folder.id === folderId)) return;
     if (detailState.detailMode === 'entry' && detailState.selectedEntry) {
-      libraryState.addFavoriteEntry(detailState.selectedEntry, folderId);
+      libraryState.addFavoriteEntry(detailState.selectedEntry, detailDictionaryId(), folderId);
       return;
     }
     if (detailState.detailMode === 'content' && detailState.selectedContent) {
-      libraryState.addFavoriteContent(detailState.selectedContent, folderId);
+      libraryState.addFavoriteContent(detailState.selectedContent, detailDictionaryId(), folderId);
     }
   }
 
   function openFavorite(item: FavoriteItem) {
     if (item.kind === 'entry' && item.id != null) {
-      void openEntry(item.id);
+      void openEntry(item.id, item.dictionaryId);
       return;
     }
     if (item.kind === 'content' && item.local) {
-      void openContent(item.local, item.sourcePath);
+      void openContent(item.local, item.sourcePath, itemZipPath(item.dictionaryId));
     }
   }
 
@@ -626,15 +938,16 @@ export function createDictionaryStore(): DictionaryStore {
     setRetryAction(async () => {
       await openInlineHref(href, currentSourcePath, currentLocal);
     });
+    const linkZipPath = detailZipPath ?? zipPath;
     const target = await withBusy('detail', () =>
-      resolveLinkTarget(zipPath, href, currentSourcePath, currentLocal)
+      resolveLinkTarget(linkZipPath, href, currentSourcePath, currentLocal)
     );
     if (!target) return;
     if (target.kind === 'content') {
-      await openContent(target.local, target.sourcePath);
+      await openContent(target.local, target.sourcePath, linkZipPath);
       return;
     }
-    await openEntry(target.id);
+    await openEntry(target.id, dictionaryAt(linkZipPath)?.id ?? null);
   }
 
   async function resolveInlineImageHref(
@@ -643,7 +956,7 @@ export function createDictionaryStore(): DictionaryStore {
     currentLocal: string | null
   ): Promise<string | null> {
     try {
-      return await resolveMediaDataUrl(zipPath, href, currentSourcePath, currentLocal);
+      return await resolveMediaDataUrl(detailZipPath ?? zipPath, href, currentSourcePath, currentLocal);
     } catch {
       return null;
     }
@@ -658,6 +971,7 @@ export function createDictionaryStore(): DictionaryStore {
     get isOpeningDetail() { return isOpeningDetail; },
     get autoOpenFirstContent() { return autoOpenFirstContent; },
     get error() { return error; },
+    get errorCode() { return errorCode; },
     get zipPath() { return zipPath; },
     get activeTab() { return activeTab; },
     get mobileTab() { return mobileTab; },
@@ -683,11 +997,15 @@ export function createDictionaryStore(): DictionaryStore {
     get searchQuery() { return searchIndexState.searchQuery; },
     get committedSearchQuery() { return searchIndexState.committedSearchQuery; },
     get searchRows() { return searchIndexState.searchRows; },
+    get searchSuggestions() { return searchIndexState.searchSuggestions; },
+    get compoundSplits() { return searchIndexState.compoundSplits; },
     get selectedContent() { return detailState.selectedContent; },
     get selectedEntry() { return detailState.selectedEntry; },
     get detailMode() { return detailState.detailMode; },
     get selectedContentLocal() { return detailState.selectedContentLocal; },
     get selectedEntryId() { return detailState.selectedEntryId; },
+    get selectedDictionaryId() { return selectedDictionaryId; },
+    get dictionaries() { return dictionaries; },
     dispose,
     retryLastOperation,
     closeDetail,
@@ -705,8 +1023,14 @@ export function createDictionaryStore(): DictionaryStore {
     bootFromManagedCache,
     useZipPath,
     pickZipFile,
+    cancelBuild,
     openContent,
     openEntry,
+    setDictionaryEnabled,
+    renameDictionary,
+    setDictionaryPinned,
+    deleteDictionary,
+    verifyDictionaryCache,
     setIndexPrefix,
     setSearchQuery,
     submitSearch,
@@ -3,7 +3,9 @@ import {
   MAX_BOOKMARK_FOLDERS,
   MAX_FAVORITES,
   MAX_RECENT_SEARCHES,
-  dedupeRecentViews
+  contentItemKey,
+  dedupeRecentViews,
+  entryItemKey
 } from '$lib/stores/dictionaryPrefsStore';
 import type { BookmarkFolder, ContentPage, EntryDetail, FavoriteItem, RecentViewItem } from '$lib/types/dictionary';
 
@@ -90,15 +92,16 @@ export function createLibraryState(onChange: () => void) {
       recentViews = dedupeRecentViews(next);
       onChange();
     },
-    isFavoriteEntry(id: number): boolean {
-      return favorites.some((item) => item.kind === 'entry' && item.id === id);
+    isFavoriteEntry(id: number, dictionaryId: string | null): boolean {
+      const key = entryItemKey(id, dictionaryId);
+      return favorites.some((item) => item.key === key);
     },
-    isFavoriteContent(local: string, sourcePath: string | null): boolean {
-      const key = `content:${sourcePath ?? ''}:${local}`;
+    isFavoriteContent(local: string, sourcePath: string | null, dictionaryId: string | null): boolean {
+      const key = contentItemKey(local, sourcePath, dictionaryId);
       return favorites.some((item) => item.key === key);
     },
-    toggleFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>) {
-      const key = `entry:${entry.id}`;
+    toggleFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>, dictionaryId: string | null) {
+      const key = entryItemKey(entry.id, dictionaryId);
       if (favorites.some((item) => item.key === key)) {
         favorites = favorites.filter((item) => item.key !== key);
         onChange();
@@ -111,13 +114,18 @@ export function createLibraryState(onChange: () => void) {
         id: entry.id,
         local: null,
         sourcePath: entry.sourcePath,
+        dictionaryId,
         folderId: activeBookmarkFolderId
       };
       favorites = [nextItem, ...favorites].slice(0, MAX_FAVORITES);
       onChange();
     },
-    addFavoriteEntry(entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>, folderId: string) {
-      const key = `entry:${entry.id}`;
+    addFavoriteEntry(
+      entry: Pick<EntryDetail, 'id' | 'headword' | 'sourcePath'>,
+      dictionaryId: string | null,
+      folderId: string
+    ) {
+      const key = entryItemKey(entry.id, dictionaryId);
       if (favorites.some((item) => item.key === key)) {
         favorites = favorites.map((item) => (
           item.key === key ? { ...item, folderId } : item
@@ -132,13 +140,14 @@ export function createLibraryState(onChange: () => void) {
         id: entry.id,
         local: null,
         sourcePath: entry.sourcePath,
+        dictionaryId,
         folderId
       };
       favorites = [nextItem, ...favorites].slice(0, MAX_FAVORITES);
       onChange();
     },
-    toggleFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>) {
-      const key = `content:${content.sourcePath ?? ''}:${content.local}`;
+    toggleFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>, dictionaryId: string | null) {
+      const key = contentItemKey(content.local, content.sourcePath, dictionaryId);
       if (favorites.some((item) => item.key === key)) {
         favorites = favorites.filter((item) => item.key !== key);
         onChange();
@@ -151,13 +160,18 @@ export function createLibraryState(onChange: () => void) {
         id: null,
         local: content.local,
         sourcePath: content.sourcePath,
+        dictionaryId,
         folderId: activeBookmarkFolderId
       };
       favorites = [nextItem, ...favorites].slice(0, MAX_FAVORITES);
       onChange();
     },
-    addFavoriteContent(content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>, folderId: string) {
-      const key = `content:${content.sourcePath ?? ''}:${content.local}`;
+    addFavoriteContent(
+      content: Pick<ContentPage, 'local' | 'title' | 'sourcePath'>,
+      dictionaryId: string | null,
+      folderId: string
+    ) {
+      const key = contentItemKey(content.local, content.sourcePath, dictionaryId);
       if (favorites.some((item) => item.key === key)) {
         favorites = favorites.map((item) => (
           item.key === key ? { ...item, folderId } : item
@@ -172,6 +186,7 @@ export function createLibraryState(onChange: () => void) {
         id: null,
         local: content.local,
         sourcePath: content.sourcePath,
+        dictionaryId,
         folderId
       };
       favorites = [nextItem, ...favorites].slice(0, MAX_FAVORITES);
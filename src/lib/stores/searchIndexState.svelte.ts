@@ -1,4 +1,4 @@
-import type { DictionaryIndexEntry, SearchHit } from '$lib/types/dictionary';
+import type { CompoundSplit, DictionaryIndexEntry, HeadwordSuggestion, SearchHit } from '$lib/types/dictionary';
 
 export function createSearchIndexState() {
   let indexPrefix = $state('');
@@ -8,6 +8,8 @@ export function createSearchIndexState() {
   let searchQuery = $state('');
   let committedSearchQuery = $state('');
   let searchRows = $state<SearchHit[]>([]);
+  let searchSuggestions = $state<HeadwordSuggestion[]>([]);
+  let compoundSplits = $state<CompoundSplit[]>([]);
 
   return {
     get indexPrefix() {
@@ -28,6 +30,12 @@ export function createSearchIndexState() {
     get searchRows() {
       return searchRows;
     },
+    get searchSuggestions() {
+      return searchSuggestions;
+    },
+    get compoundSplits() {
+      return compoundSplits;
+    },
     setIndexPrefix(value: string) {
       indexPrefix = value;
     },
@@ -46,8 +54,16 @@ export function createSearchIndexState() {
     setSearchRows(rows: SearchHit[]) {
       searchRows = rows;
     },
+    setSearchSuggestions(rows: HeadwordSuggestion[]) {
+      searchSuggestions = rows;
+    },
+    setCompoundSplits(rows: CompoundSplit[]) {
+      compoundSplits = rows;
+    },
     clearSearch() {
       searchRows = [];
+      searchSuggestions = [];
+      compoundSplits = [];
       committedSearchQuery = '';
     }
   };
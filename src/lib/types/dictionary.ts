@@ -15,6 +15,7 @@ export type ContentPage = {
   title: string;
   bodyText: string;
   bodyHtml: string;
+  encoding: string;
 };
 
 export type DictionaryIndexEntry = {
@@ -23,6 +24,48 @@ export type DictionaryIndexEntry = {
   headwordHighlights: Array<{ start: number; end: number }>;
   aliases: string[];
   sourcePath: string;
+  dictionaryId: string;
+  dictionaryName: string;
+};
+
+export type HeadwordLookupMode = 'prefix' | 'fuzzy' | 'regex';
+
+export type DictionaryBuildState = 'building' | 'ready' | 'cached' | 'error' | 'cancelled' | 'idle';
+
+export type DictionaryInfo = {
+  id: string;
+  name: string;
+  zipPath: string;
+  enabled: boolean;
+  pinned: boolean;
+  addedAt: number;
+  lastUsedAt: number;
+  sizeBytes: number;
+  fingerprint: string;
+  buildState: DictionaryBuildState;
+};
+
+export type CacheGcReport = {
+  removed: string[];
+  freedBytes: number;
+};
+
+export type CacheFileCheck = {
+  name: string;
+  expectedBytes: number;
+  actualBytes: number | null;
+  ok: boolean;
+};
+
+export type RuntimeCacheReport = {
+  cacheKey: string;
+  status: 'ok' | 'missing' | 'outdated' | 'corrupt';
+  version: number | null;
+  parserVersion: number | null;
+  searchSchemaVersion: number | null;
+  searchIndex: 'ok' | 'missing' | 'corrupt';
+  files: CacheFileCheck[];
+  problems: string[];
 };
 
 export type FavoriteItem = {
@@ -32,6 +75,7 @@ export type FavoriteItem = {
   id: number | null;
   local: string | null;
   sourcePath: string | null;
+  dictionaryId: string | null;
   folderId: string;
 };
 
@@ -48,6 +92,7 @@ export type RecentViewItem = {
   id: number | null;
   local: string | null;
   sourcePath: string | null;
+  dictionaryId: string | null;
   viewedAt: number;
 };
 
@@ -57,6 +102,17 @@ export type SearchHit = {
   sourcePath: string;
   score: number;
   snippet: string;
+  dictionaryId: string;
+  dictionaryName: string;
+  matchedVia: 'lemma' | 'gloss' | null;
+};
+
+export type HeadwordSuggestion = {
+  id: number;
+  headword: string;
+  distance: number;
+  dictionaryId: string;
+  dictionaryName: string;
 };
 
 export type EntryDetail = {
@@ -66,6 +122,7 @@ export type EntryDetail = {
   sourcePath: string;
   definitionText: string;
   definitionHtml: string;
+  encoding: string;
 };
 
 export type ReaderFontSize = number;
@@ -76,6 +133,19 @@ export type DictionaryLinkTarget =
   | { kind: 'content'; local: string; sourcePath: string }
   | { kind: 'entry'; id: number };
 
+export type CompoundPart = {
+  text: string;
+  linking: string | null;
+  headword: string;
+  link: DictionaryLinkTarget;
+};
+
+export type CompoundSplit = {
+  dictionaryId: string;
+  dictionaryName: string;
+  parts: CompoundPart[];
+};
+
 export type BuildProgress = {
   phase: string;
   current: number;
@@ -83,6 +153,28 @@ export type BuildProgress = {
   message: string;
 };
 
+export type DokhanErrorCode =
+  | 'invalid_input'
+  | 'zip_path_required'
+  | 'source_unavailable'
+  | 'not_built'
+  | 'not_found'
+  | 'unsupported'
+  | 'lock_poisoned'
+  | 'cancelled'
+  | 'io'
+  | 'zip'
+  | 'chm'
+  | 'search_index'
+  | 'cache'
+  | 'internal';
+
+export type DokhanError = {
+  code: DokhanErrorCode;
+  message: string;
+  details: Record<string, string> | null;
+};
+
 export type BuildStatus = {
   phase: string;
   current: number;
@@ -90,8 +182,43 @@ export type BuildStatus = {
   message: string;
   done: boolean;
   success: boolean;
-  error: string | null;
+  error: DokhanError | null;
   summary: MasterFeatureSummary | null;
+  seq: number;
+};
+
+export type BuildProgressEvent = {
+  key: string;
+  seq: number;
+  progress: BuildProgress;
+};
+
+export type BuildDoneEvent = {
+  key: string;
+  seq: number;
+  message: string;
+  summary: MasterFeatureSummary;
+};
+
+export type BuildErrorEvent = {
+  key: string;
+  seq: number;
+  message: string;
+  error: DokhanError | null;
+};
+
+export type CacheUsage = {
+  hits: number;
+  misses: number;
+  evictions: number;
+  entries: number;
+  bytes: number;
+  budgetBytes: number;
+};
+
+export type CacheStatsReport = {
+  lzxBlocks: CacheUsage;
+  archives: CacheUsage;
 };
 
 export type Tab = 'content' | 'index' | 'search' | 'favorites';